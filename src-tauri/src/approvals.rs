@@ -0,0 +1,222 @@
+//! Per-call user confirmations for agent actions
+//!
+//! Actions that need the user's consent (e.g. opening a URL outside the allow
+//! list) block on `request`, which opens a small approval window and emits an
+//! `approval-request` event. The window answers through `respond_approval`.
+//! Closing the window or letting the request time out counts as a denial.
+//! Requests made with `ask` may also be answered with "always allow".
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::audit;
+
+/// How long to wait for the user before denying
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Window label prefix of approval windows
+pub const APPROVAL_WINDOW_PREFIX: &str = "approval-";
+
+/// Approval window size (matches APPROVAL_WINDOW_* in the frontend constants)
+const APPROVAL_WIDTH: f64 = 300.0;
+const APPROVAL_HEIGHT: f64 = 180.0;
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Pending approvals by id, with the channel the answer goes to
+static PENDING: Mutex<BTreeMap<String, (ApprovalRequest, mpsc::Sender<ApprovalDecision>)>> =
+    Mutex::new(BTreeMap::new());
+
+/// Payload of the `approval-request` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    pub id: String,
+    /// Short summary, e.g. "Open link?"
+    pub title: String,
+    /// What exactly will happen (URL, path, command)
+    pub detail: String,
+    /// Offer an "always allow" answer
+    pub allow_always: bool,
+}
+
+/// The user's answer to an approval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalDecision {
+    Deny,
+    Allow,
+    AlwaysAllow,
+}
+
+impl ApprovalDecision {
+    pub fn is_allowed(self) -> bool {
+        self != ApprovalDecision::Deny
+    }
+}
+
+/// Ask the user to approve an action, blocking until they answer or time out
+/// Must not be called on the main thread.
+pub fn request(app: &tauri::AppHandle, title: &str, detail: &str) -> bool {
+    ask(app, title, detail, false).is_allowed()
+}
+
+/// Like `request`, optionally offering "always allow"
+pub fn ask(app: &tauri::AppHandle, title: &str, detail: &str, allow_always: bool) -> ApprovalDecision {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed).to_string();
+    let approval = ApprovalRequest {
+        id: id.clone(),
+        title: title.to_string(),
+        detail: detail.to_string(),
+        allow_always,
+    };
+    let (tx, rx) = mpsc::channel();
+    PENDING
+        .lock()
+        .unwrap()
+        .insert(id.clone(), (approval.clone(), tx));
+
+    let label = format!("{}{}", APPROVAL_WINDOW_PREFIX, id);
+    if let Err(e) = open_window(app, &label, &id) {
+        log_line!("[Rust] Failed to open approval window: {}", e);
+        PENDING.lock().unwrap().remove(&id);
+        return ApprovalDecision::Deny;
+    }
+    let _ = app.emit("approval-request", &approval);
+
+    let decision = rx
+        .recv_timeout(APPROVAL_TIMEOUT)
+        .unwrap_or(ApprovalDecision::Deny);
+    PENDING.lock().unwrap().remove(&id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.close();
+    }
+    log_line!("[Rust] Approval {}: {:?}", id, decision);
+    log_content!("[Rust] Approval {} detail: {}", id, detail);
+    audit::record_approval(title, detail, &format!("{:?}", decision));
+    decision
+}
+
+fn open_window(app: &tauri::AppHandle, label: &str, id: &str) -> Result<(), String> {
+    // Center above the main mascot when it is visible
+    let position = app.get_webview_window("main").and_then(|main| {
+        let scale = main.scale_factor().ok()?;
+        let position = main.outer_position().ok()?;
+        let size = main.outer_size().ok()?;
+        let x = (position.x as f64 + size.width as f64 / 2.0) / scale - APPROVAL_WIDTH / 2.0;
+        let y = position.y as f64 / scale - APPROVAL_HEIGHT;
+        Some((x.max(0.0), y.max(0.0)))
+    });
+
+    let url = format!("index.html?approval={}", id);
+    let mut builder = WebviewWindowBuilder::new(app, label, WebviewUrl::App(url.into()))
+        .title("Supiki")
+        .inner_size(APPROVAL_WIDTH, APPROVAL_HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .shadow(false)
+        .focused(true);
+    builder = match position {
+        Some((x, y)) => builder.position(x, y),
+        None => builder.center(),
+    };
+    builder.build().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Look up a pending approval (for the approval window)
+pub fn get(id: &str) -> Option<ApprovalRequest> {
+    PENDING
+        .lock()
+        .unwrap()
+        .get(id)
+        .map(|(approval, _)| approval.clone())
+}
+
+/// Answer a pending approval
+/// "Always allow" counts as a plain allow where it wasn't offered.
+pub fn respond(id: &str, mut decision: ApprovalDecision) -> Result<(), String> {
+    let pending = PENDING.lock().unwrap();
+    let (approval, tx) = pending
+        .get(id)
+        .ok_or_else(|| format!("No pending approval with id '{}'", id))?;
+    if decision == ApprovalDecision::AlwaysAllow && !approval.allow_always {
+        decision = ApprovalDecision::Allow;
+    }
+    // The requester may have timed out already; that is still a denial
+    let _ = tx.send(decision);
+    Ok(())
+}
+
+/// Deny the approval shown in a window that was closed without an answer
+pub fn window_closed(label: &str) {
+    if let Some(id) = label.strip_prefix(APPROVAL_WINDOW_PREFIX) {
+        let _ = respond(id, ApprovalDecision::Deny);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(id: &str, allow_always: bool) -> mpsc::Receiver<ApprovalDecision> {
+        let (tx, rx) = mpsc::channel();
+        let approval = ApprovalRequest {
+            id: id.to_string(),
+            title: "Open link?".to_string(),
+            detail: "https://example.com".to_string(),
+            allow_always,
+        };
+        PENDING.lock().unwrap().insert(id.to_string(), (approval, tx));
+        rx
+    }
+
+    #[test]
+    fn test_respond() {
+        let rx = insert("test-respond", true);
+        assert_eq!(get("test-respond").unwrap().detail, "https://example.com");
+        respond("test-respond", ApprovalDecision::AlwaysAllow).unwrap();
+        assert_eq!(rx.recv().unwrap(), ApprovalDecision::AlwaysAllow);
+        PENDING.lock().unwrap().remove("test-respond");
+        assert!(respond("test-respond", ApprovalDecision::Allow).is_err());
+
+        // "Always allow" that wasn't offered is a plain allow
+        let rx = insert("test-respond-once", false);
+        respond("test-respond-once", ApprovalDecision::AlwaysAllow).unwrap();
+        assert_eq!(rx.recv().unwrap(), ApprovalDecision::Allow);
+        PENDING.lock().unwrap().remove("test-respond-once");
+    }
+
+    #[test]
+    fn test_window_closed_denies() {
+        let rx = insert("test-closed", false);
+        window_closed("approval-test-closed");
+        assert_eq!(rx.recv().unwrap(), ApprovalDecision::Deny);
+        PENDING.lock().unwrap().remove("test-closed");
+    }
+
+    #[test]
+    fn test_serialization() {
+        let approval = ApprovalRequest {
+            id: "1".to_string(),
+            title: "t".to_string(),
+            detail: "d".to_string(),
+            allow_always: true,
+        };
+        let json = serde_json::to_value(&approval).unwrap();
+        assert_eq!(json["detail"], "d");
+        assert_eq!(json["allowAlways"], true);
+        assert_eq!(
+            serde_json::to_value(ApprovalDecision::AlwaysAllow).unwrap(),
+            "alwaysAllow"
+        );
+    }
+}
@@ -0,0 +1,650 @@
+//! Tamper-evident audit log of agent activity
+//!
+//! Turns, tool invocations and approval decisions are appended as JSON lines
+//! to `supiki/audit/audit.jsonl`. Each entry stores the SHA-256 hash of the
+//! previous one and its own hash over its contents, so editing or removing an
+//! entry breaks the chain (see `verify`). The newest entry's sequence number
+//! and hash are also kept in `head.json`, so cutting entries off the end or
+//! rewriting the whole chain is detected too. The file is rotated into
+//! `audit-<timestamp>.jsonl` archives once it grows too large; the chain
+//! continues across files.
+//!
+//! Only the GUI process writes the log. Failing to write it is logged but
+//! never stops the agent.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::state::{self, BackendMode, CODEX_SESSION_ID, SESSION_ID, SIDECAR_CWD};
+
+/// Size at which the current log is rotated
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated logs kept besides the current one
+const MAX_ARCHIVES: usize = 10;
+
+const LOG_FILE_NAME: &str = "audit.jsonl";
+
+/// Where the newest entry's sequence number and hash are anchored
+const HEAD_FILE_NAME: &str = "head.json";
+
+/// Previous hash of the very first entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Last entry's sequence number and hash, loaded from disk on first use
+static HEAD: Mutex<Option<(u32, String)>> = Mutex::new(None);
+
+/// Turn the next tool uses belong to
+static CURRENT_TURN: Mutex<Option<String>> = Mutex::new(None);
+
+static TURN_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// What an audit entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditKind {
+    TurnStart,
+    ToolUse,
+    /// A user decision in an approval window
+    Approval,
+    /// A permission request decided without asking (policy or "always allow")
+    Permission,
+    TurnEnd,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub seq: u32,
+    /// Unix timestamp in milliseconds
+    pub timestamp: f64,
+    pub kind: AuditKind,
+    /// "claude" or "codex"
+    pub backend: Option<String>,
+    pub session_id: Option<String>,
+    pub turn_id: Option<String>,
+    pub cwd: Option<String>,
+    pub tool: Option<String>,
+    /// Tool input (JSON), approval detail or turn settings
+    pub detail: Option<String>,
+    /// Decision or turn result
+    pub outcome: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// Filter for `query`; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuditQuery {
+    pub session_id: Option<String>,
+    pub turn_id: Option<String>,
+    pub tool: Option<String>,
+    /// Unix timestamps in milliseconds (inclusive)
+    pub since: Option<f64>,
+    pub until: Option<f64>,
+    /// Return only the newest entries
+    pub limit: Option<u32>,
+}
+
+/// Result of checking the hash chain
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditVerification {
+    pub entries: u32,
+    pub valid: bool,
+    /// Where the chain breaks, if it does
+    pub error: Option<String>,
+    /// The newest entry was checked against the stored head
+    pub anchored: bool,
+}
+
+/// Contents of `head.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Head {
+    seq: u32,
+    hash: String,
+}
+
+/// Get the audit log directory
+pub fn get_audit_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("audit"))
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+fn backend_name(backend: BackendMode) -> &'static str {
+    match backend {
+        BackendMode::Claude => "claude",
+        BackendMode::Codex => "codex",
+    }
+}
+
+fn session_for(backend: BackendMode) -> Option<String> {
+    match backend {
+        BackendMode::Claude => SESSION_ID.lock().unwrap().clone(),
+        BackendMode::Codex => CODEX_SESSION_ID.lock().unwrap().clone(),
+    }
+}
+
+/// Hash of an entry's contents, chained through its `prev_hash`
+pub fn compute_hash(entry: &AuditEntry) -> String {
+    let mut unhashed = entry.clone();
+    unhashed.hash = String::new();
+    let json = serde_json::to_string(&unhashed).unwrap_or_default();
+    Sha256::digest(json.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Log files oldest first, the current one last
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut archives: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("audit-") && n.ends_with(".jsonl"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Archive names embed a fixed-width timestamp, so they sort chronologically
+    archives.sort();
+    let current = dir.join(LOG_FILE_NAME);
+    if current.exists() {
+        archives.push(current);
+    }
+    archives
+}
+
+fn read_entries(path: &Path) -> Vec<AuditEntry> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log_line!("[Rust] Skipping unreadable audit entry: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Every entry in the log, oldest first
+fn read_all(dir: &Path) -> Vec<AuditEntry> {
+    log_files(dir).iter().flat_map(|p| read_entries(p)).collect()
+}
+
+fn read_head(dir: &Path) -> Option<Head> {
+    let content = fs::read_to_string(dir.join(HEAD_FILE_NAME)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Store the newest entry's position, replacing the file atomically
+fn write_head(dir: &Path, head: &Head) -> Result<(), String> {
+    let path = dir.join(HEAD_FILE_NAME);
+    let temp = dir.join(format!("{}.tmp", HEAD_FILE_NAME));
+    let content = serde_json::to_string(head).map_err(|e| e.to_string())?;
+    fs::write(&temp, content).map_err(|e| format!("Failed to write audit head: {}", e))?;
+    state::restrict_to_owner(&temp);
+    fs::rename(&temp, &path).map_err(|e| format!("Failed to write audit head: {}", e))
+}
+
+/// Sequence number and hash of the newest entry
+/// The stored head wins over the log, so entries appended after a truncation
+/// don't chain onto the cut-off end and hide it.
+fn load_head(dir: &Path) -> (u32, String) {
+    if let Some(head) = read_head(dir) {
+        return (head.seq, head.hash);
+    }
+    log_files(dir)
+        .iter()
+        .rev()
+        .find_map(|p| read_entries(p).pop())
+        .map(|e| (e.seq, e.hash))
+        .unwrap_or_else(|| (0, GENESIS_HASH.to_string()))
+}
+
+/// Move a full log aside and drop the oldest archives
+fn rotate(dir: &Path) {
+    let current = dir.join(LOG_FILE_NAME);
+    let size = fs::metadata(&current).map(|m| m.len()).unwrap_or(0);
+    if size < MAX_LOG_BYTES {
+        return;
+    }
+    let archive = dir.join(format!("audit-{:015}.jsonl", now_ms() as u64));
+    if let Err(e) = fs::rename(&current, &archive) {
+        log_line!("[Rust] Failed to rotate audit log: {}", e);
+        return;
+    }
+    let files = log_files(dir);
+    for old in files.iter().take(files.len().saturating_sub(MAX_ARCHIVES)) {
+        let _ = fs::remove_file(old);
+    }
+}
+
+/// Chain an entry to the head and append it to the log in `dir`
+fn append_to(dir: &Path, head: &mut Option<(u32, String)>, mut entry: AuditEntry) -> Result<AuditEntry, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create audit dir: {}", e))?;
+    let (seq, prev_hash) = head.get_or_insert_with(|| load_head(dir)).clone();
+    entry.seq = seq + 1;
+    entry.prev_hash = prev_hash;
+    entry.hash = compute_hash(&entry);
+
+    rotate(dir);
+    let path = dir.join(LOG_FILE_NAME);
+    let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))?;
+    state::restrict_to_owner(&path);
+
+    *head = Some((entry.seq, entry.hash.clone()));
+    write_head(
+        dir,
+        &Head {
+            seq: entry.seq,
+            hash: entry.hash.clone(),
+        },
+    )?;
+    Ok(entry)
+}
+
+fn append(entry: AuditEntry) {
+    let Some(dir) = get_audit_dir() else {
+        return;
+    };
+    let mut head = HEAD.lock().unwrap();
+    if let Err(e) = append_to(&dir, &mut head, entry) {
+        log_line!("[Rust] {}", e);
+    }
+}
+
+/// An entry for the current turn, ready to be chained
+fn entry(kind: AuditKind, backend: Option<BackendMode>) -> AuditEntry {
+    AuditEntry {
+        seq: 0,
+        timestamp: now_ms(),
+        kind,
+        backend: backend.map(|b| backend_name(b).to_string()),
+        session_id: backend.and_then(session_for),
+        turn_id: CURRENT_TURN.lock().unwrap().clone(),
+        cwd: SIDECAR_CWD.lock().unwrap().clone(),
+        tool: None,
+        detail: None,
+        outcome: None,
+        prev_hash: String::new(),
+        hash: String::new(),
+    }
+}
+
+/// Start a new turn and return its id; `settings` describes its permissions (policy, sandbox)
+pub fn begin_turn(backend: BackendMode, settings: serde_json::Value) -> String {
+    let id = format!(
+        "{}-{}",
+        now_ms() as u64,
+        TURN_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    *CURRENT_TURN.lock().unwrap() = Some(id.clone());
+    append(AuditEntry {
+        detail: Some(settings.to_string()),
+        ..entry(AuditKind::TurnStart, Some(backend))
+    });
+    id
+}
+
+/// Finish the current turn
+pub fn end_turn(backend: BackendMode, outcome: &str) {
+    append(AuditEntry {
+        outcome: Some(outcome.to_string()),
+        ..entry(AuditKind::TurnEnd, Some(backend))
+    });
+    *CURRENT_TURN.lock().unwrap() = None;
+}
+
+/// Finish a turn whose process exited (stopped, killed or crashed)
+/// Does nothing if the stream already ended it or a newer turn started.
+pub fn end_turn_on_exit(backend: BackendMode, turn_id: &str, outcome: &str) {
+    if CURRENT_TURN.lock().unwrap().as_deref() == Some(turn_id) {
+        end_turn(backend, outcome);
+    }
+}
+
+/// Record a tool invocation from the agent's stream
+pub fn record_tool_use(backend: BackendMode, tool: &str, input: &serde_json::Value) {
+    append(AuditEntry {
+        tool: Some(tool.to_string()),
+        detail: Some(input.to_string()),
+        ..entry(AuditKind::ToolUse, Some(backend))
+    });
+}
+
+/// Record the user's answer in an approval window
+pub fn record_approval(title: &str, detail: &str, outcome: &str) {
+    let backend = *state::BACKEND_MODE.lock().unwrap();
+    append(AuditEntry {
+        tool: Some(title.to_string()),
+        detail: Some(detail.to_string()),
+        outcome: Some(outcome.to_string()),
+        ..entry(AuditKind::Approval, Some(backend))
+    });
+}
+
+/// Record a permission request decided without asking
+pub fn record_permission(tool: &str, input: &serde_json::Value, outcome: &str) {
+    append(AuditEntry {
+        tool: Some(tool.to_string()),
+        detail: Some(input.to_string()),
+        outcome: Some(outcome.to_string()),
+        ..entry(AuditKind::Permission, Some(BackendMode::Claude))
+    });
+}
+
+fn matches(query: &AuditQuery, entry: &AuditEntry) -> bool {
+    let same = |wanted: &Option<String>, actual: &Option<String>| {
+        wanted.is_none() || wanted.as_deref() == actual.as_deref()
+    };
+    same(&query.session_id, &entry.session_id)
+        && same(&query.turn_id, &entry.turn_id)
+        && same(&query.tool, &entry.tool)
+        && entry.timestamp >= query.since.unwrap_or(f64::NEG_INFINITY)
+        && entry.timestamp <= query.until.unwrap_or(f64::INFINITY)
+}
+
+fn query_in(dir: &Path, query: &AuditQuery) -> Vec<AuditEntry> {
+    let mut entries: Vec<AuditEntry> = read_all(dir).into_iter().filter(|e| matches(query, e)).collect();
+    if let Some(limit) = query.limit {
+        let skip = entries.len().saturating_sub(limit as usize);
+        entries.drain(..skip);
+    }
+    entries
+}
+
+/// Entries matching the query, oldest first
+pub fn query(query: &AuditQuery) -> Result<Vec<AuditEntry>, String> {
+    let dir = get_audit_dir().ok_or("Could not find data directory")?;
+    Ok(query_in(&dir, query))
+}
+
+fn verify_entries(entries: &[AuditEntry]) -> AuditVerification {
+    let mut prev: Option<&AuditEntry> = None;
+    for entry in entries {
+        let error = if compute_hash(entry) != entry.hash {
+            Some(format!("Entry {} was modified", entry.seq))
+        } else if let Some(prev) = prev.filter(|p| p.hash != entry.prev_hash) {
+            Some(format!(
+                "Entry {} does not follow entry {}; entries are missing or were reordered",
+                entry.seq, prev.seq
+            ))
+        } else {
+            None
+        };
+        if error.is_some() {
+            return AuditVerification {
+                entries: entries.len() as u32,
+                valid: false,
+                error,
+                anchored: false,
+            };
+        }
+        prev = Some(entry);
+    }
+    AuditVerification {
+        entries: entries.len() as u32,
+        valid: true,
+        error: None,
+        anchored: false,
+    }
+}
+
+/// Check the chain in `dir` and that it ends at the stored head
+fn verify_in(dir: &Path) -> AuditVerification {
+    let entries = read_all(dir);
+    let mut result = verify_entries(&entries);
+    // Logs written before the head was stored can only be checked as a chain
+    let Some(head) = read_head(dir) else {
+        return result;
+    };
+    result.anchored = true;
+    if !result.valid {
+        return result;
+    }
+    let last = entries.last();
+    if last.map(|e| (e.seq, e.hash.as_str())) != Some((head.seq, head.hash.as_str())) {
+        result.valid = false;
+        result.error = Some(match last {
+            Some(last) if last.seq < head.seq => format!(
+                "The log ends at entry {} but entry {} was written; entries were cut off",
+                last.seq, head.seq
+            ),
+            None => format!("The log is empty but entry {} was written", head.seq),
+            Some(_) => format!("The log does not end at the stored entry {}; it was rewritten", head.seq),
+        });
+    }
+    result
+}
+
+/// Check the hash chain of the whole log against the stored head
+/// The oldest remaining entry anchors the chain, since old archives are pruned.
+pub fn verify() -> Result<AuditVerification, String> {
+    let dir = get_audit_dir().ok_or("Could not find data directory")?;
+    Ok(verify_in(&dir))
+}
+
+/// Markdown report of a turn's activity
+pub fn turn_report(turn_id: &str, entries: &[AuditEntry]) -> String {
+    let first = entries.first();
+    let field = |get: fn(&AuditEntry) -> &Option<String>| {
+        entries
+            .iter()
+            .rev()
+            .find_map(|e| get(e).clone())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let mut report = format!(
+        "# Supiki turn {}\n\n- Backend: {}\n- Session: {}\n- Working directory: {}\n- Started: {} (unix ms)\n- Entries: {} (seq {} to {})\n\n## Activity\n\n",
+        turn_id,
+        field(|e| &e.backend),
+        field(|e| &e.session_id),
+        field(|e| &e.cwd),
+        first.map(|e| e.timestamp as u64).unwrap_or(0),
+        entries.len(),
+        first.map(|e| e.seq).unwrap_or(0),
+        entries.last().map(|e| e.seq).unwrap_or(0),
+    );
+    let start = first.map(|e| e.timestamp).unwrap_or(0.0);
+    for e in entries {
+        let mut line = format!("- +{:.1}s {:?}", (e.timestamp - start) / 1000.0, e.kind);
+        if let Some(tool) = &e.tool {
+            line.push_str(&format!(" `{}`", tool));
+        }
+        if let Some(detail) = &e.detail {
+            line.push_str(&format!(": {}", detail));
+        }
+        if let Some(outcome) = &e.outcome {
+            line.push_str(&format!(" -> {}", outcome));
+        }
+        report.push_str(&line);
+        report.push('\n');
+    }
+    let verification = verify_entries(entries);
+    report.push_str(&format!(
+        "\nHash chain of these entries: {}\n",
+        match verification.error {
+            None => "intact".to_string(),
+            Some(error) => error,
+        }
+    ));
+    report
+}
+
+/// Write a turn's report to `supiki/audit/reports` and return its path
+pub fn export_turn(turn_id: &str) -> Result<String, String> {
+    let dir = get_audit_dir().ok_or("Could not find data directory")?;
+    let entries = query_in(
+        &dir,
+        &AuditQuery {
+            turn_id: Some(turn_id.to_string()),
+            ..Default::default()
+        },
+    );
+    if entries.is_empty() {
+        return Err(format!("No audit entries for turn '{}'", turn_id));
+    }
+    let reports = dir.join("reports");
+    fs::create_dir_all(&reports).map_err(|e| format!("Failed to create reports dir: {}", e))?;
+    let safe_id: String = turn_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let path = reports.join(format!("turn-{}.md", safe_id));
+    fs::write(&path, turn_report(turn_id, &entries)).map_err(|e| format!("Failed to write report: {}", e))?;
+    state::restrict_to_owner(&path);
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("supiki-audit-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn tool_use(tool: &str, turn: &str, timestamp: f64) -> AuditEntry {
+        AuditEntry {
+            timestamp,
+            tool: Some(tool.to_string()),
+            turn_id: Some(turn.to_string()),
+            session_id: Some("s1".to_string()),
+            detail: Some(r#"{"command":"ls"}"#.to_string()),
+            ..entry(AuditKind::ToolUse, None)
+        }
+    }
+
+    #[test]
+    fn test_chain_and_verify() {
+        let dir = temp_dir("chain");
+        let mut head = None;
+        for (i, tool) in ["Bash", "Edit", "Bash"].iter().enumerate() {
+            append_to(&dir, &mut head, tool_use(tool, "t1", i as f64)).unwrap();
+        }
+        let entries = read_all(&dir);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        assert!(verify_entries(&entries).valid);
+
+        // A fresh head picks up where the file ends
+        let mut fresh = None;
+        let next = append_to(&dir, &mut fresh, tool_use("Read", "t2", 9.0)).unwrap();
+        assert_eq!(next.seq, 4);
+        assert_eq!(next.prev_hash, entries[2].hash);
+
+        let mut tampered = entries.clone();
+        tampered[1].detail = Some(r#"{"command":"rm -rf /"}"#.to_string());
+        assert!(!verify_entries(&tampered).valid);
+
+        let mut removed = entries.clone();
+        removed.remove(1);
+        let result = verify_entries(&removed);
+        assert!(!result.valid);
+        assert!(result.error.unwrap().contains("missing"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_head_detects_truncation_and_rewrites() {
+        let dir = temp_dir("head");
+        let mut head = None;
+        for (i, tool) in ["Bash", "Edit", "Bash"].iter().enumerate() {
+            append_to(&dir, &mut head, tool_use(tool, "t1", i as f64)).unwrap();
+        }
+        let result = verify_in(&dir);
+        assert!(result.valid && result.anchored);
+
+        // Cutting off the newest entry leaves a valid chain, but not the stored end
+        let log = dir.join(LOG_FILE_NAME);
+        let content = fs::read_to_string(&log).unwrap();
+        let kept: Vec<&str> = content.lines().take(2).collect();
+        fs::write(&log, format!("{}\n", kept.join("\n"))).unwrap();
+        let result = verify_in(&dir);
+        assert!(!result.valid);
+        assert!(result.error.unwrap().contains("cut off"));
+
+        // New entries chain onto the stored head, so the gap stays visible
+        let mut fresh = None;
+        let next = append_to(&dir, &mut fresh, tool_use("Read", "t2", 9.0)).unwrap();
+        assert_eq!(next.seq, 4);
+        assert!(!verify_in(&dir).valid);
+
+        // A consistently rebuilt chain of the same length doesn't end at the stored hash
+        let forged_dir = temp_dir("head-forged");
+        let mut forged = None;
+        for i in 0..4 {
+            append_to(&forged_dir, &mut forged, tool_use("Read", "t1", i as f64)).unwrap();
+        }
+        fs::copy(forged_dir.join(LOG_FILE_NAME), &log).unwrap();
+        let result = verify_in(&dir);
+        assert!(!result.valid);
+        assert!(result.error.unwrap().contains("rewritten"));
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&forged_dir);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let dir = temp_dir("query");
+        let mut head = None;
+        append_to(&dir, &mut head, tool_use("Bash", "t1", 100.0)).unwrap();
+        append_to(&dir, &mut head, tool_use("Edit", "t1", 200.0)).unwrap();
+        append_to(&dir, &mut head, tool_use("Bash", "t2", 300.0)).unwrap();
+
+        let by_tool = query_in(&dir, &AuditQuery { tool: Some("Bash".into()), ..Default::default() });
+        assert_eq!(by_tool.len(), 2);
+        let by_turn = query_in(&dir, &AuditQuery { turn_id: Some("t1".into()), ..Default::default() });
+        assert_eq!(by_turn.len(), 2);
+        let by_time = query_in(&dir, &AuditQuery { since: Some(150.0), until: Some(250.0), ..Default::default() });
+        assert_eq!(by_time.len(), 1);
+        let newest = query_in(&dir, &AuditQuery { limit: Some(1), ..Default::default() });
+        assert_eq!(newest[0].turn_id.as_deref(), Some("t2"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_turn_report() {
+        let entries = vec![tool_use("Bash", "t1", 1000.0), tool_use("Edit", "t1", 2500.0)];
+        let report = turn_report("t1", &entries);
+        assert!(report.starts_with("# Supiki turn t1"));
+        assert!(report.contains("- +1.5s ToolUse `Edit`"));
+        assert!(report.contains("Session: s1"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Local bridge from the MCP server process to the GUI
+//!
+//! Most mascot tools only need the GUI to react to the tool_use in the CLI
+//! stream, but some (helper windows, confirmations) need an answer from the
+//! GUI before the tool can return. The GUI listens on a localhost TCP port and
+//! writes the port and a random token to `bridge.json` in the app data
+//! directory. Each connection carries one line-delimited JSON request and one
+//! response.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::helpers;
+use crate::opener;
+use crate::permissions;
+use crate::screenshots;
+use crate::state;
+
+/// How long the client waits to connect to the GUI
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long the GUI waits for a connected client to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where the GUI process advertises the bridge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeInfo {
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BridgeRequest {
+    token: String,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BridgeResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl From<Result<serde_json::Value, String>> for BridgeResponse {
+    fn from(result: Result<serde_json::Value, String>) -> Self {
+        match result {
+            Ok(value) => Self {
+                result: Some(value),
+                error: None,
+            },
+            Err(e) => Self {
+                result: None,
+                error: Some(e),
+            },
+        }
+    }
+}
+
+/// Get the bridge info file path
+pub fn get_bridge_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("bridge.json"))
+}
+
+/// Handle a bridge request in the GUI process
+/// Runs on a bridge thread, so handlers may block (e.g. waiting for the user)
+pub fn dispatch(app: &tauri::AppHandle, method: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    match method {
+        "spawn_helper" => helpers::spawn_from_params(app, params),
+        "dismiss_helper" => helpers::dismiss_from_params(app, params),
+        "list_helpers" => helpers::list_value(app),
+        "open_url" => opener::open_url_from_params(app, params),
+        "open_path" => opener::open_path_from_params(app, params),
+        "permission_prompt" => permissions::prompt_from_params(app, params),
+        "screenshot_consent" => screenshots::consent_from_params(app, params),
+        _ => Err(format!("Unknown bridge method '{}'", method)),
+    }
+}
+
+/// Start the bridge listener in the GUI process
+pub fn start(app: tauri::AppHandle) -> Result<(), String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| format!("Failed to bind bridge: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let token = crate::mcp_server::http::generate_token();
+
+    let path = get_bridge_file_path().ok_or("Could not find data directory")?;
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let info = BridgeInfo {
+        port,
+        token: token.clone(),
+    };
+    let content = serde_json::to_string(&info).map_err(|e| e.to_string())?;
+    state::write_secret(&path, &content).map_err(|e| format!("Failed to write bridge info: {}", e))?;
+
+    log_line!("[Rust] Bridge listening on 127.0.0.1:{}", port);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let app = app.clone();
+            let token = token.clone();
+            thread::spawn(move || {
+                if let Err(e) = serve_connection(&app, &token, stream) {
+                    log_line!("[Rust] Bridge connection failed: {}", e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+fn serve_connection(app: &tauri::AppHandle, token: &str, stream: TcpStream) -> Result<(), String> {
+    // A client that connects and sends nothing must not hold the thread
+    stream
+        .set_read_timeout(Some(REQUEST_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+
+    let response: BridgeResponse = match serde_json::from_str::<BridgeRequest>(&line) {
+        Ok(request) if state::secrets_match(&request.token, token) => dispatch(app, &request.method, request.params).into(),
+        Ok(_) => Err("Invalid bridge token".to_string()).into(),
+        Err(e) => Err(format!("Invalid bridge request: {}", e)).into(),
+    };
+    write_line(&stream, &response)
+}
+
+fn write_line<T: Serialize>(mut stream: &TcpStream, value: &T) -> Result<(), String> {
+    let mut line = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Call the GUI from another process (blocking)
+pub fn call(method: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    let path = get_bridge_file_path().ok_or("Could not find data directory")?;
+    let info: BridgeInfo = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .ok_or("Supiki is not running")?;
+
+    let address = (Ipv4Addr::LOCALHOST, info.port).into();
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+        .map_err(|_| "Supiki is not running".to_string())?;
+    write_line(
+        &stream,
+        &BridgeRequest {
+            token: info.token,
+            method: method.to_string(),
+            params,
+        },
+    )?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("Bridge read failed: {}", e))?;
+    let response: BridgeResponse =
+        serde_json::from_str(&line).map_err(|e| format!("Invalid bridge response: {}", e))?;
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(error),
+        (Some(result), None) => Ok(result),
+        (None, None) => Ok(serde_json::Value::Null),
+    }
+}
+
+/// Call the GUI from async code without blocking the runtime
+pub async fn call_async(method: &'static str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    tokio::task::spawn_blocking(move || call(method, params))
+        .await
+        .map_err(|e| format!("Bridge task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_bridge_file_path() {
+        if let Some(p) = get_bridge_file_path() {
+            assert!(p.ends_with("bridge.json"));
+            assert!(p.to_string_lossy().contains("supiki"));
+        }
+    }
+
+    #[test]
+    fn test_response_from_result() {
+        let ok: BridgeResponse = Ok(serde_json::json!(1)).into();
+        assert_eq!(serde_json::to_string(&ok).unwrap(), r#"{"result":1}"#);
+        let err: BridgeResponse = Err("nope".to_string()).into();
+        assert_eq!(serde_json::to_string(&err).unwrap(), r#"{"error":"nope"}"#);
+    }
+
+    #[test]
+    fn test_request_params_default() {
+        let request: BridgeRequest = serde_json::from_str(r#"{"token":"t","method":"list_helpers"}"#).unwrap();
+        assert_eq!(request.method, "list_helpers");
+        assert!(request.params.is_null());
+    }
+}
@@ -0,0 +1,916 @@
+//! Workspace checkpoints
+//!
+//! Before a turn that may edit files, the workspace is snapshotted so the
+//! turn can be rolled back. In a git repository the snapshot is a commit built
+//! from a scratch index (tracked and untracked files, honouring `.gitignore`)
+//! and kept under `refs/supiki/checkpoints/<id>`, so the user's index, stash
+//! and branches are left alone. Elsewhere files are copied into a
+//! content-addressed store in `supiki/checkpoints/objects/` with a manifest
+//! per checkpoint; files too large to store are listed as untracked. When
+//! the turn ends its changes are sent as a `checkpoint-diff` event;
+//! checkpoints of turns that changed nothing are dropped.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::Emitter;
+
+use crate::state::{self, BackendMode, CODEX_SESSION_ID, SESSION_ID};
+
+const INDEX_FILE_NAME: &str = "checkpoints.jsonl";
+
+/// Git refs holding checkpoint commits
+const REF_PREFIX: &str = "refs/supiki/checkpoints/";
+
+/// Oldest checkpoints beyond this are deleted
+const MAX_CHECKPOINTS: usize = 50;
+
+/// Copy checkpoints give up on workspaces with more files than this
+const MAX_FILES: usize = 5000;
+
+/// Larger files are not stored in copy checkpoints, only listed as untracked
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Checkpoints give up on workspaces whose stored (copy) or untracked (git)
+/// files add up to more than this
+const MAX_TOTAL_SIZE: u64 = 200 * 1024 * 1024;
+
+/// Manifest entry of a file too large to store; its changes can't be rolled back
+const UNTRACKED: &str = "untracked";
+
+/// Directories left out of copy checkpoints
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", ".venv", "__pycache__"];
+
+/// Serializes access to the checkpoint index and store
+static LOCK: Mutex<()> = Mutex::new(());
+
+static NEXT_TURN: AtomicU64 = AtomicU64::new(1);
+
+/// Turns that have not finished yet: backend, token and checkpoint, if one was taken
+static ACTIVE: Mutex<Vec<(BackendMode, u64, Option<String>)>> = Mutex::new(Vec::new());
+
+/// How a checkpoint is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointKind {
+    /// A commit under `refs/supiki/checkpoints/`
+    Git,
+    /// A manifest into the content-addressed store
+    Copy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeStatus {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A file changed since a checkpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    /// Path relative to the checkpoint root, with `/` separators
+    pub path: String,
+    pub status: ChangeStatus,
+    /// Added and removed lines (git checkpoints, text files only)
+    pub additions: Option<u32>,
+    pub deletions: Option<u32>,
+}
+
+/// A snapshot of the workspace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub id: String,
+    /// Unix timestamp in milliseconds
+    pub timestamp: f64,
+    /// "claude" or "codex"
+    pub backend: String,
+    pub session_id: Option<String>,
+    /// Workspace root (the repository root for git checkpoints)
+    pub root: String,
+    pub kind: CheckpointKind,
+    /// "turn", or "restore" for the state kept before a rollback
+    pub reason: String,
+    /// What changed after the checkpoint was taken
+    #[serde(default)]
+    pub changes: Vec<FileChange>,
+}
+
+/// Payload of the `checkpoint-diff` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointDiff {
+    pub checkpoint_id: String,
+    pub root: String,
+    pub changes: Vec<FileChange>,
+}
+
+/// Get the checkpoint directory
+pub fn get_checkpoints_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("checkpoints"))
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+fn backend_name(backend: BackendMode) -> &'static str {
+    match backend {
+        BackendMode::Claude => "claude",
+        BackendMode::Codex => "codex",
+    }
+}
+
+fn session_for(backend: BackendMode) -> Option<String> {
+    match backend {
+        BackendMode::Claude => SESSION_ID.lock().unwrap().clone(),
+        BackendMode::Codex => CODEX_SESSION_ID.lock().unwrap().clone(),
+    }
+}
+
+fn git_output(root: &Path, index: Option<&Path>, args: &[&str]) -> Result<Vec<u8>, String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotepath=off"])
+        .args(args)
+        .env("GIT_AUTHOR_NAME", "Supiki")
+        .env("GIT_AUTHOR_EMAIL", "supiki@localhost")
+        .env("GIT_COMMITTER_NAME", "Supiki")
+        .env("GIT_COMMITTER_EMAIL", "supiki@localhost");
+    if let Some(index) = index {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+
+    // On Windows, hide the terminal window
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd.output().map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn git(root: &Path, index: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let stdout = git_output(root, index, args)?;
+    Ok(String::from_utf8_lossy(&stdout).trim_end().to_string())
+}
+
+/// The repository root containing `dir`, if it is in one
+fn git_root(dir: &Path) -> Option<PathBuf> {
+    git(dir, None, &["rev-parse", "--show-toplevel"]).ok().map(PathBuf::from)
+}
+
+fn git_head(root: &Path) -> Option<String> {
+    git(root, None, &["rev-parse", "--verify", "-q", "HEAD"]).ok()
+}
+
+/// Scratch index for building trees without touching the user's index
+fn scratch_index(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("index-{}", id))
+}
+
+/// Write the current working tree as a git tree object
+fn git_snapshot_tree(root: &Path, scratch: &Path) -> Result<String, String> {
+    let _ = fs::remove_file(scratch);
+    // Starting from a copy of the real index lets git skip unchanged files
+    let real_index = git(root, None, &["rev-parse", "--git-path", "index"])?;
+    if fs::copy(root.join(real_index), scratch).is_err() {
+        if git_head(root).is_some() {
+            git(root, Some(scratch), &["read-tree", "HEAD"])?;
+        } else {
+            git(root, Some(scratch), &["read-tree", "--empty"])?;
+        }
+    }
+    let tree = git(root, Some(scratch), &["add", "-A"]).and_then(|_| git(root, Some(scratch), &["write-tree"]));
+    let _ = fs::remove_file(scratch);
+    tree
+}
+
+/// Total size of the untracked files `git add -A` would store
+fn git_untracked_size(root: &Path) -> Result<u64, String> {
+    let listing = git_output(root, None, &["ls-files", "-z", "--others", "--exclude-standard"])?;
+    Ok(listing
+        .split(|b| *b == 0)
+        .filter(|path| !path.is_empty())
+        .filter_map(|path| fs::metadata(root.join(String::from_utf8_lossy(path).as_ref())).ok())
+        .map(|m| m.len())
+        .sum())
+}
+
+fn git_checkpoint(root: &Path, store: &Path, id: &str) -> Result<(), String> {
+    if git_untracked_size(root)? > MAX_TOTAL_SIZE {
+        return Err(format!(
+            "the untracked files add up to more than {} MB",
+            MAX_TOTAL_SIZE / (1024 * 1024)
+        ));
+    }
+    let tree = git_snapshot_tree(root, &scratch_index(store, id))?;
+    let message = format!("supiki checkpoint {}", id);
+    let head = git_head(root);
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+    if let Some(head) = &head {
+        args.extend(["-p", head.as_str()]);
+    }
+    let commit = git(root, None, &args)?;
+    let name = format!("{}{}", REF_PREFIX, id);
+    git(root, None, &["update-ref", name.as_str(), commit.as_str()])?;
+    Ok(())
+}
+
+/// Parse `diff-tree -z --name-status` and `-z --numstat` output into changes
+/// With `-z` paths come verbatim (not C-quoted) and end with a NUL.
+fn parse_git_changes(name_status: &str, numstat: &str) -> Vec<FileChange> {
+    let counts: BTreeMap<&str, (Option<u32>, Option<u32>)> = numstat
+        .split('\0')
+        .filter_map(|entry| {
+            let mut parts = entry.splitn(3, '\t');
+            let additions = parts.next()?.parse().ok();
+            let deletions = parts.next()?.parse().ok();
+            Some((parts.next()?, (additions, deletions)))
+        })
+        .collect();
+    let fields: Vec<&str> = name_status.split('\0').collect();
+    fields
+        .chunks(2)
+        .filter_map(|entry| {
+            let [status, path] = entry else {
+                return None;
+            };
+            if path.is_empty() {
+                return None;
+            }
+            let status = match *status {
+                "A" => ChangeStatus::Added,
+                "D" => ChangeStatus::Deleted,
+                _ => ChangeStatus::Modified,
+            };
+            let (additions, deletions) = counts.get(path).copied().unwrap_or((None, None));
+            Some(FileChange {
+                path: path.to_string(),
+                status,
+                additions,
+                deletions,
+            })
+        })
+        .collect()
+}
+
+fn git_changes(root: &Path, store: &Path, id: &str) -> Result<Vec<FileChange>, String> {
+    let tree = git_snapshot_tree(root, &scratch_index(store, &format!("{}-now", id)))?;
+    let checkpoint = format!("{}{}", REF_PREFIX, id);
+    let diff = |format: &str| {
+        git_output(
+            root,
+            None,
+            &["diff-tree", "-r", "-z", "--no-renames", format, checkpoint.as_str(), tree.as_str()],
+        )
+        .map(|stdout| String::from_utf8_lossy(&stdout).to_string())
+    };
+    Ok(parse_git_changes(&diff("--name-status")?, &diff("--numstat")?))
+}
+
+fn git_restore(root: &Path, store: &Path, id: &str, changes: &[FileChange]) -> Result<(), String> {
+    let mut paths = Vec::new();
+    for change in changes {
+        if change.status == ChangeStatus::Added {
+            let _ = fs::remove_file(root.join(&change.path));
+        } else {
+            paths.push(change.path.as_str());
+        }
+    }
+    if paths.is_empty() {
+        return Ok(());
+    }
+    // Check the files out of the checkpoint through a scratch index
+    let scratch = scratch_index(store, &format!("{}-restore", id));
+    let checkpoint = format!("{}{}", REF_PREFIX, id);
+    let mut args = vec!["checkout-index", "-f", "--"];
+    args.extend(paths);
+    let result = git(root, Some(&scratch), &["read-tree", checkpoint.as_str()])
+        .and_then(|_| git(root, Some(&scratch), &args));
+    let _ = fs::remove_file(&scratch);
+    result.map(|_| ())
+}
+
+fn hash_bytes(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash every file under `dir`, copying new contents into `objects` if given
+/// `total` adds up the size of the hashed files.
+fn walk(
+    root: &Path,
+    dir: &Path,
+    files: &mut BTreeMap<String, String>,
+    total: &mut u64,
+    objects: Option<&Path>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()) {
+                walk(root, &path, files, total, objects)?;
+            }
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !file_type.is_file() {
+            continue;
+        }
+        if files.len() >= MAX_FILES {
+            return Err(format!("the workspace has more than {} files", MAX_FILES));
+        }
+        let relative: Vec<String> = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        if metadata.len() > MAX_FILE_SIZE {
+            files.insert(relative.join("/"), UNTRACKED.to_string());
+            continue;
+        }
+        *total += metadata.len();
+        if *total > MAX_TOTAL_SIZE {
+            return Err(format!(
+                "the workspace has more than {} MB of files",
+                MAX_TOTAL_SIZE / (1024 * 1024)
+            ));
+        }
+        let Ok(data) = fs::read(&path) else {
+            continue;
+        };
+        let hash = hash_bytes(&data);
+        if let Some(objects) = objects {
+            let object = objects.join(&hash);
+            if !object.exists() {
+                fs::write(&object, &data).map_err(|e| format!("Failed to store file: {}", e))?;
+                state::restrict_to_owner(&object);
+            }
+        }
+        files.insert(relative.join("/"), hash);
+    }
+    Ok(())
+}
+
+fn scan(root: &Path, objects: Option<&Path>) -> Result<BTreeMap<String, String>, String> {
+    let mut files = BTreeMap::new();
+    walk(root, root, &mut files, &mut 0, objects)?;
+    Ok(files)
+}
+
+/// Compare two manifests (path -> content hash)
+/// Files that were untracked in `before` are left out, since they can't be
+/// rolled back; new files are reported (and removed by a rollback) either way.
+fn manifest_changes(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> Vec<FileChange> {
+    let change = |path: &String, status| FileChange {
+        path: path.clone(),
+        status,
+        additions: None,
+        deletions: None,
+    };
+    let paths: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    paths
+        .into_iter()
+        .filter_map(|path| match (before.get(path), after.get(path)) {
+            (Some(a), _) if a == UNTRACKED => None,
+            (None, Some(_)) => Some(change(path, ChangeStatus::Added)),
+            (Some(_), None) => Some(change(path, ChangeStatus::Deleted)),
+            (Some(a), Some(b)) if a != b => Some(change(path, ChangeStatus::Modified)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn manifest_path(store: &Path, id: &str) -> PathBuf {
+    store.join("manifests").join(format!("{}.json", id))
+}
+
+fn load_manifest(store: &Path, id: &str) -> Result<BTreeMap<String, String>, String> {
+    let content = fs::read_to_string(manifest_path(store, id)).map_err(|e| format!("Checkpoint files are missing: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Checkpoint manifest is corrupt: {}", e))
+}
+
+fn copy_checkpoint(root: &Path, store: &Path, id: &str) -> Result<(), String> {
+    let objects = store.join("objects");
+    fs::create_dir_all(&objects).map_err(|e| format!("Failed to create checkpoint store: {}", e))?;
+    fs::create_dir_all(store.join("manifests")).map_err(|e| format!("Failed to create checkpoint store: {}", e))?;
+    let manifest = scan(root, Some(&objects))?;
+    let path = manifest_path(store, id);
+    fs::write(&path, serde_json::to_string(&manifest).map_err(|e| e.to_string())?)
+        .map_err(|e| format!("Failed to write checkpoint manifest: {}", e))?;
+    state::restrict_to_owner(&path);
+    Ok(())
+}
+
+fn copy_restore(root: &Path, store: &Path, id: &str, changes: &[FileChange]) -> Result<(), String> {
+    let manifest = load_manifest(store, id)?;
+    for change in changes {
+        let target = root.join(&change.path);
+        match manifest.get(&change.path) {
+            None => {
+                let _ = fs::remove_file(&target);
+            }
+            Some(hash) if hash == UNTRACKED => {
+                log_line!("[Rust] {} was too large to checkpoint; leaving it as it is", change.path);
+            }
+            Some(hash) => {
+                if let Some(parent) = target.parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+                fs::copy(store.join("objects").join(hash), &target)
+                    .map_err(|e| format!("Failed to restore {}: {}", change.path, e))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_index(store: &Path) -> Vec<Checkpoint> {
+    fs::read_to_string(store.join(INDEX_FILE_NAME))
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn write_index(store: &Path, records: &[Checkpoint]) -> Result<(), String> {
+    let path = store.join(INDEX_FILE_NAME);
+    let content: String = records
+        .iter()
+        .filter_map(|r| serde_json::to_string(r).ok())
+        .map(|line| line + "\n")
+        .collect();
+    fs::write(&path, content).map_err(|e| format!("Failed to write checkpoint index: {}", e))?;
+    state::restrict_to_owner(&path);
+    Ok(())
+}
+
+/// Delete a checkpoint's commit ref or manifest (objects are collected separately)
+fn delete_storage(store: &Path, record: &Checkpoint) {
+    match record.kind {
+        CheckpointKind::Git => {
+            let name = format!("{}{}", REF_PREFIX, record.id);
+            let _ = git(Path::new(&record.root), None, &["update-ref", "-d", name.as_str()]);
+        }
+        CheckpointKind::Copy => {
+            let _ = fs::remove_file(manifest_path(store, &record.id));
+        }
+    }
+}
+
+/// Delete stored files no remaining manifest refers to
+fn collect_objects(store: &Path, records: &[Checkpoint]) {
+    let mut used = BTreeSet::new();
+    for record in records.iter().filter(|r| r.kind == CheckpointKind::Copy) {
+        if let Ok(manifest) = load_manifest(store, &record.id) {
+            used.extend(manifest.into_values());
+        }
+    }
+    let Ok(entries) = fs::read_dir(store.join("objects")) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !used.contains(entry.file_name().to_string_lossy().as_ref()) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Take a checkpoint of `dir` (the caller holds `LOCK`)
+fn create(store: &Path, dir: &Path, backend: &str, reason: &str) -> Result<Checkpoint, String> {
+    fs::create_dir_all(store).map_err(|e| format!("Failed to create checkpoint store: {}", e))?;
+    let timestamp = now_ms();
+    let id = format!("{:015}-{:04x}", timestamp as u64, rand::random::<u16>());
+    let (root, kind) = match git_root(dir) {
+        Some(root) => {
+            git_checkpoint(&root, store, &id)?;
+            (root, CheckpointKind::Git)
+        }
+        None => {
+            copy_checkpoint(dir, store, &id)?;
+            (dir.to_path_buf(), CheckpointKind::Copy)
+        }
+    };
+    let record = Checkpoint {
+        id,
+        timestamp,
+        backend: backend.to_string(),
+        session_id: None,
+        root: root.to_string_lossy().to_string(),
+        kind,
+        reason: reason.to_string(),
+        changes: Vec::new(),
+    };
+
+    let line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+    let index = store.join(INDEX_FILE_NAME);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&index)
+        .map_err(|e| format!("Failed to open checkpoint index: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write checkpoint index: {}", e))?;
+    state::restrict_to_owner(&index);
+
+    let records = read_index(store);
+    if records.len() > MAX_CHECKPOINTS {
+        let (dropped, kept) = records.split_at(records.len() - MAX_CHECKPOINTS);
+        for old in dropped {
+            delete_storage(store, old);
+        }
+        write_index(store, kept)?;
+        if dropped.iter().any(|r| r.kind == CheckpointKind::Copy) {
+            collect_objects(store, kept);
+        }
+    }
+    Ok(record)
+}
+
+/// Drop a checkpoint (the caller holds `LOCK`)
+fn discard_locked(store: &Path, id: &str) {
+    let mut records = read_index(store);
+    let Some(record) = records.iter().find(|r| r.id == id).cloned() else {
+        return;
+    };
+    delete_storage(store, &record);
+    records.retain(|r| r.id != id);
+    if let Err(e) = write_index(store, &records) {
+        log_line!("[Rust] {}", e);
+    }
+    if record.kind == CheckpointKind::Copy {
+        collect_objects(store, &records);
+    }
+}
+
+fn changes_since(store: &Path, record: &Checkpoint) -> Result<Vec<FileChange>, String> {
+    let root = Path::new(&record.root);
+    match record.kind {
+        CheckpointKind::Git => git_changes(root, store, &record.id),
+        CheckpointKind::Copy => Ok(manifest_changes(&load_manifest(store, &record.id)?, &scan(root, None)?)),
+    }
+}
+
+/// Remove an unfinished turn, returning its checkpoint
+fn take_active(backend: BackendMode, turn: u64) -> Option<Option<String>> {
+    let mut active = ACTIVE.lock().unwrap();
+    let index = active.iter().position(|(b, t, _)| *b == backend && *t == turn)?;
+    Some(active.remove(index).2)
+}
+
+/// Start a turn of `backend`, snapshotting `cwd` if given
+/// Returns the turn's token for `discard` and `finish_turn`.
+pub fn begin_turn(backend: BackendMode, cwd: Option<&str>) -> u64 {
+    let turn = NEXT_TURN.fetch_add(1, Ordering::Relaxed);
+    let checkpoint = match (cwd, get_checkpoints_dir()) {
+        (Some(cwd), Some(store)) => {
+            let _guard = LOCK.lock().unwrap();
+            match create(&store, Path::new(cwd), backend_name(backend), "turn") {
+                Ok(record) => {
+                    log_line!("[Rust] Took {:?} checkpoint {} of {}", record.kind, record.id, record.root);
+                    Some(record.id)
+                }
+                Err(e) => {
+                    log_line!("[Rust] Could not take a checkpoint of {}: {}", cwd, e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    ACTIVE.lock().unwrap().push((backend, turn, checkpoint));
+    turn
+}
+
+/// Drop the checkpoint of a turn that never started
+pub fn discard(backend: BackendMode, turn: u64) {
+    let (Some(Some(id)), Some(store)) = (take_active(backend, turn), get_checkpoints_dir()) else {
+        return;
+    };
+    let _guard = LOCK.lock().unwrap();
+    discard_locked(&store, &id);
+}
+
+/// The checkpoint of an unfinished turn, if one was taken
+pub fn active_turn(backend: BackendMode, turn: u64) -> Option<String> {
+    ACTIVE
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(b, t, _)| *b == backend && *t == turn)
+        .and_then(|(_, _, id)| id.clone())
+}
+
+/// Workspace root of a checkpoint, if it still exists
+pub fn root(id: &str) -> Option<PathBuf> {
+    let store = get_checkpoints_dir()?;
+    let _guard = LOCK.lock().unwrap();
+    let record = read_index(&store).into_iter().find(|r| r.id == id)?;
+    Some(PathBuf::from(record.root))
+}
+
+/// Content of a file when a checkpoint was taken
+/// `Some(None)` means the file did not exist; `None` that the checkpoint
+/// can't tell (it is gone, or the file is outside it or was too large).
+pub fn original_content(id: &str, path: &Path) -> Option<Option<Vec<u8>>> {
+    let store = get_checkpoints_dir()?;
+    let _guard = LOCK.lock().unwrap();
+    let record = read_index(&store).into_iter().find(|r| r.id == id)?;
+    let root = Path::new(&record.root);
+    let relative = path.strip_prefix(root).ok().map(Path::to_path_buf).or_else(|| {
+        let canonical_root = fs::canonicalize(root).ok()?;
+        let canonical = fs::canonicalize(path.parent()?).ok()?.join(path.file_name()?);
+        canonical.strip_prefix(canonical_root).ok().map(Path::to_path_buf)
+    })?;
+    let relative: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let relative = relative.join("/");
+
+    match record.kind {
+        CheckpointKind::Git => {
+            let object = format!("{}{}:{}", REF_PREFIX, id, relative);
+            match git_output(root, None, &["cat-file", "blob", object.as_str()]) {
+                Ok(data) => Some(Some(data)),
+                Err(_) => {
+                    let name = format!("{}{}", REF_PREFIX, id);
+                    git(root, None, &["rev-parse", "--verify", "-q", name.as_str()]).ok()?;
+                    Some(None)
+                }
+            }
+        }
+        CheckpointKind::Copy => {
+            let manifest = load_manifest(&store, id).ok()?;
+            match manifest.get(&relative) {
+                Some(hash) if hash == UNTRACKED => None,
+                Some(hash) => fs::read(store.join("objects").join(hash)).ok().map(Some),
+                None => Some(None),
+            }
+        }
+    }
+}
+
+/// Record what a turn changed and emit it as a `checkpoint-diff` event
+/// Called when the turn's result arrives and again when its process exits,
+/// so a stopped or crashed turn is recorded too; only the first call counts,
+/// and a call for a turn that was replaced by a newer one does nothing.
+pub fn finish_turn(app: &tauri::AppHandle, backend: BackendMode, turn: u64) {
+    let (Some(Some(id)), Some(store)) = (take_active(backend, turn), get_checkpoints_dir()) else {
+        return;
+    };
+    let id = id.as_str();
+    let _guard = LOCK.lock().unwrap();
+    let mut records = read_index(&store);
+    let Some(record) = records.iter_mut().find(|r| r.id == id) else {
+        return;
+    };
+    let changes = match changes_since(&store, record) {
+        Ok(changes) => changes,
+        Err(e) => {
+            log_line!("[Rust] Could not compare checkpoint {}: {}", id, e);
+            return;
+        }
+    };
+    if changes.is_empty() {
+        discard_locked(&store, id);
+        return;
+    }
+
+    record.session_id = session_for(backend);
+    record.changes = changes.clone();
+    let diff = CheckpointDiff {
+        checkpoint_id: record.id.clone(),
+        root: record.root.clone(),
+        changes,
+    };
+    if let Err(e) = write_index(&store, &records) {
+        log_line!("[Rust] {}", e);
+    }
+    log_line!("[Rust] Turn changed {} file(s) since checkpoint {}", diff.changes.len(), id);
+    let _ = app.emit("checkpoint-diff", &diff);
+}
+
+/// Checkpoints with recorded changes, newest first, optionally for one session
+pub fn list(session_id: Option<&str>) -> Vec<Checkpoint> {
+    let Some(store) = get_checkpoints_dir() else {
+        return Vec::new();
+    };
+    let _guard = LOCK.lock().unwrap();
+    let mut records: Vec<Checkpoint> = read_index(&store)
+        .into_iter()
+        .filter(|r| !r.changes.is_empty())
+        .filter(|r| session_id.is_none() || r.session_id.as_deref() == session_id)
+        .collect();
+    records.reverse();
+    records
+}
+
+/// Put the workspace back to a checkpoint, returning the files that changed
+/// The current state is kept as a "restore" checkpoint first, so a rollback
+/// can be undone the same way.
+pub fn restore(id: &str) -> Result<CheckpointDiff, String> {
+    let store = get_checkpoints_dir().ok_or("Could not find data directory")?;
+    let _guard = LOCK.lock().unwrap();
+    let record = read_index(&store)
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("No checkpoint with id '{}'", id))?;
+    let root = Path::new(&record.root);
+    if !root.is_dir() {
+        return Err(format!("{} no longer exists", record.root));
+    }
+
+    let changes = changes_since(&store, &record)?;
+    if changes.is_empty() {
+        return Ok(CheckpointDiff {
+            checkpoint_id: record.id,
+            root: record.root,
+            changes,
+        });
+    }
+    let safety = create(&store, root, &record.backend, "restore")?;
+    match record.kind {
+        CheckpointKind::Git => git_restore(root, &store, &record.id, &changes)?,
+        CheckpointKind::Copy => copy_restore(root, &store, &record.id, &changes)?,
+    }
+
+    // The safety checkpoint's changes are the rollback itself
+    let mut records = read_index(&store);
+    let undone = changes_since(&store, &safety).unwrap_or_default();
+    if let Some(saved) = records.iter_mut().find(|r| r.id == safety.id) {
+        saved.session_id = record.session_id.clone();
+        saved.changes = undone;
+    }
+    write_index(&store, &records)?;
+
+    log_line!("[Rust] Restored checkpoint {} ({} file(s))", id, changes.len());
+    Ok(CheckpointDiff {
+        checkpoint_id: record.id,
+        root: record.root,
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("supiki-checkpoints-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_get_checkpoints_dir() {
+        if let Some(dir) = get_checkpoints_dir() {
+            assert!(dir.ends_with("checkpoints"));
+            assert!(dir.to_string_lossy().contains("supiki"));
+        }
+    }
+
+    #[test]
+    fn test_parse_git_changes() {
+        let name_status = ["M", "src/lib.rs", "A", "new file.txt", "D", "old.png", "M", "메모\t\"1\".md", ""].join("\0");
+        let numstat = ["3\t1\tsrc/lib.rs", "2\t0\tnew file.txt", "-\t-\told.png", "1\t1\t메모\t\"1\".md", ""].join("\0");
+        let changes = parse_git_changes(&name_status, &numstat);
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes[0].status, ChangeStatus::Modified);
+        assert_eq!((changes[0].additions, changes[0].deletions), (Some(3), Some(1)));
+        assert_eq!(changes[1].path, "new file.txt");
+        assert_eq!(changes[1].status, ChangeStatus::Added);
+        assert_eq!(changes[2].status, ChangeStatus::Deleted);
+        assert_eq!(changes[2].additions, None);
+        // Non-ASCII names, tabs and quotes come through unquoted
+        assert_eq!(changes[3].path, "메모\t\"1\".md");
+        assert_eq!((changes[3].additions, changes[3].deletions), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn test_copy_checkpoint_round_trip() {
+        let workspace = test_dir("copy-ws");
+        let store = test_dir("copy-store");
+        fs::create_dir_all(workspace.join("src")).unwrap();
+        fs::create_dir_all(workspace.join("node_modules")).unwrap();
+        fs::write(workspace.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(workspace.join("notes.txt"), "keep").unwrap();
+        fs::write(workspace.join("node_modules/dep.js"), "skipped").unwrap();
+
+        copy_checkpoint(&workspace, &store, "a").unwrap();
+        fs::write(workspace.join("src/main.rs"), "fn main() { edited() }").unwrap();
+        fs::remove_file(workspace.join("notes.txt")).unwrap();
+        fs::write(workspace.join("new.txt"), "added").unwrap();
+
+        let changes = manifest_changes(&load_manifest(&store, "a").unwrap(), &scan(&workspace, None).unwrap());
+        let summary: Vec<(&str, ChangeStatus)> = changes.iter().map(|c| (c.path.as_str(), c.status)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("new.txt", ChangeStatus::Added),
+                ("notes.txt", ChangeStatus::Deleted),
+                ("src/main.rs", ChangeStatus::Modified),
+            ]
+        );
+
+        copy_restore(&workspace, &store, "a", &changes).unwrap();
+        assert_eq!(fs::read_to_string(workspace.join("src/main.rs")).unwrap(), "fn main() {}");
+        assert_eq!(fs::read_to_string(workspace.join("notes.txt")).unwrap(), "keep");
+        assert!(!workspace.join("new.txt").exists());
+        let _ = fs::remove_dir_all(&workspace);
+        let _ = fs::remove_dir_all(&store);
+    }
+
+    #[test]
+    fn test_copy_checkpoint_untracked_files() {
+        let workspace = test_dir("untracked-ws");
+        let store = test_dir("untracked-store");
+        fs::create_dir_all(&workspace).unwrap();
+        let large = vec![b'x'; MAX_FILE_SIZE as usize + 1];
+        fs::write(workspace.join("large.bin"), &large).unwrap();
+
+        copy_checkpoint(&workspace, &store, "a").unwrap();
+        let manifest = load_manifest(&store, "a").unwrap();
+        assert_eq!(manifest.get("large.bin").map(String::as_str), Some(UNTRACKED));
+
+        // A large file that shrinks is not mistaken for a new one and deleted
+        fs::write(workspace.join("large.bin"), "small").unwrap();
+        fs::write(workspace.join("huge.bin"), &large).unwrap();
+        let changes = manifest_changes(&manifest, &scan(&workspace, None).unwrap());
+        let summary: Vec<(&str, ChangeStatus)> = changes.iter().map(|c| (c.path.as_str(), c.status)).collect();
+        assert_eq!(summary, [("huge.bin", ChangeStatus::Added)]);
+
+        copy_restore(&workspace, &store, "a", &changes).unwrap();
+        assert!(workspace.join("large.bin").exists());
+        assert!(!workspace.join("huge.bin").exists());
+        let _ = fs::remove_dir_all(&workspace);
+        let _ = fs::remove_dir_all(&store);
+    }
+
+    #[test]
+    fn test_git_checkpoint_round_trip() {
+        let workspace = test_dir("git-ws");
+        let store = test_dir("git-store");
+        fs::create_dir_all(&workspace).unwrap();
+        fs::create_dir_all(&store).unwrap();
+        if git(&workspace, None, &["init"]).is_err() {
+            return;
+        }
+        fs::write(workspace.join("tracked.txt"), "one\n").unwrap();
+        git(&workspace, None, &["add", "tracked.txt"]).unwrap();
+        git(&workspace, None, &["commit", "-m", "init"]).unwrap();
+        fs::write(workspace.join("untracked.txt"), "draft\n").unwrap();
+
+        git_checkpoint(&workspace, &store, "a").unwrap();
+        fs::write(workspace.join("tracked.txt"), "one\ntwo\n").unwrap();
+        fs::remove_file(workspace.join("untracked.txt")).unwrap();
+        fs::write(workspace.join("added.txt"), "new\n").unwrap();
+
+        let changes = git_changes(&workspace, &store, "a").unwrap();
+        let tracked = changes.iter().find(|c| c.path == "tracked.txt").unwrap();
+        assert_eq!((tracked.status, tracked.additions), (ChangeStatus::Modified, Some(1)));
+        assert_eq!(changes.len(), 3);
+
+        git_restore(&workspace, &store, "a", &changes).unwrap();
+        assert_eq!(fs::read_to_string(workspace.join("tracked.txt")).unwrap(), "one\n");
+        assert_eq!(fs::read_to_string(workspace.join("untracked.txt")).unwrap(), "draft\n");
+        assert!(!workspace.join("added.txt").exists());
+        // The user's index and branch are untouched
+        assert_eq!(git(&workspace, None, &["status", "--porcelain"]).unwrap(), "?? untracked.txt");
+        let _ = fs::remove_dir_all(&workspace);
+        let _ = fs::remove_dir_all(&store);
+    }
+}
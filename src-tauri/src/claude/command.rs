@@ -47,6 +47,14 @@ impl ClaudeCommandBuilder {
         self
     }
 
+    pub fn with_disallowed_tools(mut self, tools: &[&str]) -> Self {
+        self.args.push("--disallowedTools".to_string());
+        for tool in tools {
+            self.args.push(tool.to_string());
+        }
+        self
+    }
+
     pub fn with_system_prompt(mut self, prompt: String) -> Self {
         self.args.push("--system-prompt".to_string());
         self.args.push(prompt);
@@ -66,6 +74,13 @@ impl ClaudeCommandBuilder {
         self
     }
 
+    /// Route permission requests for non-allowed tools to an MCP tool
+    pub fn with_permission_prompt_tool(mut self, tool: &str) -> Self {
+        self.args.push("--permission-prompt-tool".to_string());
+        self.args.push(tool.to_string());
+        self
+    }
+
     pub fn build(self) -> Vec<String> {
         self.args
     }
@@ -114,6 +129,27 @@ mod tests {
         assert!(args.contains(&"tool2".to_string()));
     }
 
+    #[test]
+    fn test_builder_with_disallowed_tools() {
+        let args = ClaudeCommandBuilder::new()
+            .with_disallowed_tools(&["Bash(rm:*)"])
+            .build();
+
+        assert_eq!(args, ["--disallowedTools", "Bash(rm:*)"]);
+    }
+
+    #[test]
+    fn test_builder_with_permission_prompt_tool() {
+        let args = ClaudeCommandBuilder::new()
+            .with_permission_prompt_tool("mcp__mascot__approve_permission")
+            .build();
+
+        assert_eq!(
+            args,
+            ["--permission-prompt-tool", "mcp__mascot__approve_permission"]
+        );
+    }
+
     #[test]
     fn test_builder_with_streaming_input() {
         let args = ClaudeCommandBuilder::new()
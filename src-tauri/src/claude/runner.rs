@@ -4,7 +4,7 @@
 //! Uses --print mode with streaming JSON output for real-time updates.
 //! Handles interactive tools (ExitPlanMode, AskUserQuestion) via bidirectional stdin/stdout.
 
-use std::io::{BufRead, BufReader, Cursor, Write};
+use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 use std::process::{ChildStdin, Command, Stdio};
 use std::sync::{Arc, Mutex};
@@ -14,12 +14,26 @@ use std::thread;
 use std::os::windows::process::CommandExt;
 
 use base64::prelude::*;
-use image::ImageFormat;
 use serde::{Deserialize, Serialize};
 use tauri::Emitter;
 
 use super::command::ClaudeCommandBuilder;
-use crate::state::{save_session_to_disk, DEV_MODE, SESSION_ID, SIDECAR_CWD, SUPIKI_MODE};
+use crate::audit;
+use crate::checkpoints;
+use crate::emotion::EmotionEvent;
+use crate::file_edits;
+use crate::guardrails;
+use crate::image_pipeline::{self, ImageOptions};
+use crate::movement::{self, MoveToRequest};
+use crate::policy;
+use crate::screenshots;
+use crate::sequences::{self, PlaySequenceRequest};
+use crate::state::{
+    save_session_to_disk, update_mascot_emotion, BackendMode, DEV_MODE, SESSION_ID, SIDECAR_CWD, SUPIKI_MODE,
+};
+use crate::tempfiles::{self, TempOwner};
+use crate::tts::{self, SpeakRequest};
+use crate::utterance::{self, SayRequest};
 
 /// Global stdin handle for sending responses to Claude CLI
 static CLAUDE_STDIN: std::sync::LazyLock<Arc<Mutex<Option<ChildStdin>>>> =
@@ -139,7 +153,7 @@ fn get_mcp_exe_path(_app: &tauri::AppHandle) -> Option<PathBuf> {
 }
 
 /// Write the MCP config file with the current executable path and --mcp flag
-fn write_mcp_config(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+fn write_mcp_config(app: &tauri::AppHandle, owner: TempOwner) -> Result<PathBuf, String> {
     let mcp_exe_path =
         get_mcp_exe_path(app).ok_or("Could not find current executable")?;
 
@@ -165,12 +179,16 @@ fn write_mcp_config(app: &tauri::AppHandle) -> Result<PathBuf, String> {
         }
     });
 
-    // Write to temp directory
-    let config_path = std::env::temp_dir().join("mascot-mcp.json");
-    std::fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
-        .map_err(|e| format!("Failed to write MCP config: {}", e))?;
+    // Private per-turn file, removed when the turn ends
+    let config_path = tempfiles::create(
+        owner,
+        "mcp-config",
+        "json",
+        serde_json::to_string_pretty(&config).unwrap().as_bytes(),
+    )
+    .map_err(|e| format!("Failed to write MCP config: {}", e))?;
 
-    eprintln!("[Rust] Wrote MCP config to {:?}", config_path);
+    log_line!("[Rust] Wrote MCP config to {:?}", config_path);
     Ok(config_path)
 }
 
@@ -195,54 +213,40 @@ fn get_system_prompt() -> String {
     }
 }
 
-/// Convert a base64 image to WebP format for smaller size
+/// Downscale and re-encode a base64 image through the shared image pipeline
 /// Returns (media_type, base64_data) tuple
-fn convert_to_webp(base64_data: &str, original_media_type: &str) -> (String, String) {
-    // Skip conversion if already WebP
-    if original_media_type == "image/webp" {
-        return ("image/webp".to_string(), base64_data.to_string());
-    }
+fn prepare_image(base64_data: &str, original_media_type: &str) -> (String, String) {
+    let image_bytes = match BASE64_STANDARD.decode(base64_data) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log_line!("[Rust] Failed to decode base64: {}, using original", e);
+            return (original_media_type.to_string(), base64_data.to_string());
+        }
+    };
 
-    // Try to decode and convert
-    match BASE64_STANDARD.decode(base64_data) {
-        Ok(image_bytes) => {
-            let original_size = image_bytes.len();
-
-            // Try to load the image
-            match image::load_from_memory(&image_bytes) {
-                Ok(img) => {
-                    // Encode as WebP
-                    let mut webp_buffer = Cursor::new(Vec::new());
-                    match img.write_to(&mut webp_buffer, ImageFormat::WebP) {
-                        Ok(()) => {
-                            let webp_bytes = webp_buffer.into_inner();
-                            let new_size = webp_bytes.len();
-                            let savings = if original_size > 0 {
-                                100.0 - (new_size as f64 / original_size as f64 * 100.0)
-                            } else {
-                                0.0
-                            };
-                            eprintln!(
-                                "[Rust] Converted image to WebP: {} -> {} bytes ({:.1}% smaller)",
-                                original_size, new_size, savings
-                            );
-                            let webp_base64 = BASE64_STANDARD.encode(&webp_bytes);
-                            ("image/webp".to_string(), webp_base64)
-                        }
-                        Err(e) => {
-                            eprintln!("[Rust] Failed to encode WebP: {}, using original", e);
-                            (original_media_type.to_string(), base64_data.to_string())
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("[Rust] Failed to load image: {}, using original", e);
-                    (original_media_type.to_string(), base64_data.to_string())
-                }
-            }
+    let img = match image::load_from_memory(&image_bytes) {
+        Ok(img) => img,
+        Err(e) => {
+            log_line!("[Rust] Failed to load image: {}, using original", e);
+            return (original_media_type.to_string(), base64_data.to_string());
+        }
+    };
+
+    match image_pipeline::process(img, &ImageOptions::for_attachments()) {
+        Ok(encoded) => {
+            log_line!(
+                "[Rust] Encoded image as {}: {} -> {} bytes, {}x{} (~{} tokens)",
+                encoded.media_type,
+                image_bytes.len(),
+                encoded.data.len(),
+                encoded.width,
+                encoded.height,
+                encoded.estimated_tokens()
+            );
+            (encoded.media_type.to_string(), encoded.to_base64())
         }
         Err(e) => {
-            eprintln!("[Rust] Failed to decode base64: {}, using original", e);
+            log_line!("[Rust] {}, using original", e);
             (original_media_type.to_string(), base64_data.to_string())
         }
     }
@@ -280,7 +284,7 @@ pub fn send_tool_result(tool_use_id: &str, content: &str, is_error: bool) -> Res
         });
 
         let json_str = message.to_string();
-        eprintln!("[Rust] Sending tool result: {}", json_str);
+        log_content!("[Rust] Sending tool result: {}", json_str);
 
         stdin
             .write_all(json_str.as_bytes())
@@ -332,7 +336,7 @@ pub fn send_ask_user_question_result(
         });
 
         let json_str = message.to_string();
-        eprintln!("[Rust] Sending AskUserQuestion result: {}", json_str);
+        log_content!("[Rust] Sending AskUserQuestion result: {}", json_str);
 
         stdin
             .write_all(json_str.as_bytes())
@@ -413,8 +417,8 @@ fn build_stream_json_message(prompt: &str, images: &[String]) -> String {
             ("image/png".to_string(), image.clone())
         };
 
-        // Convert to WebP for smaller size
-        let (media_type, data) = convert_to_webp(&raw_data, &original_media_type);
+        // Re-encode with a size cap
+        let (media_type, data) = prepare_image(&raw_data, &original_media_type);
 
         content.push(serde_json::json!({
             "type": "image",
@@ -441,7 +445,8 @@ fn build_stream_json_message(prompt: &str, images: &[String]) -> String {
 /// Returns immediately after spawning - results come via Tauri events
 pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) -> Result<(), String> {
     // Write MCP config with correct executable path
-    let mcp_config_path = write_mcp_config(&app)?;
+    let temp_owner = tempfiles::new_turn();
+    let mcp_config_path = write_mcp_config(&app, temp_owner)?;
 
     // Get session ID and dev mode state
     let session_id = SESSION_ID.lock().unwrap().clone();
@@ -455,16 +460,25 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
         .with_streaming_input()
         .with_mcp_config(&mcp_config_path);
 
-    // In dev mode, allow all tools and skip permission prompts
-    // In normal mode, restrict to only mascot MCP tools
-    if is_dev {
+    // Translate the permission policy for the workspace; tools it neither
+    // allows nor denies outright are decided by the permission prompt tool
+    let custom_cwd = SIDECAR_CWD.lock().unwrap().clone();
+    let mut permissions = policy::effective_policy(custom_cwd.as_deref(), is_dev).claude;
+    if permissions.skip_permissions && !guardrails::allow_unattended_turn(&app, custom_cwd.as_deref()) {
+        permissions.skip_permissions = false;
+    }
+    let allowed: Vec<&str> = permissions.allowed_tools.iter().map(String::as_str).collect();
+    let disallowed: Vec<&str> = permissions.disallowed_tools.iter().map(String::as_str).collect();
+    if !allowed.is_empty() {
+        builder = builder.with_allowed_tools(&allowed);
+    }
+    if !disallowed.is_empty() {
+        builder = builder.with_disallowed_tools(&disallowed);
+    }
+    if permissions.skip_permissions {
         builder = builder.with_skip_permissions();
     } else {
-        builder = builder.with_allowed_tools(&[
-            "mcp__mascot__set_emotion",
-            "mcp__mascot__move_to",
-            "mcp__mascot__capture_screenshot",
-        ]);
+        builder = builder.with_permission_prompt_tool("mcp__mascot__approve_permission");
     }
 
     builder = builder
@@ -476,7 +490,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
 
     let args = builder.build();
 
-    eprintln!(
+    log_line!(
         "[Rust] Running claude CLI with {} args, images={}",
         args.len(),
         images.len()
@@ -498,33 +512,48 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
     }
 
     // Set working directory if custom cwd is set
-    let custom_cwd = SIDECAR_CWD.lock().unwrap().clone();
     if let Some(ref cwd) = custom_cwd {
         cmd.current_dir(cwd);
-        eprintln!("[Rust] Using custom CWD: {}", cwd);
+        log_line!("[Rust] Using custom CWD: {}", cwd);
     }
 
+    // Snapshot the workspace so the turn's edits can be rolled back, unless the policy denies every way to edit it
+    let checkpoint_cwd = if permissions.can_write {
+        custom_cwd.as_deref()
+    } else {
+        None
+    };
+    let turn = checkpoints::begin_turn(BackendMode::Claude, checkpoint_cwd);
+    file_edits::begin_turn(BackendMode::Claude, turn);
+
     // Spawn the process
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn claude CLI: {}. Is Claude Code installed?", e))?;
+    let mut child = cmd.spawn().map_err(|e| {
+        tempfiles::release(temp_owner);
+        checkpoints::discard(BackendMode::Claude, turn);
+        file_edits::discard(BackendMode::Claude, turn);
+        format!("Failed to spawn claude CLI: {}. Is Claude Code installed?", e)
+    })?;
+    let audit_turn = audit::begin_turn(
+        BackendMode::Claude,
+        serde_json::to_value(&permissions).unwrap_or_default(),
+    );
 
     // Take stdin and store it globally for interactive tool responses
     if let Some(mut stdin) = child.stdin.take() {
         // Send the initial user message
         let message = build_stream_json_message(&prompt, &images);
-        eprintln!(
+        log_line!(
             "[Rust] Sending stream-json message with {} images",
             images.len()
         );
         if let Err(e) = stdin.write_all(message.as_bytes()) {
-            eprintln!("[Rust] Failed to write to stdin: {}", e);
+            log_line!("[Rust] Failed to write to stdin: {}", e);
         }
         if let Err(e) = stdin.write_all(b"\n") {
-            eprintln!("[Rust] Failed to write newline: {}", e);
+            log_line!("[Rust] Failed to write newline: {}", e);
         }
         if let Err(e) = stdin.flush() {
-            eprintln!("[Rust] Failed to flush stdin: {}", e);
+            log_line!("[Rust] Failed to flush stdin: {}", e);
         }
 
         // Store stdin globally for later interactive tool responses
@@ -543,7 +572,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
             let line = match line {
                 Ok(l) => l,
                 Err(e) => {
-                    eprintln!("[Rust] Error reading stdout: {}", e);
+                    log_line!("[Rust] Error reading stdout: {}", e);
                     break;
                 }
             };
@@ -555,17 +584,17 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
             // Try to parse as JSON
             match serde_json::from_str::<StreamEvent>(&line) {
                 Ok(event) => {
-                    handle_stream_event(&app_handle, event);
+                    handle_stream_event(&app_handle, event, turn);
                 }
                 Err(_) => {
                     // Not JSON, might be raw text or error
-                    eprintln!("[Rust] Non-JSON line: {}", line);
+                    log_content!("[Rust] Non-JSON line: {}", line);
                 }
             }
         }
 
         // Wait for process to complete
-        match child.wait() {
+        let outcome = match child.wait() {
             Ok(status) => {
                 if !status.success() {
                     let _ = app_handle.emit(
@@ -575,6 +604,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
                         }),
                     );
                 }
+                format!("process {}", status)
             }
             Err(e) => {
                 let _ = app_handle.emit(
@@ -583,13 +613,21 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
                         "error": format!("Failed to wait for claude CLI: {}", e)
                     }),
                 );
+                "process lost".to_string()
             }
-        }
+        };
+
+        // Close the audit turn of a stopped or crashed process (no-op after a result)
+        audit::end_turn_on_exit(BackendMode::Claude, &audit_turn, &outcome);
 
-        // Clean up global stdin reference
+        // Clean up global stdin reference and the turn's temp files
         *CLAUDE_STDIN.lock().unwrap() = None;
+        tempfiles::release(temp_owner);
 
-        eprintln!("[Rust] Claude CLI process ended");
+        // Report what a stopped or crashed turn changed (no-op after a result)
+        finish_turn_changes(&app_handle, turn);
+
+        log_line!("[Rust] Claude CLI process ended");
     });
 
     // Spawn thread to read stderr for logging
@@ -598,7 +636,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 if let Ok(line_content) = line {
-                    eprintln!("[Claude] {}", line_content);
+                    log_content!("[Claude] {}", line_content);
                 }
             }
         });
@@ -608,10 +646,10 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
 }
 
 /// Handle a streaming event from Claude CLI
-fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
+fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent, turn: u64) {
     match event {
         StreamEvent::System { session_id, subtype } => {
-            eprintln!(
+            log_line!(
                 "[Rust] System event: subtype={:?}, session_id={:?}",
                 subtype, session_id
             );
@@ -624,11 +662,14 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
             for block in message.content {
                 match block {
                     ContentBlock::Text { text } => {
-                        eprintln!("[Rust] Emitting agent-stream with {} chars", text.len());
+                        log_line!("[Rust] Emitting agent-stream with {} chars", text.len());
                         let _ = app.emit("agent-stream", &text);
                     }
                     ContentBlock::ToolUse { id, name, input } => {
-                        eprintln!("[Rust] Tool use: {} with input: {:?}", name, input);
+                        log_line!("[Rust] Tool use: {}", name);
+                        log_content!("[Rust] Tool input: {}", input);
+                        audit::record_tool_use(BackendMode::Claude, &name, &input);
+                        file_edits::track_claude_tool(&name, &input);
 
                         // Handle Task tool - emit subagent start event
                         if name == "Task" {
@@ -638,7 +679,7 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
                                 .unwrap_or("task")
                                 .to_string();
                             let task_id = format!("{}_{}", id.clone(), description.replace(' ', "_"));
-                            eprintln!("[Rust] Task tool detected, emitting subagent-start: {}", task_id);
+                            log_line!("[Rust] Task tool detected, emitting subagent-start: {}", task_id);
 
                             // Track this subagent
                             if let Ok(mut subagents) = ACTIVE_SUBAGENTS.lock() {
@@ -656,7 +697,7 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
 
                         // Handle interactive tools that need user response
                         if name == "ExitPlanMode" {
-                            eprintln!("[Rust] ExitPlanMode tool detected, emitting event");
+                            log_line!("[Rust] ExitPlanMode tool detected, emitting event");
                             let _ = app.emit(
                                 "agent-exit-plan-mode",
                                 ExitPlanModeEvent {
@@ -664,7 +705,7 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
                                 },
                             );
                         } else if name == "AskUserQuestion" {
-                            eprintln!("[Rust] AskUserQuestion tool detected, emitting event");
+                            log_line!("[Rust] AskUserQuestion tool detected, emitting event");
                             // Parse questions from input
                             let questions: Vec<QuestionData> = input
                                 .get("questions")
@@ -681,12 +722,43 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
                             );
                         } else if name.contains("set_emotion") {
                             // Emit specific events based on MCP tool
-                            let _ = app.emit("agent-emotion", &input);
+                            match EmotionEvent::from_tool_input(&input) {
+                                Ok(event) => {
+                                    update_mascot_emotion(event.emotion.as_str());
+                                    let _ = app.emit("agent-emotion", &event);
+                                }
+                                Err(e) => log_line!("[Rust] Ignoring set_emotion: {}", e),
+                            }
                         } else if name.contains("move_to") {
-                            let _ = app.emit("clawd-move", &input);
+                            let moved = MoveToRequest::from_tool_input(&input)
+                                .and_then(|req| movement::emit_move(app, &req));
+                            if let Err(e) = moved {
+                                log_line!("[Rust] Ignoring move_to: {}", e);
+                            }
+                        } else if name.ends_with("__say") {
+                            let said = SayRequest::from_tool_input(&input)
+                                .and_then(|req| utterance::emit_say(app, &req));
+                            if let Err(e) = said {
+                                log_line!("[Rust] Ignoring say: {}", e);
+                            }
+                        } else if name.ends_with("__speak") {
+                            let text = SpeakRequest::from_tool_input(&input)
+                                .and_then(|req| req.validate().map(String::from));
+                            match text {
+                                Ok(text) => tts::speak(app, &text),
+                                Err(e) => log_line!("[Rust] Ignoring speak: {}", e),
+                            }
+                        } else if name.ends_with("__play_sequence") {
+                            let played = PlaySequenceRequest::from_tool_input(&input)
+                                .and_then(|req| sequences::emit_sequence(app, &req));
+                            if let Err(e) = played {
+                                log_line!("[Rust] Ignoring play_sequence: {}", e);
+                            }
+                        } else if name.ends_with("__cancel_sequence") {
+                            let _ = sequences::emit_cancel(app);
                         } else if name.contains("capture_screenshot") {
                             // Screenshot handling would go here
-                            eprintln!("[Rust] Screenshot requested");
+                            log_line!("[Rust] Screenshot requested");
                         }
 
                         // Also emit a generic tool-use event
@@ -706,7 +778,7 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
             result,
             session_id,
         } => {
-            eprintln!(
+            log_line!(
                 "[Rust] Result: subtype={:?}, session_id={:?}",
                 subtype, session_id
             );
@@ -720,7 +792,7 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
             // Emit subagent-end for all active subagents when conversation turn completes
             if let Ok(mut subagents) = ACTIVE_SUBAGENTS.lock() {
                 for task_id in subagents.drain(..) {
-                    eprintln!("[Rust] Emitting subagent-end for: {}", task_id);
+                    log_line!("[Rust] Emitting subagent-end for: {}", task_id);
                     let _ = app.emit(
                         "subagent-end",
                         SubagentEndEvent {
@@ -731,11 +803,18 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
             }
 
             // Emit result event
+            let success = subtype.as_deref() == Some("success");
+            audit::end_turn(BackendMode::Claude, subtype.as_deref().unwrap_or("unknown"));
+            finish_turn_changes(app, turn);
+            let text = result.unwrap_or_default();
+            if success {
+                tts::read_answer(app, &text);
+            }
             let _ = app.emit(
                 "agent-result",
                 serde_json::json!({
-                    "success": subtype.as_deref() == Some("success"),
-                    "text": result.unwrap_or_default()
+                    "success": success,
+                    "text": text
                 }),
             );
         }
@@ -745,10 +824,17 @@ fn handle_stream_event(app: &tauri::AppHandle, event: StreamEvent) {
     }
 }
 
+/// Report the turn's file edits and workspace changes (once per turn)
+fn finish_turn_changes(app: &tauri::AppHandle, turn: u64) {
+    file_edits::finish_turn(app, BackendMode::Claude, turn);
+    checkpoints::finish_turn(app, BackendMode::Claude, turn);
+}
+
 /// Clear the current session
 pub fn clear_session() {
     *SESSION_ID.lock().unwrap() = None;
-    eprintln!("[Rust] Session cleared");
+    screenshots::revoke_session_grant();
+    log_line!("[Rust] Session cleared");
 }
 
 /// Check if claude CLI is available
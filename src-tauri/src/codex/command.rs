@@ -10,6 +10,15 @@ const DEFAULT_MODEL: &str = "gpt-5.2";
 /// Default reasoning effort level
 const DEFAULT_REASONING_EFFORT: &str = "high";
 
+/// Quote a value as a TOML basic string
+fn toml_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
 /// Builder for Codex CLI command arguments
 pub struct CodexCommandBuilder {
     args: Vec<String>,
@@ -35,11 +44,31 @@ impl CodexCommandBuilder {
         self
     }
 
-    pub fn with_full_auto(mut self) -> Self {
-        self.args.push("--full-auto".to_string());
+    /// Sandbox policy for model-generated commands (read-only, workspace-write, ...)
+    pub fn with_sandbox(mut self, mode: &str) -> Self {
+        self.args.push("--sandbox".to_string());
+        self.args.push(mode.to_string());
         self
     }
 
+    /// Network access for commands in the workspace-write sandbox
+    pub fn with_network_access(self, enabled: bool) -> Self {
+        self.with_config("sandbox_workspace_write.network_access", &enabled.to_string())
+    }
+
+    /// Add an MCP server for this run only (config.toml is left alone)
+    pub fn with_mcp_server(self, name: &str, command: &str, args: &[&str]) -> Self {
+        let args: Vec<String> = args.iter().map(|a| toml_string(a)).collect();
+        self.with_config(&format!("mcp_servers.{}.command", name), &toml_string(command))
+            .with_config(&format!("mcp_servers.{}.args", name), &format!("[{}]", args.join(",")))
+    }
+
+    /// Extra directories the workspace-write sandbox may write to
+    pub fn with_writable_roots(self, roots: &[String]) -> Self {
+        let roots: Vec<String> = roots.iter().map(|r| toml_string(r)).collect();
+        self.with_config("sandbox_workspace_write.writable_roots", &format!("[{}]", roots.join(",")))
+    }
+
     pub fn with_skip_git_repo_check(mut self) -> Self {
         self.args.push("--skip-git-repo-check".to_string());
         self
@@ -57,12 +86,7 @@ impl CodexCommandBuilder {
     }
 
     pub fn with_system_prompt(self, prompt: &str) -> Self {
-        // Escape quotes and newlines for TOML string
-        let escaped = prompt
-            .replace('\\', "\\\\")
-            .replace('"', "\\\"")
-            .replace('\n', "\\n");
-        self.with_config("developer_instructions", &format!("\"{}\"", escaped))
+        self.with_config("developer_instructions", &toml_string(prompt))
     }
 
     pub fn with_working_directory(mut self, cwd: Option<&String>) -> Self {
@@ -108,14 +132,29 @@ mod tests {
     }
 
     #[test]
-    fn test_builder_with_json_and_full_auto() {
+    fn test_builder_with_json_output() {
+        let args = CodexCommandBuilder::new().with_json_output().build();
+
+        assert!(args.contains(&"--json".to_string()));
+        assert!(!args.contains(&"--full-auto".to_string()));
+    }
+
+    #[test]
+    fn test_builder_with_sandbox() {
+        let args = CodexCommandBuilder::new().with_sandbox("read-only").build();
+
+        assert_eq!(args, ["exec", "--sandbox", "read-only"]);
+    }
+
+    #[test]
+    fn test_builder_with_workspace_write_options() {
         let args = CodexCommandBuilder::new()
-            .with_json_output()
-            .with_full_auto()
+            .with_network_access(false)
+            .with_writable_roots(&["C:\\cache".to_string(), "/tmp/a\"b".to_string()])
             .build();
 
-        assert!(args.contains(&"--json".to_string()));
-        assert!(args.contains(&"--full-auto".to_string()));
+        assert!(args.contains(&"sandbox_workspace_write.network_access=false".to_string()));
+        assert!(args.contains(&r#"sandbox_workspace_write.writable_roots=["C:\\cache","/tmp/a\"b"]"#.to_string()));
     }
 
     #[test]
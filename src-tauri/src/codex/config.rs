@@ -0,0 +1,155 @@
+//! Codex config.toml handling
+//!
+//! The mascot MCP server is passed to Codex as `--config mcp_servers.mascot.*`
+//! overrides on every run, so the user's `config.toml` is never modified.
+//! Earlier versions appended a `[mcp_servers.mascot]` table to it; `uninstall`
+//! removes that entry with a format-preserving TOML editor, after backing the
+//! file up, and checks the result before keeping it.
+
+use std::fs;
+use std::path::PathBuf;
+
+use toml_edit::{DocumentMut, Item};
+
+use super::command::CodexCommandBuilder;
+
+/// Name of the mascot server in Codex's `mcp_servers`
+pub const MCP_SERVER_NAME: &str = "mascot";
+
+/// Suffix of the backup written before the first edit
+const BACKUP_SUFFIX: &str = "supiki-backup";
+
+/// Codex's config file (`$CODEX_HOME/config.toml`, by default in `~/.codex`)
+pub fn get_codex_config_path() -> Option<PathBuf> {
+    let codex_home = std::env::var_os("CODEX_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".codex")))?;
+    Some(codex_home.join("config.toml"))
+}
+
+/// Point Codex at the mascot MCP server for one run
+pub fn with_mascot_server(builder: CodexCommandBuilder, exe: &str) -> CodexCommandBuilder {
+    builder.with_mcp_server(MCP_SERVER_NAME, exe, &["--mcp"])
+}
+
+/// Remove the mascot server from a config, keeping everything else as written
+/// Returns None when the config has no mascot entry.
+pub fn remove_mascot_entry(content: &str) -> Result<Option<String>, String> {
+    let mut doc: DocumentMut = content
+        .parse()
+        .map_err(|e| format!("Codex config is not valid TOML: {}", e))?;
+    let removed = match doc.get_mut("mcp_servers") {
+        Some(Item::Table(servers)) => servers.remove(MCP_SERVER_NAME).is_some(),
+        Some(Item::Value(toml_edit::Value::InlineTable(servers))) => servers.remove(MCP_SERVER_NAME).is_some(),
+        _ => false,
+    };
+    if !removed {
+        return Ok(None);
+    }
+    let updated = doc.to_string();
+    validate(&updated)?;
+    Ok(Some(updated))
+}
+
+/// Check that a config parses and has no mascot entry
+fn validate(content: &str) -> Result<(), String> {
+    let doc: DocumentMut = content
+        .parse()
+        .map_err(|e| format!("Edited Codex config is not valid TOML: {}", e))?;
+    let still_there = doc
+        .get("mcp_servers")
+        .and_then(|servers| servers.get(MCP_SERVER_NAME))
+        .is_some();
+    if still_there {
+        return Err("The mascot entry is still in the edited Codex config".to_string());
+    }
+    Ok(())
+}
+
+/// Remove the mascot entry earlier versions added to the user's config.toml
+/// Returns whether an entry was removed.
+pub fn uninstall() -> Result<bool, String> {
+    let path = get_codex_config_path().ok_or("Could not find the Codex config directory")?;
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(false);
+    };
+    let Some(updated) = remove_mascot_entry(&content)? else {
+        return Ok(false);
+    };
+
+    let backup = path.with_extension(format!("toml.{}", BACKUP_SUFFIX));
+    if !backup.exists() {
+        fs::write(&backup, &content).map_err(|e| format!("Failed to back up Codex config: {}", e))?;
+    }
+    fs::write(&path, &updated).map_err(|e| format!("Failed to write Codex config: {}", e))?;
+
+    // Put the original back if the file did not end up as intended
+    let written = fs::read_to_string(&path).unwrap_or_default();
+    if written != updated || validate(&written).is_err() {
+        fs::write(&path, &content).map_err(|e| format!("Failed to restore Codex config: {}", e))?;
+        return Err("Codex config could not be updated; the original was restored".to_string());
+    }
+
+    log_line!("[Rust] Removed the mascot MCP server from {:?} (backup: {:?})", path, backup);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_table_entry_keeps_the_rest() {
+        let config = r#"# my settings
+model = "o3" # inline comment
+
+[mcp_servers.mascot]
+command = "C:\\supiki\\supiki.exe"
+args = ["--mcp"]
+
+[mcp_servers.other]
+command = "other"
+"#;
+        let updated = remove_mascot_entry(config).unwrap().unwrap();
+        assert_eq!(
+            updated,
+            r#"# my settings
+model = "o3" # inline comment
+
+[mcp_servers.other]
+command = "other"
+"#
+        );
+    }
+
+    #[test]
+    fn test_remove_inline_table_entry() {
+        let config = r#"mcp_servers = { mascot = { command = "x" }, other = { command = "y" } }
+"#;
+        let updated = remove_mascot_entry(config).unwrap().unwrap();
+        assert!(!updated.contains("mascot"));
+        assert!(updated.contains(r#"other = { command = "y" }"#));
+    }
+
+    #[test]
+    fn test_remove_missing_or_invalid() {
+        assert_eq!(remove_mascot_entry("model = \"o3\"\n").unwrap(), None);
+        assert_eq!(remove_mascot_entry("").unwrap(), None);
+        assert!(remove_mascot_entry("[mcp_servers.mascot\n").is_err());
+    }
+
+    #[test]
+    fn test_with_mascot_server() {
+        let args = with_mascot_server(CodexCommandBuilder::new(), "/opt/supiki/supiki").build();
+        assert_eq!(
+            args,
+            [
+                "exec",
+                "--config",
+                "mcp_servers.mascot.command=\"/opt/supiki/supiki\"",
+                "--config",
+                "mcp_servers.mascot.args=[\"--mcp\"]",
+            ]
+        );
+    }
+}
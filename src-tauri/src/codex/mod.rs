@@ -3,6 +3,10 @@
 //! This module provides the OpenAI Codex CLI integration for the mascot application.
 
 mod command;
+mod config;
 mod runner;
+mod sandbox;
 
 pub use runner::{check_codex_available, check_codex_available_with_app, clear_session, run_query};
+pub use config::uninstall as uninstall_mcp_config;
+pub use sandbox::{sandbox_status, CodexSandboxStatus};
@@ -3,7 +3,6 @@
 //! Spawns the `codex` CLI process and streams responses back via Tauri events.
 //! Uses exec mode with JSON output for machine-readable streaming.
 
-use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
@@ -17,8 +16,25 @@ use serde::Deserialize;
 use tauri::{Emitter, Manager};
 
 use super::command::CodexCommandBuilder;
+use super::config;
+use super::sandbox;
+use crate::audit;
+use crate::checkpoints;
 use crate::claude::ToolUseEvent;
-use crate::state::{save_codex_session_to_disk, CODEX_SESSION_ID, DEV_MODE, SIDECAR_CWD, SUPIKI_MODE};
+use crate::emotion::EmotionEvent;
+use crate::file_edits;
+use crate::guardrails;
+use crate::movement::{self, MoveToRequest};
+use crate::screenshots;
+use crate::sequences::{self, PlaySequenceRequest};
+use crate::settings::CodexSandbox;
+use crate::state::{
+    save_codex_session_to_disk, update_mascot_emotion, BackendMode, CODEX_SESSION_ID, DEV_MODE, SIDECAR_CWD,
+    SUPIKI_MODE,
+};
+use crate::tempfiles::{self, TempOwner};
+use crate::tts::{self, SpeakRequest};
+use crate::utterance::{self, SayRequest};
 
 /// Codex JSONL event types
 #[derive(Debug, Deserialize)]
@@ -119,6 +135,33 @@ pub enum CodexItem {
         #[serde(default)]
         text: Option<String>,
     },
+
+    #[serde(rename = "command_execution")]
+    CommandExecution {
+        #[serde(default)]
+        command: Option<String>,
+        #[serde(default)]
+        exit_code: Option<i32>,
+        #[serde(default)]
+        status: Option<String>,
+    },
+
+    #[serde(rename = "file_change")]
+    FileChange {
+        #[serde(default)]
+        changes: Vec<CodexFileUpdate>,
+        #[serde(default)]
+        status: Option<String>,
+    },
+}
+
+/// A file touched by a Codex file_change item
+#[derive(Debug, Deserialize, Clone)]
+pub struct CodexFileUpdate {
+    pub path: String,
+    /// "add", "update" or "delete"
+    #[serde(default)]
+    pub kind: Option<String>,
 }
 
 /// Codex content block
@@ -186,95 +229,27 @@ fn get_mcp_exe_path(_app: &tauri::AppHandle) -> Option<PathBuf> {
     std::env::current_exe().ok()
 }
 
-/// Write the MCP config to ~/.codex/config.toml
-/// This merges with existing config to avoid overwriting user settings
-fn write_codex_mcp_config(app: &tauri::AppHandle) -> Result<(), String> {
-    let mcp_exe_path =
-        get_mcp_exe_path(app).ok_or("Could not find current executable")?;
-
+/// Path of the executable as Codex should launch it for the MCP server
+fn mcp_exe_string(app: &tauri::AppHandle) -> Result<String, String> {
+    let mcp_exe_path = get_mcp_exe_path(app).ok_or("Could not find current executable")?;
     let mcp_exe_str = mcp_exe_path
         .canonicalize()
-        .unwrap_or(mcp_exe_path.clone())
+        .unwrap_or(mcp_exe_path)
         .to_string_lossy()
         .to_string();
 
     // Remove \\?\ prefix on Windows
-    let mcp_exe_str = if mcp_exe_str.starts_with(r"\\?\") {
-        mcp_exe_str[4..].to_string()
-    } else {
-        mcp_exe_str
-    };
-
-    // Escape backslashes for TOML
-    let mcp_exe_str = mcp_exe_str.replace('\\', "\\\\");
-
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
-    let codex_config_dir = home.join(".codex");
-    let config_path = codex_config_dir.join("config.toml");
-
-    // Create config directory if needed
-    fs::create_dir_all(&codex_config_dir)
-        .map_err(|e| format!("Failed to create .codex directory: {}", e))?;
-
-    // Read existing config or create empty
-    let existing_config = if config_path.exists() {
-        fs::read_to_string(&config_path).unwrap_or_default()
-    } else {
-        String::new()
-    };
-
-    // Check if mascot server is already configured
-    if existing_config.contains("[mcp_servers.mascot]") {
-        // Update existing mascot config
-        let mut lines: Vec<String> = existing_config.lines().map(String::from).collect();
-        let mut in_mascot_section = false;
-        let mut command_updated = false;
-
-        for line in &mut lines {
-            if line.trim() == "[mcp_servers.mascot]" {
-                in_mascot_section = true;
-            } else if line.trim().starts_with('[') && in_mascot_section {
-                in_mascot_section = false;
-            } else if in_mascot_section && line.trim().starts_with("command") {
-                *line = format!("command = \"{}\"", mcp_exe_str);
-                command_updated = true;
-            }
-        }
-
-        if !command_updated && in_mascot_section {
-            // Find the mascot section and add command after it
-            for (i, line) in lines.clone().iter().enumerate() {
-                if line.trim() == "[mcp_servers.mascot]" {
-                    lines.insert(i + 1, format!("command = \"{}\"", mcp_exe_str));
-                    break;
-                }
-            }
-        }
-
-        fs::write(&config_path, lines.join("\n"))
-            .map_err(|e| format!("Failed to write config: {}", e))?;
-    } else {
-        // Append new mascot server config
-        let mascot_config = format!(
-            "\n[mcp_servers.mascot]\ncommand = \"{}\"\nargs = [\"--mcp\"]\n",
-            mcp_exe_str
-        );
-
-        let new_config = format!("{}{}", existing_config, mascot_config);
-        fs::write(&config_path, new_config)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
-    }
-
-    eprintln!("[Rust] Wrote Codex MCP config to {:?}", config_path);
-    Ok(())
+    Ok(match mcp_exe_str.strip_prefix(r"\\?\") {
+        Some(stripped) => stripped.to_string(),
+        None => mcp_exe_str,
+    })
 }
 
-/// Save base64 images to temp files for Codex (which needs file paths)
-fn save_images_to_temp(images: &[String]) -> Result<Vec<PathBuf>, String> {
+/// Save base64 images to private temp files for Codex (which needs file paths)
+fn save_images_to_temp(images: &[String], owner: TempOwner) -> Result<Vec<PathBuf>, String> {
     images
         .iter()
-        .enumerate()
-        .map(|(i, base64_data)| {
+        .map(|base64_data| {
             // Parse the data URL to extract mime type and base64 data
             let parts: Vec<&str> = base64_data.splitn(2, ',').collect();
             let (extension, data) = if parts.len() == 2 {
@@ -302,14 +277,11 @@ fn save_images_to_temp(images: &[String]) -> Result<Vec<PathBuf>, String> {
                 .decode(data)
                 .map_err(|e| format!("Failed to decode base64 image: {}", e))?;
 
-            // Write to temp file
-            let temp_path = std::env::temp_dir()
-                .join(format!("mascot-codex-image-{}-{}.{}", std::process::id(), i, extension));
-
-            fs::write(&temp_path, &image_data)
+            // Write to a temp file removed when the turn ends
+            let temp_path = tempfiles::create(owner, "codex-image", extension, &image_data)
                 .map_err(|e| format!("Failed to write temp image: {}", e))?;
 
-            eprintln!("[Rust] Saved image to {:?}", temp_path);
+            log_line!("[Rust] Saved image to {:?}", temp_path);
             Ok(temp_path)
         })
         .collect()
@@ -325,7 +297,7 @@ fn get_system_prompt() -> String {
     } else if is_dev {
         "You are Supiki, a helpful AI assistant mascot on the user's desktop. \
          You have access to coding capabilities and can help with coding tasks. \
-         Use mcp__mascot__set_emotion to express yourself and mcp__mascot__move_to to navigate the screen. \
+         Use mcp__mascot__set_emotion to express yourself, mcp__mascot__move_to to navigate the screen and mcp__mascot__say for short remarks. \
          Be professional but friendly! When using tables, keep them to 3 columns or fewer."
             .to_string()
     } else {
@@ -350,12 +322,16 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
         return "Could not find codex in PATH. Please install codex or ensure it's in your PATH.".to_string();
     })?;
 
-    // Write MCP config
-    write_codex_mcp_config(&app)?;
+    // The MCP server is passed per run, so ~/.codex/config.toml is never modified
+    let mcp_exe = mcp_exe_string(&app)?;
 
     // Save images to temp files if provided
+    let temp_owner = tempfiles::new_turn();
     let image_paths = if !images.is_empty() {
-        save_images_to_temp(&images)?
+        save_images_to_temp(&images, temp_owner).map_err(|e| {
+            tempfiles::release(temp_owner);
+            e
+        })?
     } else {
         Vec::new()
     };
@@ -363,12 +339,22 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
     // Check if we have a session to resume
     let session_id = CODEX_SESSION_ID.lock().unwrap().clone();
     let custom_cwd = SIDECAR_CWD.lock().unwrap().clone();
+    let is_dev = *DEV_MODE.lock().unwrap();
+    // Codex can't ask mid-turn, so the sandbox is picked up front
+    let mut sandbox = sandbox::sandbox_status(custom_cwd.as_deref(), is_dev);
+    if sandbox.mode != CodexSandbox::ReadOnly && !guardrails::allow_unattended_turn(&app, custom_cwd.as_deref()) {
+        sandbox.restrict_to_read_only();
+    }
+    log_line!("[Rust] Codex sandbox: {:?}", sandbox);
 
     // Build command arguments using builder
-    let mut builder = CodexCommandBuilder::new()
+    let builder = CodexCommandBuilder::new()
         .with_session_resume(session_id.as_ref())
-        .with_json_output()
-        .with_full_auto()
+        .with_json_output();
+    let builder = config::with_mascot_server(builder, &mcp_exe);
+
+    let mut builder = sandbox
+        .apply(builder)
         .with_skip_git_repo_check()
         .with_default_model_config();
 
@@ -383,7 +369,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
         .with_prompt(prompt)
         .build();
 
-    eprintln!("[Rust] Running codex CLI: {:?} with {} args", codex_exe, args.len());
+    log_line!("[Rust] Running codex CLI: {:?} with {} args", codex_exe, args.len());
 
     // Build the command
     let mut cmd = Command::new(&codex_exe);
@@ -398,10 +384,27 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
         cmd.creation_flags(CREATE_NO_WINDOW);
     }
 
+    // Snapshot the workspace unless the sandbox keeps Codex from writing to it
+    let checkpoint_cwd = if sandbox.mode == CodexSandbox::ReadOnly {
+        None
+    } else {
+        custom_cwd.as_deref()
+    };
+    let turn = checkpoints::begin_turn(BackendMode::Codex, checkpoint_cwd);
+    file_edits::begin_turn(BackendMode::Codex, turn);
+
     // Spawn the process
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn codex CLI at {:?}: {}", codex_exe, e))?;
+    let mut child = cmd.spawn().map_err(|e| {
+        tempfiles::release(temp_owner);
+        checkpoints::discard(BackendMode::Codex, turn);
+        file_edits::discard(BackendMode::Codex, turn);
+        format!("Failed to spawn codex CLI at {:?}: {}", codex_exe, e)
+    })?;
+    let audit_turn = audit::begin_turn(
+        BackendMode::Codex,
+        serde_json::to_value(&sandbox).unwrap_or_default(),
+    );
+    let _ = app.emit("codex-sandbox", &sandbox);
 
     let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
     let stderr = child.stderr.take();
@@ -416,7 +419,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
             let line = match line {
                 Ok(l) => l,
                 Err(e) => {
-                    eprintln!("[Rust] Error reading stdout: {}", e);
+                    log_line!("[Rust] Error reading stdout: {}", e);
                     break;
                 }
             };
@@ -428,17 +431,18 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
             // Try to parse as JSON
             match serde_json::from_str::<CodexStreamEvent>(&line) {
                 Ok(event) => {
-                    handle_codex_event(&app_handle, event, &mut accumulated_text);
+                    handle_codex_event(&app_handle, event, &mut accumulated_text, turn);
                 }
                 Err(e) => {
                     // Not JSON, might be raw text or error
-                    eprintln!("[Rust] Non-JSON line ({}): {}", e, line);
+                    log_line!("[Rust] Non-JSON line: {}", e);
+                    log_content!("[Rust] Non-JSON line: {}", line);
                 }
             }
         }
 
         // Wait for process to complete
-        match child.wait() {
+        let outcome = match child.wait() {
             Ok(status) => {
                 if !status.success() {
                     let _ = app_handle.emit(
@@ -448,6 +452,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
                         }),
                     );
                 }
+                format!("process {}", status)
             }
             Err(e) => {
                 let _ = app_handle.emit(
@@ -456,15 +461,20 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
                         "error": format!("Failed to wait for codex CLI: {}", e)
                     }),
                 );
+                "process lost".to_string()
             }
-        }
+        };
+
+        // Close the audit turn of a killed or crashed process (no-op after turn.completed)
+        audit::end_turn_on_exit(BackendMode::Codex, &audit_turn, &outcome);
 
         // Clean up temp images
-        for path in image_paths {
-            let _ = fs::remove_file(path);
-        }
+        tempfiles::release(temp_owner);
+
+        // Report what a killed or crashed turn changed (no-op after turn.completed)
+        finish_turn_changes(&app_handle, turn);
 
-        eprintln!("[Rust] Codex CLI process ended");
+        log_line!("[Rust] Codex CLI process ended");
     });
 
     // Spawn thread to read stderr for logging
@@ -473,7 +483,7 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 if let Ok(line_content) = line {
-                    eprintln!("[Codex] {}", line_content);
+                    log_content!("[Codex] {}", line_content);
                 }
             }
         });
@@ -483,10 +493,10 @@ pub fn run_query(app: tauri::AppHandle, prompt: String, images: Vec<String>) ->
 }
 
 /// Handle a streaming event from Codex CLI
-fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulated_text: &mut String) {
+fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulated_text: &mut String, turn: u64) {
     match event {
         CodexStreamEvent::ThreadStarted { thread_id } => {
-            eprintln!("[Rust] Codex thread started: {:?}", thread_id);
+            log_line!("[Rust] Codex thread started: {:?}", thread_id);
             if let Some(tid) = thread_id {
                 *CODEX_SESSION_ID.lock().unwrap() = Some(tid.clone());
                 save_codex_session_to_disk(&tid);
@@ -494,7 +504,7 @@ fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulat
         }
 
         CodexStreamEvent::TurnStarted { turn_id } => {
-            eprintln!("[Rust] Codex turn started: {:?}", turn_id);
+            log_line!("[Rust] Codex turn started: {:?}", turn_id);
         }
 
         CodexStreamEvent::ItemStarted { item } => {
@@ -511,11 +521,17 @@ fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulat
                         }
                     }
                     CodexItem::ToolCall { name, arguments } => {
+                        record_tool_call(name.as_deref(), &arguments);
                         handle_tool_call(app, name.as_deref(), &arguments);
                     }
                     CodexItem::McpToolCall { tool, arguments, .. } => {
+                        record_tool_call(tool.as_deref(), &arguments);
                         handle_tool_call(app, tool.as_deref(), &arguments);
                     }
+                    CodexItem::CommandExecution { command, .. } => {
+                        let arguments = Some(serde_json::json!({ "command": command }));
+                        record_tool_call(Some("command_execution"), &arguments);
+                    }
                     CodexItem::AgentMessage { text } => {
                         if let Some(t) = text {
                             if !t.is_empty() {
@@ -548,6 +564,18 @@ fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulat
                     CodexItem::McpToolCall { tool, arguments, .. } => {
                         handle_tool_call(app, tool.as_deref(), &arguments);
                     }
+                    CodexItem::FileChange { changes, .. } => {
+                        // Patches are applied as a whole, so they're recorded once they're done
+                        let arguments = Some(serde_json::json!({
+                            "changes": changes
+                                .iter()
+                                .map(|c| serde_json::json!({ "path": c.path, "kind": c.kind }))
+                                .collect::<Vec<_>>()
+                        }));
+                        record_tool_call(Some("file_change"), &arguments);
+                        let cwd = SIDECAR_CWD.lock().unwrap().clone().map(PathBuf::from);
+                        file_edits::track_codex_paths(changes.iter().map(|c| c.path.as_str()), cwd.as_deref());
+                    }
                     CodexItem::AgentMessage { text } => {
                         if let Some(t) = text {
                             if !t.is_empty() && !accumulated_text.contains(&t) {
@@ -562,7 +590,9 @@ fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulat
         }
 
         CodexStreamEvent::TurnCompleted { turn_id } => {
-            eprintln!("[Rust] Codex turn completed: {:?}", turn_id);
+            log_line!("[Rust] Codex turn completed: {:?}", turn_id);
+            audit::end_turn(BackendMode::Codex, "success");
+            finish_turn_changes(app, turn);
             let _ = app.emit(
                 "agent-result",
                 serde_json::json!({
@@ -570,11 +600,14 @@ fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulat
                     "text": accumulated_text.clone()
                 }),
             );
+            tts::read_answer(app, accumulated_text);
             accumulated_text.clear();
         }
 
         CodexStreamEvent::TurnFailed { error } => {
-            eprintln!("[Rust] Codex turn failed: {:?}", error);
+            log_line!("[Rust] Codex turn failed: {:?}", error);
+            audit::end_turn(BackendMode::Codex, "failed");
+            finish_turn_changes(app, turn);
             let _ = app.emit(
                 "agent-error",
                 serde_json::json!({
@@ -584,7 +617,7 @@ fn handle_codex_event(app: &tauri::AppHandle, event: CodexStreamEvent, accumulat
         }
 
         CodexStreamEvent::Error { message } => {
-            eprintln!("[Rust] Codex error: {:?}", message);
+            log_line!("[Rust] Codex error: {:?}", message);
             let _ = app.emit(
                 "agent-error",
                 serde_json::json!({
@@ -604,6 +637,12 @@ fn extract_text_from_content(content: &CodexContent) -> Option<String> {
     }
 }
 
+/// Record a tool call in the audit log (once, when the item starts)
+fn record_tool_call(name: Option<&str>, arguments: &Option<serde_json::Value>) {
+    let input = arguments.clone().unwrap_or_default();
+    audit::record_tool_use(BackendMode::Codex, name.unwrap_or("unknown"), &input);
+}
+
 /// Handle tool call events
 fn handle_tool_call(app: &tauri::AppHandle, name: Option<&str>, arguments: &Option<serde_json::Value>) {
     let name = match name {
@@ -613,15 +652,45 @@ fn handle_tool_call(app: &tauri::AppHandle, name: Option<&str>, arguments: &Opti
 
     let input = arguments.clone().unwrap_or(serde_json::json!({}));
 
-    eprintln!("[Rust] Codex tool call: {} with input: {:?}", name, input);
+    log_line!("[Rust] Codex tool call: {}", name);
+    log_content!("[Rust] Codex tool input: {}", input);
 
     // Emit specific events based on tool
     if name.contains("set_emotion") {
-        let _ = app.emit("agent-emotion", &input);
+        match EmotionEvent::from_tool_input(&input) {
+            Ok(event) => {
+                update_mascot_emotion(event.emotion.as_str());
+                let _ = app.emit("agent-emotion", &event);
+            }
+            Err(e) => log_line!("[Rust] Ignoring set_emotion: {}", e),
+        }
     } else if name.contains("move_to") {
-        let _ = app.emit("clawd-move", &input);
+        let moved = MoveToRequest::from_tool_input(&input)
+            .and_then(|req| movement::emit_move(app, &req));
+        if let Err(e) = moved {
+            log_line!("[Rust] Ignoring move_to: {}", e);
+        }
+    } else if name == "say" || name.ends_with("__say") {
+        let said = SayRequest::from_tool_input(&input)
+            .and_then(|req| utterance::emit_say(app, &req));
+        if let Err(e) = said {
+            log_line!("[Rust] Ignoring say: {}", e);
+        }
+    } else if name == "speak" || name.ends_with("__speak") {
+        match SpeakRequest::from_tool_input(&input).and_then(|req| req.validate().map(String::from)) {
+            Ok(text) => tts::speak(app, &text),
+            Err(e) => log_line!("[Rust] Ignoring speak: {}", e),
+        }
+    } else if name.ends_with("play_sequence") {
+        let played = PlaySequenceRequest::from_tool_input(&input)
+            .and_then(|req| sequences::emit_sequence(app, &req));
+        if let Err(e) = played {
+            log_line!("[Rust] Ignoring play_sequence: {}", e);
+        }
+    } else if name.ends_with("cancel_sequence") {
+        let _ = sequences::emit_cancel(app);
     } else if name.contains("capture_screenshot") {
-        eprintln!("[Rust] Screenshot requested via Codex");
+        log_line!("[Rust] Screenshot requested via Codex");
     }
 
     // Also emit a generic tool-use event
@@ -634,10 +703,17 @@ fn handle_tool_call(app: &tauri::AppHandle, name: Option<&str>, arguments: &Opti
     );
 }
 
+/// Report the turn's file edits and workspace changes (once per turn)
+fn finish_turn_changes(app: &tauri::AppHandle, turn: u64) {
+    file_edits::finish_turn(app, BackendMode::Codex, turn);
+    checkpoints::finish_turn(app, BackendMode::Codex, turn);
+}
+
 /// Clear the current Codex session
 pub fn clear_session() {
     *CODEX_SESSION_ID.lock().unwrap() = None;
-    eprintln!("[Rust] Codex session cleared");
+    screenshots::revoke_session_grant();
+    log_line!("[Rust] Codex session cleared");
 }
 
 /// Check if codex CLI is available
@@ -666,7 +742,7 @@ pub fn check_codex_available() -> Result<String, String> {
                         }
                     }
                     Err(e) => {
-                        eprintln!("[Rust] Failed to run codex at {:?}: {}", path, e);
+                        log_line!("[Rust] Failed to run codex at {:?}: {}", path, e);
                     }
                 }
             }
@@ -782,6 +858,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_command_execution() {
+        let json = r#"{"type": "item.started", "item": {"id": "item_1", "type": "command_execution", "command": "bash -lc ls", "aggregated_output": "", "exit_code": null, "status": "in_progress"}}"#;
+        let event: CodexStreamEvent = serde_json::from_str(json).unwrap();
+        match event {
+            CodexStreamEvent::ItemStarted {
+                item: Some(CodexItem::CommandExecution { command, exit_code, .. }),
+            } => {
+                assert_eq!(command.as_deref(), Some("bash -lc ls"));
+                assert_eq!(exit_code, None);
+            }
+            _ => panic!("Expected a command_execution item"),
+        }
+    }
+
     #[test]
     fn test_parse_error() {
         let json = r#"{"type": "error", "message": "Something went wrong"}"#;
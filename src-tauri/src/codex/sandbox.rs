@@ -0,0 +1,149 @@
+//! Codex sandbox selection
+//!
+//! The sandbox mode comes from the settings when the user picked one and
+//! from the permission policy otherwise, so outside dev mode Codex runs
+//! read-only unless the user allowed more. Network access and extra writable
+//! roots only apply to the workspace-write sandbox.
+
+use serde::Serialize;
+
+use super::command::CodexCommandBuilder;
+use crate::opener::expand_home;
+use crate::policy;
+use crate::settings::{AppSettings, CodexSandbox};
+
+/// The sandbox a Codex turn runs in
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSandboxStatus {
+    pub mode: CodexSandbox,
+    /// Chosen in the settings rather than derived from the permission policy
+    pub from_settings: bool,
+    /// Whether sandboxed commands can use the network
+    pub network_access: bool,
+    /// Writable directories besides the workspace
+    pub writable_roots: Vec<String>,
+}
+
+/// Combine the settings with the policy's sandbox mode
+pub fn resolve(settings: &AppSettings, policy_mode: CodexSandbox) -> CodexSandboxStatus {
+    let mode = settings.codex_sandbox.unwrap_or(policy_mode);
+    let workspace_write = mode == CodexSandbox::WorkspaceWrite;
+    CodexSandboxStatus {
+        mode,
+        from_settings: settings.codex_sandbox.is_some(),
+        network_access: match mode {
+            CodexSandbox::ReadOnly => false,
+            CodexSandbox::WorkspaceWrite => settings.codex_network_access,
+            CodexSandbox::DangerFullAccess => true,
+        },
+        writable_roots: if workspace_write {
+            settings
+                .codex_writable_roots
+                .iter()
+                .filter(|root| !root.trim().is_empty())
+                .map(|root| expand_home(root).to_string_lossy().to_string())
+                .collect()
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+/// The sandbox for a workspace, from the saved settings and policy
+pub fn sandbox_status(cwd: Option<&str>, dev_mode: bool) -> CodexSandboxStatus {
+    let policy_mode = policy::effective_policy(cwd, dev_mode).codex_sandbox;
+    resolve(&crate::settings::load_settings(), policy_mode)
+}
+
+impl CodexSandboxStatus {
+    /// Fall back to the read-only sandbox for this turn
+    pub fn restrict_to_read_only(&mut self) {
+        self.mode = CodexSandbox::ReadOnly;
+        self.network_access = false;
+        self.writable_roots.clear();
+    }
+
+    /// Add the sandbox arguments to a Codex command
+    pub fn apply(&self, builder: CodexCommandBuilder) -> CodexCommandBuilder {
+        let mut builder = builder.with_sandbox(self.mode.as_str());
+        if self.mode == CodexSandbox::WorkspaceWrite {
+            builder = builder.with_network_access(self.network_access);
+            if !self.writable_roots.is_empty() {
+                builder = builder.with_writable_roots(&self.writable_roots);
+            }
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_mode_is_the_default() {
+        let settings = AppSettings::default();
+        let status = resolve(&settings, CodexSandbox::ReadOnly);
+        assert_eq!(status.mode, CodexSandbox::ReadOnly);
+        assert!(!status.from_settings);
+        assert!(!status.network_access);
+    }
+
+    #[test]
+    fn test_settings_override_policy() {
+        let settings = AppSettings {
+            codex_sandbox: Some(CodexSandbox::WorkspaceWrite),
+            codex_network_access: true,
+            codex_writable_roots: vec!["/tmp/cache".to_string(), " ".to_string()],
+            ..AppSettings::default()
+        };
+        let status = resolve(&settings, CodexSandbox::ReadOnly);
+        assert_eq!(status.mode, CodexSandbox::WorkspaceWrite);
+        assert!(status.from_settings);
+        assert!(status.network_access);
+        assert_eq!(status.writable_roots, ["/tmp/cache"]);
+
+        let args = status.apply(CodexCommandBuilder::new()).build();
+        assert_eq!(
+            args,
+            [
+                "exec",
+                "--sandbox",
+                "workspace-write",
+                "--config",
+                "sandbox_workspace_write.network_access=true",
+                "--config",
+                "sandbox_workspace_write.writable_roots=[\"/tmp/cache\"]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_restrict_to_read_only() {
+        let settings = AppSettings {
+            codex_sandbox: Some(CodexSandbox::WorkspaceWrite),
+            codex_network_access: true,
+            codex_writable_roots: vec!["/tmp/cache".to_string()],
+            ..AppSettings::default()
+        };
+        let mut status = resolve(&settings, CodexSandbox::ReadOnly);
+        status.restrict_to_read_only();
+        assert_eq!(status.apply(CodexCommandBuilder::new()).build(), ["exec", "--sandbox", "read-only"]);
+        assert!(status.from_settings);
+    }
+
+    #[test]
+    fn test_extra_access_only_applies_to_workspace_write() {
+        let settings = AppSettings {
+            codex_sandbox: Some(CodexSandbox::ReadOnly),
+            codex_network_access: true,
+            codex_writable_roots: vec!["/tmp/cache".to_string()],
+            ..AppSettings::default()
+        };
+        let status = resolve(&settings, CodexSandbox::WorkspaceWrite);
+        assert!(!status.network_access);
+        assert!(status.writable_roots.is_empty());
+        assert_eq!(status.apply(CodexCommandBuilder::new()).build(), ["exec", "--sandbox", "read-only"]);
+    }
+}
@@ -2,18 +2,27 @@
 //!
 //! All commands exposed to the frontend via tauri-specta.
 
-use std::fs;
-use std::process::Command;
-
 use tauri::Manager;
 
+use crate::approvals::{self, ApprovalDecision, ApprovalRequest};
+use crate::audit::{self, AuditEntry, AuditQuery, AuditVerification};
+use crate::checkpoints::{self, Checkpoint, CheckpointDiff};
 use crate::claude::{
     check_claude_available, clear_session as clear_claude_session,
     confirm_exit_plan_mode, deny_exit_plan_mode, respond_to_ask_user_question,
     run_query as run_claude_query,
 };
-use crate::codex::{check_codex_available_with_app, clear_session as clear_codex_session, run_query as run_codex_query};
-use crate::state::{BackendMode, BACKEND_MODE, CODEX_SESSION_ID, DEV_MODE, MAX_RECENT_CWDS, RECENT_CWDS, SESSION_ID, SIDECAR_CWD, SUPIKI_MODE, save_cwd_to_disk, save_recent_cwds_to_disk};
+use crate::codex::{self, check_codex_available_with_app, clear_session as clear_codex_session, run_query as run_codex_query, CodexSandboxStatus};
+use crate::guardrails;
+use crate::helpers;
+use crate::logging;
+use crate::opener;
+use crate::policy::{self, EffectivePolicy};
+use crate::screenshots::{self, CaptureRecord};
+use crate::settings::{load_settings, save_settings, AppSettings};
+use crate::state::{BackendMode, ChatSessionSummary, BACKEND_MODE, CODEX_SESSION_ID, DEV_MODE, LANGUAGE, MAX_RECENT_CWDS, RECENT_CWDS, SESSION_ID, SIDECAR_CWD, SUPIKI_MODE, save_chat_sessions_to_disk, save_cwd_to_disk, save_recent_cwds_to_disk};
+use crate::tempfiles::{self, TempOwner};
+use crate::tts;
 
 /// Send a message to the AI backend (Claude or Codex)
 #[tauri::command]
@@ -22,37 +31,45 @@ pub async fn send_agent_message(
     app: tauri::AppHandle,
     message: String,
     images: Vec<String>,
-    _language: Option<String>,
+    language: Option<String>,
 ) -> Result<(), String> {
     let mode = *BACKEND_MODE.lock().unwrap();
+    if language.is_some() {
+        *LANGUAGE.lock().unwrap() = language;
+    }
 
-    println!(
-        "[Rust] send_agent_message called with: {}, images: {}, backend: {:?}",
-        message,
+    log_line!(
+        "[Rust] send_agent_message called with {} chars, images: {}, backend: {:?}",
+        message.chars().count(),
         images.len(),
         mode
     );
+    log_content!("[Rust] Message: {}", message);
 
-    // Route to appropriate backend
-    match mode {
+    // Route to appropriate backend, off the async runtime: starting a turn
+    // snapshots the workspace (git or a file copy) and may wait for approvals
+    tauri::async_runtime::spawn_blocking(move || match mode {
         BackendMode::Claude => run_claude_query(app, message, images),
         BackendMode::Codex => run_codex_query(app, message, images),
-    }
+    })
+    .await
+    .map_err(|e| format!("Failed to start the turn: {}", e))?
 }
 
 /// Clear the current session (for active backend)
 #[tauri::command]
 #[specta::specta]
-pub fn clear_agent_session() -> Result<(), String> {
+pub fn clear_agent_session(app: tauri::AppHandle) -> Result<(), String> {
+    helpers::dismiss_all(&app);
     let mode = *BACKEND_MODE.lock().unwrap();
     match mode {
         BackendMode::Claude => {
             clear_claude_session();
-            println!("[Rust] Claude session cleared");
+            log_line!("[Rust] Claude session cleared");
         }
         BackendMode::Codex => {
             clear_codex_session();
-            println!("[Rust] Codex session cleared");
+            log_line!("[Rust] Codex session cleared");
         }
     }
     Ok(())
@@ -71,7 +88,7 @@ pub fn get_session_id() -> Option<String> {
 pub fn stop_sidecar() {
     // In CLI mode, we can't easily cancel a running query
     // The process will run to completion
-    println!("[Rust] Stop requested (CLI mode - no action taken)");
+    log_line!("[Rust] Stop requested (CLI mode - no action taken)");
 }
 
 /// Quit the application
@@ -102,13 +119,19 @@ pub fn is_supiki_mode() -> bool {
 
 /// Set custom working directory for Claude CLI
 /// Also clears the session to start fresh with the new cwd
+/// Root, home and system directories are refused or confirmed first
 #[tauri::command]
 #[specta::specta]
-pub fn set_sidecar_cwd(path: String) -> Result<(), String> {
+pub async fn set_sidecar_cwd(app: tauri::AppHandle, path: String) -> Result<(), String> {
     // Validate path exists
     if !std::path::Path::new(&path).is_dir() {
         return Err(format!("Directory does not exist: {}", path));
     }
+    // Checking may wait for the user's approval, so keep it off the async runtime
+    let check_path = path.clone();
+    tauri::async_runtime::spawn_blocking(move || guardrails::check_cwd(&app, std::path::Path::new(&check_path)))
+        .await
+        .map_err(|e| format!("Failed to check the directory: {}", e))??;
 
     // Add to recent cwds (if not already the most recent)
     {
@@ -135,7 +158,7 @@ pub fn set_sidecar_cwd(path: String) -> Result<(), String> {
     clear_claude_session();
     clear_codex_session();
 
-    println!("[Rust] CWD set to: {} (sessions cleared)", path);
+    log_line!("[Rust] CWD set to: {} (sessions cleared)", path);
     Ok(())
 }
 
@@ -146,6 +169,22 @@ pub fn get_sidecar_cwd() -> Option<String> {
     SIDECAR_CWD.lock().unwrap().clone()
 }
 
+/// Get the permission policy in effect for a directory (default: the current cwd)
+#[tauri::command]
+#[specta::specta]
+pub fn get_effective_policy(cwd: Option<String>) -> EffectivePolicy {
+    let cwd = cwd.or_else(|| SIDECAR_CWD.lock().unwrap().clone());
+    policy::effective_policy(cwd.as_deref(), *DEV_MODE.lock().unwrap())
+}
+
+/// Get the sandbox the next Codex turn will run in
+#[tauri::command]
+#[specta::specta]
+pub fn get_codex_sandbox() -> CodexSandboxStatus {
+    let cwd = SIDECAR_CWD.lock().unwrap().clone();
+    codex::sandbox_status(cwd.as_deref(), *DEV_MODE.lock().unwrap())
+}
+
 /// Get actual working directory (custom if set, otherwise app's cwd)
 #[tauri::command]
 #[specta::specta]
@@ -178,6 +217,123 @@ pub fn get_recent_cwds() -> Vec<String> {
     RECENT_CWDS.lock().unwrap().clone()
 }
 
+/// Sync the chat history index from the frontend
+/// Persisted so the MCP server can expose saved sessions as a resource
+#[tauri::command]
+#[specta::specta]
+pub fn sync_chat_sessions(sessions: Vec<ChatSessionSummary>) {
+    save_chat_sessions_to_disk(&sessions);
+}
+
+/// Get the persisted app settings
+#[tauri::command]
+#[specta::specta]
+pub fn get_app_settings() -> AppSettings {
+    load_settings()
+}
+
+/// Replace the persisted app settings
+/// The MCP server picks up changes on its next tool call
+#[tauri::command]
+#[specta::specta]
+pub fn set_app_settings(settings: AppSettings) -> Result<(), String> {
+    save_settings(&settings)?;
+    logging::set_patterns(&settings.log_redact_patterns);
+    Ok(())
+}
+
+/// List the screenshots the agent has taken, newest first
+#[tauri::command]
+#[specta::specta]
+pub fn get_screenshot_history() -> Vec<CaptureRecord> {
+    screenshots::history()
+}
+
+/// Delete one screenshot from the history
+#[tauri::command]
+#[specta::specta]
+pub fn delete_screenshot(id: String) -> Result<(), String> {
+    screenshots::delete(&id)
+}
+
+/// Delete the whole screenshot history, returning how many were removed
+#[tauri::command]
+#[specta::specta]
+pub fn purge_screenshot_history() -> Result<u32, String> {
+    screenshots::purge()
+}
+
+/// List workspace checkpoints with their changes, newest first
+/// Without a session id, lists the active backend's current session.
+#[tauri::command]
+#[specta::specta]
+pub fn list_checkpoints(session_id: Option<String>) -> Vec<Checkpoint> {
+    let session_id = session_id.or_else(|| match *BACKEND_MODE.lock().unwrap() {
+        BackendMode::Claude => SESSION_ID.lock().unwrap().clone(),
+        BackendMode::Codex => CODEX_SESSION_ID.lock().unwrap().clone(),
+    });
+    checkpoints::list(session_id.as_deref())
+}
+
+/// Roll the workspace back to a checkpoint, returning the files that changed
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_checkpoint(id: String) -> Result<CheckpointDiff, String> {
+    // Restoring runs git or copies files, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || checkpoints::restore(&id))
+        .await
+        .map_err(|e| format!("Failed to restore checkpoint: {}", e))?
+}
+
+/// Record the UI language (selects the TTS voice)
+#[tauri::command]
+#[specta::specta]
+pub fn set_language(language: String) {
+    *LANGUAGE.lock().unwrap() = Some(language);
+}
+
+/// Stop reading aloud
+#[tauri::command]
+#[specta::specta]
+pub fn stop_speaking(app: tauri::AppHandle) {
+    tts::stop(&app);
+}
+
+/// Get a pending approval for the approval window
+#[tauri::command]
+#[specta::specta]
+pub fn get_approval_request(id: String) -> Option<ApprovalRequest> {
+    approvals::get(&id)
+}
+
+/// Approve or deny a pending agent action
+#[tauri::command]
+#[specta::specta]
+pub fn respond_approval(id: String, decision: ApprovalDecision) -> Result<(), String> {
+    approvals::respond(&id, decision)
+}
+
+/// Query the audit log of agent activity (oldest first)
+#[tauri::command]
+#[specta::specta]
+pub fn query_audit_log(query: AuditQuery) -> Result<Vec<AuditEntry>, String> {
+    audit::query(&query)
+}
+
+/// Check the audit log's hash chain for tampering
+#[tauri::command]
+#[specta::specta]
+pub fn verify_audit_log() -> Result<AuditVerification, String> {
+    audit::verify()
+}
+
+/// Export a turn's activity as a Markdown report and return its path
+#[tauri::command]
+#[specta::specta]
+pub fn export_audit_turn(turn_id: String) -> Result<String, String> {
+    audit::export_turn(&turn_id)
+}
+
 /// Answer an AskUserQuestion from the agent
 /// The tool_use_id identifies which tool call to respond to
 /// The questions_json contains the original questions (needed for proper response format)
@@ -259,43 +415,14 @@ pub fn open_image_in_viewer(base64_data: String) -> Result<(), String> {
         .decode(parts[1])
         .map_err(|e| format!("Failed to decode base64: {}", e))?;
 
-    // Create temp file path
-    let temp_dir = std::env::temp_dir();
-    let filename = format!("clawd-image-{}.{}", std::process::id(), extension);
-    let temp_path = temp_dir.join(filename);
-
-    // Write to temp file
-    fs::write(&temp_path, &image_data)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
-
-    println!("[Rust] Opening image: {:?}", temp_path);
-
-    // Open with system default viewer using shell
-    #[cfg(target_os = "windows")]
-    {
-        Command::new("cmd")
-            .args(["/C", "start", "", &temp_path.to_string_lossy()])
-            .spawn()
-            .map_err(|e| format!("Failed to open image: {}", e))?;
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        Command::new("open")
-            .arg(&temp_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open image: {}", e))?;
-    }
+    // Write to a new private temp file, kept until the app exits since the
+    // viewer may still have it open
+    let temp_path = tempfiles::create(TempOwner::Viewer, "image", extension, &image_data)?;
 
-    #[cfg(target_os = "linux")]
-    {
-        Command::new("xdg-open")
-            .arg(&temp_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open image: {}", e))?;
-    }
+    log_line!("[Rust] Opening image: {:?}", temp_path);
 
-    Ok(())
+    // Open with system default viewer
+    opener::open_with_system(&temp_path.to_string_lossy())
 }
 
 /// Check if Claude CLI is available
@@ -332,7 +459,7 @@ pub fn set_backend_mode(mode: String) -> Result<(), String> {
         _ => return Err(format!("Invalid backend mode: {}. Use 'claude' or 'codex'.", mode)),
     };
     *BACKEND_MODE.lock().unwrap() = backend;
-    println!("[Rust] Backend mode set to: {:?}", backend);
+    log_line!("[Rust] Backend mode set to: {:?}", backend);
     Ok(())
 }
 
@@ -346,17 +473,27 @@ pub fn get_codex_session_id() -> Option<String> {
 /// Clear Codex session specifically
 #[tauri::command]
 #[specta::specta]
-pub fn clear_codex_session_cmd() -> Result<(), String> {
+pub fn clear_codex_session_cmd(app: tauri::AppHandle) -> Result<(), String> {
+    helpers::dismiss_all(&app);
     clear_codex_session();
-    println!("[Rust] Codex session cleared");
+    log_line!("[Rust] Codex session cleared");
     Ok(())
 }
 
+/// Remove the mascot MCP server entry older versions added to ~/.codex/config.toml
+/// Returns whether an entry was removed (the original is backed up first)
+#[tauri::command]
+#[specta::specta]
+pub fn uninstall_codex_mcp_config() -> Result<bool, String> {
+    codex::uninstall_mcp_config()
+}
+
 /// Clear Claude session specifically
 #[tauri::command]
 #[specta::specta]
-pub fn clear_claude_session_cmd() -> Result<(), String> {
+pub fn clear_claude_session_cmd(app: tauri::AppHandle) -> Result<(), String> {
+    helpers::dismiss_all(&app);
     clear_claude_session();
-    println!("[Rust] Claude session cleared");
+    log_line!("[Rust] Claude session cleared");
     Ok(())
 }
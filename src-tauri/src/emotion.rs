@@ -0,0 +1,184 @@
+//! Mascot emotions
+//!
+//! The single source of truth for the emotions the mascot can show. Shared by
+//! the MCP server (tool schema and validation), the CLI runners (parsing
+//! tool_use input) and the frontend bindings. Must stay in sync with
+//! `EMOTIONS` in `src/emotion.ts`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+/// Default time an emotion is shown before blending back
+pub const DEFAULT_DURATION_MS: u32 = 5000;
+
+/// An emotion the mascot can express
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+    specta::Type,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Emotion {
+    #[default]
+    Neutral,
+    Happy,
+    Sad,
+    Excited,
+    Thinking,
+    Confused,
+    Surprised,
+    Curious,
+}
+
+impl Emotion {
+    pub const ALL: [Emotion; 8] = [
+        Emotion::Neutral,
+        Emotion::Happy,
+        Emotion::Sad,
+        Emotion::Excited,
+        Emotion::Thinking,
+        Emotion::Confused,
+        Emotion::Surprised,
+        Emotion::Curious,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Emotion::Neutral => "neutral",
+            Emotion::Happy => "happy",
+            Emotion::Sad => "sad",
+            Emotion::Excited => "excited",
+            Emotion::Thinking => "thinking",
+            Emotion::Confused => "confused",
+            Emotion::Surprised => "surprised",
+            Emotion::Curious => "curious",
+        }
+    }
+
+    /// Comma-separated list of valid emotion names
+    pub fn valid_names() -> String {
+        Self::ALL.iter().map(|e| e.as_str()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl fmt::Display for Emotion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Emotion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|e| e.as_str() == name)
+            .ok_or_else(|| format!("Unknown emotion '{}'. Valid emotions: {}", s, Self::valid_names()))
+    }
+}
+
+/// Payload of the `agent-emotion` event
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EmotionEvent {
+    pub emotion: Emotion,
+    /// How long to show the emotion in milliseconds
+    pub duration: u32,
+    /// Strength of the expression from 0.0 to 1.0
+    pub intensity: f32,
+    /// Emotion to return to after the duration (neutral when unset)
+    pub blend_to: Option<Emotion>,
+}
+
+impl EmotionEvent {
+    /// Validate raw set_emotion arguments
+    pub fn new(
+        emotion: &str,
+        duration_ms: Option<u32>,
+        intensity: Option<f32>,
+        blend_to: Option<&str>,
+    ) -> Result<Self, String> {
+        let intensity = intensity.unwrap_or(1.0);
+        if !(0.0..=1.0).contains(&intensity) {
+            return Err(format!("Intensity must be between 0.0 and 1.0, got {}", intensity));
+        }
+        Ok(Self {
+            emotion: emotion.parse()?,
+            duration: duration_ms.unwrap_or(DEFAULT_DURATION_MS),
+            intensity,
+            blend_to: blend_to.map(str::parse).transpose()?,
+        })
+    }
+
+    /// Parse the input of a set_emotion tool_use from the CLI stream
+    pub fn from_tool_input(input: &serde_json::Value) -> Result<Self, String> {
+        let emotion = input
+            .get("emotion")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing emotion")?;
+        let duration_ms = input
+            .get("duration_ms")
+            .and_then(|v| v.as_u64())
+            .map(|d| d.min(u32::MAX as u64) as u32);
+        let intensity = input.get("intensity").and_then(|v| v.as_f64()).map(|i| i as f32);
+        let blend_to = input.get("blend_to").and_then(|v| v.as_str());
+        Self::new(emotion, duration_ms, intensity, blend_to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_emotion() {
+        assert_eq!("Happy".parse::<Emotion>(), Ok(Emotion::Happy));
+        assert_eq!(" curious ".parse::<Emotion>(), Ok(Emotion::Curious));
+        let err = "love".parse::<Emotion>().unwrap_err();
+        assert!(err.contains("neutral, happy, sad"), "{}", err);
+    }
+
+    #[test]
+    fn test_serialization_matches_names() {
+        for emotion in Emotion::ALL {
+            let json = serde_json::to_string(&emotion).unwrap();
+            assert_eq!(json, format!("\"{}\"", emotion.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_event_from_tool_input() {
+        let input = serde_json::json!({
+            "emotion": "excited",
+            "duration_ms": 2000,
+            "intensity": 0.5,
+            "blend_to": "happy"
+        });
+        let event = EmotionEvent::from_tool_input(&input).unwrap();
+        assert_eq!(event.emotion, Emotion::Excited);
+        assert_eq!(event.duration, 2000);
+        assert_eq!(event.blend_to, Some(Emotion::Happy));
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["blendTo"], "happy");
+    }
+
+    #[test]
+    fn test_event_rejects_invalid_values() {
+        assert!(EmotionEvent::new("happy", None, Some(1.5), None).is_err());
+        assert!(EmotionEvent::new("happy", None, None, Some("angry")).is_err());
+        assert!(EmotionEvent::from_tool_input(&serde_json::json!({})).is_err());
+    }
+}
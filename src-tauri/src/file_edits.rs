@@ -0,0 +1,398 @@
+//! Per-turn diffs of agent file edits
+//!
+//! File-editing tool calls (Claude's Edit, Write and MultiEdit, Codex's
+//! `file_change` items) are noted as they stream in. When the turn ends each
+//! touched file is compared with its content before the turn, taken from the
+//! turn's checkpoint. Without one, Claude files fall back to the content read
+//! when the tool call line is processed; by then the CLI may already have
+//! applied the edit, so that diff is best effort and may come out empty.
+//! Files with no earlier content at all (Codex without a checkpoint) are still
+//! listed, marked `before_unknown` and without a diff. Paths are reported
+//! relative to the checkpoint root or the working directory.
+//! The unified diffs are sent as an `agent-file-changes` event.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use tauri::Emitter;
+
+use crate::checkpoints::{self, ChangeStatus};
+use crate::state::{BackendMode, CODEX_SESSION_ID, SESSION_ID, SIDECAR_CWD};
+
+/// Claude tools that edit a file named by `file_path`
+const CLAUDE_EDIT_TOOLS: &[&str] = &["Edit", "Write", "MultiEdit"];
+
+/// Lines of context around each hunk
+const CONTEXT_LINES: usize = 3;
+
+/// Larger files are reported without a text diff
+const MAX_DIFF_BYTES: usize = 1024 * 1024;
+
+/// A file touched during a turn
+struct TrackedFile {
+    path: PathBuf,
+    tools: Vec<String>,
+    /// Content when first touched, `None` inside if the file did not exist
+    /// (not read for Codex, whose items arrive after the edit)
+    before: Option<Option<Vec<u8>>>,
+}
+
+/// Files each unfinished turn has touched, by backend and turn token
+static TURNS: Mutex<Vec<(BackendMode, u64, Vec<TrackedFile>)>> = Mutex::new(Vec::new());
+
+/// A hunk of a unified diff
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    /// 1-based start line and line count in the old file
+    pub old_start: u32,
+    pub old_lines: u32,
+    /// 1-based start line and line count in the new file
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Lines prefixed with ' ', '-' or '+'
+    pub lines: Vec<String>,
+}
+
+/// How an agent changed one file during a turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    /// Relative to the checkpoint root or working directory, with `/`
+    /// separators; absolute for files outside it
+    pub path: String,
+    /// Tools that edited the file
+    pub tools: Vec<String>,
+    pub status: ChangeStatus,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<DiffHunk>,
+    /// The whole diff as unified diff text
+    pub unified: String,
+    /// No text diff, because a version is binary or too large
+    pub binary: bool,
+    /// No text diff, because the content before the turn is unknown
+    pub before_unknown: bool,
+}
+
+/// Payload of the `agent-file-changes` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentFileChanges {
+    /// "claude" or "codex"
+    pub backend: String,
+    pub session_id: Option<String>,
+    /// Checkpoint the turn can be rolled back to
+    pub checkpoint_id: Option<String>,
+    pub files: Vec<FileDiff>,
+}
+
+/// Start tracking a turn of `backend` under its `checkpoints::begin_turn` token
+pub fn begin_turn(backend: BackendMode, turn: u64) {
+    TURNS.lock().unwrap().push((backend, turn, Vec::new()));
+}
+
+/// Stop tracking a turn whose process never started
+pub fn discard(backend: BackendMode, turn: u64) {
+    TURNS.lock().unwrap().retain(|(b, t, _)| !(*b == backend && *t == turn));
+}
+
+/// Note that `tool` edits `path`, reading the file first if `read_before`
+fn track(backend: BackendMode, path: PathBuf, tool: &str, read_before: bool) {
+    let mut turns = TURNS.lock().unwrap();
+    let Some((_, _, files)) = turns.iter_mut().rev().find(|(b, _, _)| *b == backend) else {
+        return;
+    };
+    match files.iter_mut().find(|f| f.path == path) {
+        Some(file) => {
+            if !file.tools.iter().any(|t| t == tool) {
+                file.tools.push(tool.to_string());
+            }
+        }
+        None => {
+            let before = read_before.then(|| fs::read(&path).ok());
+            files.push(TrackedFile {
+                path,
+                tools: vec![tool.to_string()],
+                before,
+            });
+        }
+    }
+}
+
+/// Note a Claude tool call if it edits a file
+pub fn track_claude_tool(name: &str, input: &serde_json::Value) {
+    if !CLAUDE_EDIT_TOOLS.contains(&name) {
+        return;
+    }
+    if let Some(path) = input.get("file_path").and_then(|p| p.as_str()) {
+        track(BackendMode::Claude, PathBuf::from(path), name, true);
+    }
+}
+
+/// Note the files of a Codex `file_change` item, relative to the turn's cwd
+pub fn track_codex_paths<'a>(paths: impl IntoIterator<Item = &'a str>, cwd: Option<&Path>) {
+    for path in paths {
+        let path = Path::new(path);
+        let path = match cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        };
+        track(BackendMode::Codex, path, "file_change", false);
+    }
+}
+
+/// Unified diff of two texts, with its hunks and line counts
+pub fn diff_text(path: &str, old: &str, new: &str) -> (Vec<DiffHunk>, String, u32, u32) {
+    let diff = TextDiff::from_lines(old, new);
+    let (mut additions, mut deletions) = (0, 0);
+    let mut hunks = Vec::new();
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+        // Empty ranges start at the line before, as in `diff -u`
+        let start = |range: &std::ops::Range<usize>| {
+            if range.is_empty() {
+                range.start as u32
+            } else {
+                range.start as u32 + 1
+            }
+        };
+        let mut lines = Vec::new();
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let sign = match change.tag() {
+                    ChangeTag::Equal => ' ',
+                    ChangeTag::Delete => {
+                        deletions += 1;
+                        '-'
+                    }
+                    ChangeTag::Insert => {
+                        additions += 1;
+                        '+'
+                    }
+                };
+                let text = change.value();
+                lines.push(format!("{}{}", sign, text.strip_suffix('\n').unwrap_or(text)));
+            }
+        }
+        hunks.push(DiffHunk {
+            old_start: start(&old_range),
+            old_lines: old_range.len() as u32,
+            new_start: start(&new_range),
+            new_lines: new_range.len() as u32,
+            lines,
+        });
+    }
+    let unified = diff
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string();
+    (hunks, unified, additions, deletions)
+}
+
+/// Compare a file's content before and after the turn
+fn file_diff(path: &str, tools: Vec<String>, before: Option<&[u8]>, after: Option<&[u8]>) -> Option<FileDiff> {
+    let status = match (before, after) {
+        (None, None) => return None,
+        (Some(a), Some(b)) if a == b => return None,
+        (None, Some(_)) => ChangeStatus::Added,
+        (Some(_), None) => ChangeStatus::Deleted,
+        (Some(_), Some(_)) => ChangeStatus::Modified,
+    };
+    let text = |data: Option<&[u8]>| match data {
+        None => Some(""),
+        Some(data) if data.len() <= MAX_DIFF_BYTES => std::str::from_utf8(data).ok(),
+        Some(_) => None,
+    };
+    let (hunks, unified, additions, deletions, binary) = match (text(before), text(after)) {
+        (Some(old), Some(new)) => {
+            let (hunks, unified, additions, deletions) = diff_text(path, old, new);
+            (hunks, unified, additions, deletions, false)
+        }
+        _ => (Vec::new(), String::new(), 0, 0, true),
+    };
+    Some(FileDiff {
+        path: path.to_string(),
+        tools,
+        status,
+        additions,
+        deletions,
+        hunks,
+        unified,
+        binary,
+        before_unknown: false,
+    })
+}
+
+/// A file whose content before the turn is unknown, listed without a diff
+fn unknown_diff(path: &str, tools: Vec<String>, exists: bool) -> FileDiff {
+    FileDiff {
+        path: path.to_string(),
+        tools,
+        status: if exists {
+            ChangeStatus::Modified
+        } else {
+            ChangeStatus::Deleted
+        },
+        additions: 0,
+        deletions: 0,
+        hunks: Vec::new(),
+        unified: String::new(),
+        binary: false,
+        before_unknown: true,
+    }
+}
+
+/// `path` relative to `root` with `/` separators, or absolute outside it
+fn display_path(path: &Path, root: Option<&Path>) -> String {
+    match root.and_then(|root| path.strip_prefix(root).ok()) {
+        Some(relative) if !relative.as_os_str().is_empty() => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        _ => path.to_string_lossy().to_string(),
+    }
+}
+
+/// Diff the files the backend's turn touched and emit `agent-file-changes`
+/// Call before `checkpoints::finish_turn`, which may drop the checkpoint.
+/// Does nothing when the turn was already finished.
+pub fn finish_turn(app: &tauri::AppHandle, backend: BackendMode, turn: u64) {
+    let files = {
+        let mut turns = TURNS.lock().unwrap();
+        let Some(index) = turns.iter().position(|(b, t, _)| *b == backend && *t == turn) else {
+            return;
+        };
+        turns.remove(index).2
+    };
+    if files.is_empty() {
+        return;
+    }
+
+    let checkpoint_id = checkpoints::active_turn(backend, turn);
+    let root = checkpoint_id
+        .as_deref()
+        .and_then(checkpoints::root)
+        .or_else(|| SIDECAR_CWD.lock().unwrap().clone().map(PathBuf::from));
+    let diffs: Vec<FileDiff> = files
+        .into_iter()
+        .filter_map(|file| {
+            let path = display_path(&file.path, root.as_deref());
+            let before = checkpoint_id
+                .as_deref()
+                .and_then(|id| checkpoints::original_content(id, &file.path))
+                .or(file.before);
+            let after = fs::read(&file.path).ok();
+            let Some(before) = before else {
+                log_line!("[Rust] No content from before the turn for {}, listing it without a diff", path);
+                return Some(unknown_diff(&path, file.tools, after.is_some()));
+            };
+            file_diff(&path, file.tools, before.as_deref(), after.as_deref())
+        })
+        .collect();
+    if diffs.is_empty() {
+        return;
+    }
+
+    log_line!("[Rust] Turn edited {} file(s)", diffs.len());
+    let changes = AgentFileChanges {
+        backend: match backend {
+            BackendMode::Claude => "claude",
+            BackendMode::Codex => "codex",
+        }
+        .to_string(),
+        session_id: match backend {
+            BackendMode::Claude => SESSION_ID.lock().unwrap().clone(),
+            BackendMode::Codex => CODEX_SESSION_ID.lock().unwrap().clone(),
+        },
+        checkpoint_id,
+        files: diffs,
+    };
+    let _ = app.emit("agent-file-changes", &changes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_text_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        let (hunks, unified, additions, deletions) = diff_text("src/x.txt", old, new);
+        assert_eq!((additions, deletions), (2, 1));
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_lines), (1, 5));
+        assert_eq!((hunks[0].new_start, hunks[0].new_lines), (1, 5));
+        assert_eq!(hunks[0].lines[..3], [" a", "-b", "+B"]);
+        assert_eq!(hunks[1].lines.last().map(String::as_str), Some("+k"));
+        assert!(unified.starts_with("--- a/src/x.txt\n+++ b/src/x.txt\n@@ -1,5 +1,5 @@\n"));
+    }
+
+    #[test]
+    fn test_file_diff_status() {
+        let tools = || vec!["Write".to_string()];
+        let added = file_diff("new.txt", tools(), None, Some(b"one\ntwo\n")).unwrap();
+        assert_eq!(added.status, ChangeStatus::Added);
+        assert_eq!(added.additions, 2);
+        assert_eq!((added.hunks[0].old_start, added.hunks[0].old_lines), (0, 0));
+
+        let deleted = file_diff("old.txt", tools(), Some(b"one\n"), None).unwrap();
+        assert_eq!((deleted.status, deleted.deletions), (ChangeStatus::Deleted, 1));
+
+        assert_eq!(file_diff("same.txt", tools(), Some(b"x"), Some(b"x")), None);
+        assert_eq!(file_diff("gone.txt", tools(), None, None), None);
+
+        let binary = file_diff("image.png", tools(), Some(&[0xff, 0xfe]), Some(&[0xff])).unwrap();
+        assert!(binary.binary);
+        assert!(binary.hunks.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_baseline_and_display_path() {
+        let unknown = unknown_diff("src/x.rs", vec!["file_change".to_string()], true);
+        assert!(unknown.before_unknown);
+        assert_eq!(unknown.status, ChangeStatus::Modified);
+        assert!(unknown.hunks.is_empty() && !unknown.binary);
+        assert_eq!(unknown_diff("x", Vec::new(), false).status, ChangeStatus::Deleted);
+
+        let root = std::env::temp_dir().join("supiki-project");
+        let file = root.join("src").join("x.rs");
+        assert_eq!(display_path(&file, Some(&root)), "src/x.rs");
+        let outside = std::env::temp_dir().join("elsewhere.rs");
+        assert_eq!(display_path(&outside, Some(&root)), outside.to_string_lossy());
+        assert_eq!(display_path(&file, None), file.to_string_lossy());
+
+        let (_, unified, _, _) = diff_text(&display_path(&file, Some(&root)), "a\n", "b\n");
+        assert!(unified.starts_with("--- a/src/x.rs\n+++ b/src/x.rs\n"));
+    }
+
+    #[test]
+    fn test_track_claude_tool() {
+        begin_turn(BackendMode::Claude, u64::MAX);
+        let input = serde_json::json!({ "file_path": "/nonexistent/supiki/a.rs", "old_string": "x" });
+        track_claude_tool("Edit", &input);
+        track_claude_tool("MultiEdit", &input);
+        track_claude_tool("Read", &serde_json::json!({ "file_path": "/nonexistent/supiki/b.rs" }));
+
+        {
+            let turns = TURNS.lock().unwrap();
+            let (_, _, files) = turns.iter().find(|(_, t, _)| *t == u64::MAX).unwrap();
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].tools, ["Edit", "MultiEdit"]);
+            assert_eq!(files[0].before, Some(None));
+        }
+
+        discard(BackendMode::Claude, u64::MAX);
+        assert!(!TURNS.lock().unwrap().iter().any(|(_, t, _)| *t == u64::MAX));
+    }
+}
@@ -0,0 +1,261 @@
+//! Guardrails for the agent's working directory
+//!
+//! `set_sidecar_cwd` refuses or asks about directories an agent should not
+//! work in: the filesystem root, the home directory and its parents, and
+//! system directories. Optionally, turns without permission checks (Claude's
+//! skipped permissions, a writable Codex sandbox) need a git repository with
+//! a clean tree and fall back to asking or read-only otherwise; such turns
+//! never run in a sensitive directory, including a cwd restored at startup or
+//! the app's own directory when none is set. All of these emit a
+//! `workspace-warning` event with the reason.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::approvals;
+use crate::settings::{load_settings, SensitiveCwdAction};
+
+#[cfg(not(windows))]
+const SYSTEM_DIRS: &[&str] = &[
+    "/bin",
+    "/boot",
+    "/dev",
+    "/etc",
+    "/lib",
+    "/lib64",
+    "/proc",
+    "/sbin",
+    "/sys",
+    "/usr",
+    "/var/cache",
+    "/var/db",
+    "/var/lib",
+    "/var/log",
+    "/var/mail",
+    "/var/root",
+    "/var/spool",
+    "/Applications",
+    "/Library",
+    "/System",
+    "/private/etc",
+    "/private/var/db",
+    "/private/var/log",
+    "/private/var/root",
+];
+
+/// Directories that are sensitive themselves but hold ordinary work below them
+/// (`/var/www`, macOS's `$TMPDIR` under `/private/var/folders`)
+#[cfg(not(windows))]
+const SYSTEM_PARENTS: &[&str] = &["/var", "/private", "/private/var"];
+
+#[cfg(windows)]
+const SYSTEM_PARENTS: &[&str] = &[];
+
+#[cfg(windows)]
+const SYSTEM_DIRS: &[&str] = &[
+    "C:\\Windows",
+    "C:\\Program Files",
+    "C:\\Program Files (x86)",
+    "C:\\ProgramData",
+];
+
+/// Payload of the `workspace-warning` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceWarning {
+    pub path: String,
+    pub reason: String,
+    /// The directory was not used
+    pub blocked: bool,
+}
+
+/// Drop the `\\?\` prefix `fs::canonicalize` adds on Windows
+fn strip_verbatim(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    PathBuf::from(text.strip_prefix(r"\\?\").unwrap_or(&text))
+}
+
+fn is_within(path: &Path, dir: &str) -> bool {
+    let lower = PathBuf::from(path.to_string_lossy().to_lowercase());
+    lower.starts_with(dir.to_lowercase())
+}
+
+/// Why a directory is unsafe as a workspace, if it is
+pub fn sensitive_reason(path: &Path, home: Option<&Path>) -> Option<String> {
+    if path.parent().is_none() {
+        return Some("the filesystem root".to_string());
+    }
+    if let Some(home) = home {
+        if path == home {
+            return Some("your home directory".to_string());
+        }
+        if home.starts_with(path) {
+            return Some("a parent of your home directory".to_string());
+        }
+    }
+    SYSTEM_DIRS
+        .iter()
+        .find(|dir| is_within(path, dir))
+        .or_else(|| SYSTEM_PARENTS.iter().find(|dir| path == Path::new(dir)))
+        .map(|dir| format!("a system directory ({})", dir))
+}
+
+/// Why a directory does not qualify for a turn without permission checks, if it doesn't
+pub fn git_clean_reason(dir: &Path) -> Option<String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(dir).args(["status", "--porcelain"]);
+
+    // On Windows, hide the terminal window
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    match cmd.output() {
+        Err(e) => Some(format!("git could not be run: {}", e)),
+        Ok(output) if !output.status.success() => Some("not a git repository".to_string()),
+        Ok(output) if !output.stdout.is_empty() => Some("the git working tree has uncommitted changes".to_string()),
+        Ok(_) => None,
+    }
+}
+
+fn warn(app: &tauri::AppHandle, path: &Path, reason: &str, blocked: bool) {
+    log_line!("[Rust] Workspace warning for {}: {}", path.display(), reason);
+    let warning = WorkspaceWarning {
+        path: path.display().to_string(),
+        reason: reason.to_string(),
+        blocked,
+    };
+    let _ = app.emit("workspace-warning", &warning);
+}
+
+/// The resolved path and why it is unsafe as a workspace, if it is
+pub fn sensitive_dir(path: &Path) -> Option<(PathBuf, String)> {
+    let canonical = fs::canonicalize(path)
+        .map(|p| strip_verbatim(&p))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let home = dirs::home_dir().map(|h| fs::canonicalize(&h).map(|p| strip_verbatim(&p)).unwrap_or(h));
+    let reason = sensitive_reason(&canonical, home.as_deref())?;
+    Some((canonical, reason))
+}
+
+/// Refuse or confirm a sensitive directory before it becomes the cwd
+/// Blocks while the user is asked, so it must not run on the main thread.
+pub fn check_cwd(app: &tauri::AppHandle, path: &Path) -> Result<(), String> {
+    let Some((canonical, reason)) = sensitive_dir(path) else {
+        return Ok(());
+    };
+
+    let allowed = match load_settings().sensitive_cwd_action {
+        SensitiveCwdAction::Allow => true,
+        SensitiveCwdAction::Refuse => false,
+        SensitiveCwdAction::Confirm => approvals::request(
+            app,
+            "Work in this folder?",
+            &format!("{}\n\nThis is {}.", canonical.display(), reason),
+        ),
+    };
+    warn(app, &canonical, &format!("This is {}.", reason), !allowed);
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!("Refusing to work in {}: it is {}", canonical.display(), reason))
+    }
+}
+
+/// Whether a turn may run without permission checks in `cwd`
+/// The directory is checked again here because it may not have gone through
+/// `check_cwd`: a cwd restored at startup, or the app's own directory when
+/// none is set.
+pub fn allow_unattended_turn(app: &tauri::AppHandle, cwd: Option<&str>) -> bool {
+    let dir = cwd
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    if let Some((canonical, reason)) = sensitive_dir(&dir) {
+        warn(
+            app,
+            &canonical,
+            &format!("This is {}, so permission checks stay on for this turn.", reason),
+            false,
+        );
+        return false;
+    }
+    if !load_settings().require_clean_git_repo {
+        return true;
+    }
+    match git_clean_reason(&dir) {
+        None => true,
+        Some(reason) => {
+            warn(
+                app,
+                &dir,
+                &format!("The folder is {}, so permission checks stay on for this turn.", reason),
+                false,
+            );
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_sensitive_reason() {
+        let home = Path::new("/home/alice");
+        let reason = |p: &str| sensitive_reason(Path::new(p), Some(home));
+
+        assert_eq!(reason("/").as_deref(), Some("the filesystem root"));
+        assert_eq!(reason("/home/alice").as_deref(), Some("your home directory"));
+        assert_eq!(reason("/home").as_deref(), Some("a parent of your home directory"));
+        assert_eq!(reason("/etc").as_deref(), Some("a system directory (/etc)"));
+        assert_eq!(reason("/usr/local/lib").as_deref(), Some("a system directory (/usr)"));
+        assert_eq!(reason("/home/alice/projects/supiki"), None);
+        assert_eq!(reason("/tmp/scratch"), None);
+        assert_eq!(reason("/var/log/nginx").as_deref(), Some("a system directory (/var/log)"));
+        assert_eq!(reason("/var").as_deref(), Some("a system directory (/var)"));
+        assert_eq!(reason("/var/www/site"), None);
+        assert_eq!(reason("/private/var/folders/xy/T/project"), None);
+        // Prefix matching is by path component
+        assert_eq!(reason("/usrdata/project"), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_sensitive_reason() {
+        let home = Path::new("C:\\Users\\alice");
+        let reason = |p: &str| sensitive_reason(Path::new(p), Some(home));
+
+        assert_eq!(reason("C:\\").as_deref(), Some("the filesystem root"));
+        assert_eq!(reason("C:\\Users").as_deref(), Some("a parent of your home directory"));
+        assert!(reason("c:\\windows\\System32").is_some());
+        assert_eq!(reason("C:\\Users\\alice\\code"), None);
+    }
+
+    #[test]
+    fn test_git_clean_reason() {
+        let dir = std::env::temp_dir().join(format!("supiki-guard-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let initialized = Command::new("git").arg("-C").arg(&dir).arg("init").output();
+        if initialized.map(|o| o.status.success()).unwrap_or(false) {
+            assert_eq!(git_clean_reason(&dir), None);
+            fs::write(dir.join("new.txt"), "x").unwrap();
+            assert_eq!(
+                git_clean_reason(&dir).as_deref(),
+                Some("the git working tree has uncommitted changes")
+            );
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
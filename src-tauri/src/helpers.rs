@@ -0,0 +1,246 @@
+//! Agent-controlled helper mascots
+//!
+//! Helpers reuse the `mini-mascot-N` windows that the frontend spawns for
+//! subagent tasks. The agent creates and dismisses them through the MCP
+//! server (via the bridge); the backend owns the windows, enforces the slot
+//! limit and cleans them up when the session is cleared.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::emotion::Emotion;
+use crate::utterance::MINI_WINDOW_PREFIX;
+
+/// Number of mini mascot windows (shared with the frontend's subagent mascots)
+pub const MAX_HELPERS: u32 = 5;
+
+/// Longest helper name, in characters
+const MAX_NAME_CHARS: usize = 32;
+
+/// Mini mascot window size (matches MINI_WINDOW_* in the frontend constants)
+const HELPER_WIDTH: f64 = 80.0;
+const HELPER_HEIGHT: f64 = 70.0;
+
+/// Gap between the main mascot and its helpers
+const HELPER_GAP: f64 = 10.0;
+
+/// A live helper mascot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Helper {
+    /// Window label, also usable as the `window` of the say tool
+    pub id: String,
+    pub name: String,
+    pub emotion: Emotion,
+}
+
+/// Helpers spawned by the agent
+static HELPERS: Mutex<Vec<Helper>> = Mutex::new(Vec::new());
+
+/// Request to spawn a helper
+#[derive(Debug, Clone, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct SpawnHelperRequest {
+    /// Short name describing the helper's subtask
+    pub name: String,
+    /// Initial emotion (default: neutral)
+    #[serde(default)]
+    #[schemars(with = "Option<Emotion>")]
+    pub emotion: Option<String>,
+}
+
+/// Request to dismiss a helper
+#[derive(Debug, Clone, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct DismissHelperRequest {
+    /// The helper id returned by spawn_helper or list_helpers
+    pub id: String,
+}
+
+fn label(slot: u32) -> String {
+    format!("{}{}", MINI_WINDOW_PREFIX, slot)
+}
+
+/// First slot without an open window
+fn free_slot(app: &tauri::AppHandle) -> Option<u32> {
+    (1..=MAX_HELPERS).find(|slot| app.get_webview_window(&label(*slot)).is_none())
+}
+
+/// Live helpers, dropping any whose window was closed
+pub fn list(app: &tauri::AppHandle) -> Vec<Helper> {
+    let mut helpers = HELPERS.lock().unwrap();
+    helpers.retain(|h| app.get_webview_window(&h.id).is_some());
+    helpers.clone()
+}
+
+/// Spawn a helper mascot next to the main mascot
+pub fn spawn(app: &tauri::AppHandle, request: &SpawnHelperRequest) -> Result<Helper, String> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err("Helper name must not be empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!("Helper name must be at most {} characters", MAX_NAME_CHARS));
+    }
+    let emotion = request
+        .emotion
+        .as_deref()
+        .map(str::parse::<Emotion>)
+        .transpose()?
+        .unwrap_or_default();
+
+    let slot = free_slot(app).ok_or_else(|| {
+        format!(
+            "All {} helper slots are in use. Dismiss a helper first.",
+            MAX_HELPERS
+        )
+    })?;
+    let id = label(slot);
+
+    // Stack helpers to the right of the main mascot, bottoms aligned
+    let (x, y) = match app.get_webview_window("main") {
+        Some(main) => {
+            let scale = main.scale_factor().unwrap_or(1.0);
+            let position = main.outer_position().map_err(|e| e.to_string())?;
+            let size = main.outer_size().map_err(|e| e.to_string())?;
+            let main_x = position.x as f64 / scale;
+            let main_y = position.y as f64 / scale;
+            let main_width = size.width as f64 / scale;
+            let main_height = size.height as f64 / scale;
+            (
+                main_x + main_width + HELPER_GAP + (slot - 1) as f64 * HELPER_WIDTH,
+                main_y + main_height - HELPER_HEIGHT,
+            )
+        }
+        None => (100.0, 100.0),
+    };
+
+    let url = format!("index.html?miniMascot=true&id={}&emotion={}", slot, emotion);
+    WebviewWindowBuilder::new(app, &id, WebviewUrl::App(url.into()))
+        .title(format!("Mini Supiki - {}", name))
+        .inner_size(HELPER_WIDTH, HELPER_HEIGHT)
+        .position(x, y)
+        .resizable(false)
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .shadow(false)
+        .build()
+        .map_err(|e| format!("Failed to create helper window: {}", e))?;
+
+    let helper = Helper {
+        id,
+        name: name.to_string(),
+        emotion,
+    };
+    HELPERS.lock().unwrap().push(helper.clone());
+    log_line!("[Rust] Spawned helper {} ({})", helper.id, helper.name);
+    Ok(helper)
+}
+
+/// Dismiss a helper spawned by the agent
+pub fn dismiss(app: &tauri::AppHandle, id: &str) -> Result<Helper, String> {
+    let helper = {
+        let mut helpers = HELPERS.lock().unwrap();
+        let index = helpers
+            .iter()
+            .position(|h| h.id == id)
+            .ok_or_else(|| format!("No helper with id '{}'", id))?;
+        helpers.remove(index)
+    };
+    if let Some(window) = app.get_webview_window(&helper.id) {
+        let _ = window.close();
+    }
+    log_line!("[Rust] Dismissed helper {} ({})", helper.id, helper.name);
+    Ok(helper)
+}
+
+/// Close every helper spawned by the agent (e.g. when the session is cleared)
+pub fn dismiss_all(app: &tauri::AppHandle) {
+    let helpers: Vec<Helper> = HELPERS.lock().unwrap().drain(..).collect();
+    for helper in helpers {
+        if let Some(window) = app.get_webview_window(&helper.id) {
+            let _ = window.close();
+        }
+    }
+}
+
+/// Forget a helper whose window was closed by other means
+pub fn forget(label: &str) {
+    HELPERS.lock().unwrap().retain(|h| h.id != label);
+}
+
+/// Bridge handler for spawn_helper
+pub fn spawn_from_params(app: &tauri::AppHandle, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    let request: SpawnHelperRequest =
+        serde_json::from_value(params).map_err(|e| format!("Invalid spawn_helper params: {}", e))?;
+    let helper = spawn(app, &request)?;
+    Ok(serde_json::json!({ "helper": helper, "helpers": list(app) }))
+}
+
+/// Bridge handler for dismiss_helper
+pub fn dismiss_from_params(app: &tauri::AppHandle, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    let request: DismissHelperRequest =
+        serde_json::from_value(params).map_err(|e| format!("Invalid dismiss_helper params: {}", e))?;
+    let helper = dismiss(app, &request.id)?;
+    Ok(serde_json::json!({ "helper": helper, "helpers": list(app) }))
+}
+
+/// Bridge handler for list_helpers
+pub fn list_value(app: &tauri::AppHandle) -> Result<serde_json::Value, String> {
+    Ok(serde_json::json!({ "helpers": list(app) }))
+}
+
+/// Describe live helpers for the model
+pub fn describe(helpers: &[Helper]) -> String {
+    if helpers.is_empty() {
+        return "No helpers are active.".to_string();
+    }
+    let lines: Vec<String> = helpers
+        .iter()
+        .map(|h| format!("- {}: {} ({})", h.id, h.name, h.emotion))
+        .collect();
+    format!(
+        "Active helpers ({}/{}):\n{}",
+        helpers.len(),
+        MAX_HELPERS,
+        lines.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        assert_eq!(label(3), "mini-mascot-3");
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(&[]), "No helpers are active.");
+        let helpers = vec![Helper {
+            id: label(1),
+            name: "tests".to_string(),
+            emotion: Emotion::Thinking,
+        }];
+        assert_eq!(
+            describe(&helpers),
+            "Active helpers (1/5):\n- mini-mascot-1: tests (thinking)"
+        );
+    }
+
+    #[test]
+    fn test_helper_serialization() {
+        let helper = Helper {
+            id: label(2),
+            name: "docs".to_string(),
+            emotion: Emotion::Happy,
+        };
+        let json = serde_json::to_value(&helper).unwrap();
+        assert_eq!(json["id"], "mini-mascot-2");
+        assert_eq!(json["emotion"], "happy");
+    }
+}
@@ -0,0 +1,271 @@
+//! Shared image encoding pipeline
+//!
+//! Used for MCP screenshots and for images attached to chat messages.
+//! Images are resized to a maximum dimension, optionally converted to
+//! grayscale, and encoded as lossless WebP or lossy JPEG. When a byte budget
+//! is set, quality and then size are reduced until the result fits.
+
+use std::io::Cursor;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageFormat, RgbaImage};
+
+/// Quality at or above which images are encoded as lossless WebP
+pub const LOSSLESS_QUALITY: u8 = 100;
+
+/// Lowest JPEG quality tried while fitting a byte budget
+const MIN_QUALITY: u8 = 40;
+
+/// Quality step used while fitting a byte budget
+const QUALITY_STEP: u8 = 10;
+
+/// Quality used when a lossless image does not fit the byte budget
+const FALLBACK_QUALITY: u8 = 85;
+
+/// Each downscale pass shrinks the image to this fraction
+const DOWNSCALE_FACTOR: f32 = 0.75;
+
+/// Images are never downscaled below this long edge to fit a budget,
+/// and requested sizes are raised to it
+pub const MIN_DIMENSION: u32 = 256;
+
+/// Most tiles a capture is split into; tiles grow to stay within it
+pub const MAX_TILES: usize = 16;
+
+/// Approximate pixels per token for Claude's vision input
+const PIXELS_PER_TOKEN: u32 = 750;
+
+/// Options controlling how an image is encoded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageOptions {
+    /// Longest edge in pixels; larger images are downscaled
+    pub max_dimension: u32,
+    /// 1-99 encodes lossy JPEG, 100 encodes lossless WebP
+    pub quality: u8,
+    pub grayscale: bool,
+    /// Target size of the encoded image in bytes
+    pub max_bytes: Option<usize>,
+    pub filter: FilterType,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            max_dimension: 2560,
+            quality: LOSSLESS_QUALITY,
+            grayscale: false,
+            max_bytes: None,
+            filter: FilterType::Triangle,
+        }
+    }
+}
+
+impl ImageOptions {
+    /// Options for images attached to chat messages
+    /// Claude downscales anything with a longer edge than 1568px anyway
+    pub fn for_attachments() -> Self {
+        Self {
+            max_dimension: 1568,
+            quality: FALLBACK_QUALITY,
+            max_bytes: Some(3_750_000),
+            ..Self::default()
+        }
+    }
+}
+
+/// An encoded image ready to be sent to the model
+#[derive(Debug, Clone)]
+pub struct EncodedImage {
+    pub data: Vec<u8>,
+    pub media_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl EncodedImage {
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(&self.data)
+    }
+
+    /// Estimated vision token cost of the image
+    pub fn estimated_tokens(&self) -> u32 {
+        estimate_tokens(self.width, self.height)
+    }
+}
+
+/// Estimate the vision token cost of an image with the given dimensions
+pub fn estimate_tokens(width: u32, height: u32) -> u32 {
+    ((width as u64 * height as u64) / PIXELS_PER_TOKEN as u64).max(1) as u32
+}
+
+/// A requested long edge, kept between `MIN_DIMENSION` and `limit`
+pub fn clamp_dimension(requested: u32, limit: u32) -> u32 {
+    requested.clamp(MIN_DIMENSION, limit.max(MIN_DIMENSION))
+}
+
+/// Scale dimensions so the longest edge is at most `max_dimension`
+fn fit_dimensions(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    if longest <= max_dimension || max_dimension == 0 {
+        return (width, height);
+    }
+    let scale = max_dimension as f32 / longest as f32;
+    (
+        ((width as f32 * scale) as u32).max(1),
+        ((height as f32 * scale) as u32).max(1),
+    )
+}
+
+/// Encode an image with the given quality
+fn encode(img: &DynamicImage, quality: u8) -> Result<(Vec<u8>, &'static str), String> {
+    let mut buffer = Cursor::new(Vec::new());
+    if quality >= LOSSLESS_QUALITY {
+        img.write_to(&mut buffer, ImageFormat::WebP)
+            .map_err(|e| format!("Failed to encode WebP: {}", e))?;
+        Ok((buffer.into_inner(), "image/webp"))
+    } else {
+        // JPEG has no alpha channel
+        let rgb = DynamicImage::ImageRgb8(img.to_rgb8());
+        rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, quality.max(1)))
+            .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+        Ok((buffer.into_inner(), "image/jpeg"))
+    }
+}
+
+/// Resize, convert and encode an image according to `options`
+pub fn process(img: DynamicImage, options: &ImageOptions) -> Result<EncodedImage, String> {
+    let (width, height) = fit_dimensions(img.width(), img.height(), options.max_dimension);
+    let mut img = if (width, height) != (img.width(), img.height()) {
+        img.resize_exact(width, height, options.filter)
+    } else {
+        img
+    };
+    if options.grayscale {
+        img = DynamicImage::ImageLuma8(img.to_luma8());
+    }
+
+    let mut quality = options.quality;
+    loop {
+        let (data, media_type) = encode(&img, quality)?;
+        let fits = options.max_bytes.is_none_or(|max| data.len() <= max);
+        let longest = img.width().max(img.height());
+        if fits || (quality <= MIN_QUALITY && longest <= MIN_DIMENSION) {
+            return Ok(EncodedImage {
+                data,
+                media_type,
+                width: img.width(),
+                height: img.height(),
+            });
+        }
+
+        // Over budget: lower quality first, then shrink the image
+        if quality >= LOSSLESS_QUALITY {
+            quality = FALLBACK_QUALITY;
+        } else if quality > MIN_QUALITY {
+            quality = quality.saturating_sub(QUALITY_STEP).max(MIN_QUALITY);
+        } else {
+            let target = ((longest as f32 * DOWNSCALE_FACTOR) as u32).max(MIN_DIMENSION);
+            let (w, h) = fit_dimensions(img.width(), img.height(), target);
+            img = img.resize_exact(w, h, options.filter);
+        }
+    }
+}
+
+/// A tile cut from a larger image
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub image: RgbaImage,
+}
+
+/// Split an image into tiles no larger than `tile_size` on either edge
+/// Tiles are enlarged as needed so there are at most `max_tiles`.
+pub fn split_tiles(img: &RgbaImage, tile_size: u32, max_tiles: usize) -> Vec<Tile> {
+    let mut tile_size = tile_size.max(1);
+    let count = |size: u32| (img.width().div_ceil(size) as usize) * (img.height().div_ceil(size) as usize);
+    while count(tile_size) > max_tiles.max(1) {
+        tile_size += tile_size / 4 + 1;
+    }
+    let mut tiles = Vec::new();
+    for y in (0..img.height()).step_by(tile_size as usize) {
+        for x in (0..img.width()).step_by(tile_size as usize) {
+            let width = tile_size.min(img.width() - x);
+            let height = tile_size.min(img.height() - y);
+            let image = image::imageops::crop_imm(img, x, y, width, height).to_image();
+            tiles.push(Tile { x, y, image });
+        }
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(width: u32, height: u32) -> DynamicImage {
+        let img = RgbaImage::from_fn(width, height, |x, y| {
+            let v = (x.wrapping_mul(7919) ^ y.wrapping_mul(104_729)) as u8;
+            image::Rgba([v, v.wrapping_mul(3), v.wrapping_add(91), 255])
+        });
+        DynamicImage::ImageRgba8(img)
+    }
+
+    #[test]
+    fn test_fit_dimensions() {
+        assert_eq!(fit_dimensions(4000, 2000, 2000), (2000, 1000));
+        assert_eq!(fit_dimensions(800, 600, 2000), (800, 600));
+        assert_eq!(fit_dimensions(100, 5000, 1000), (20, 1000));
+    }
+
+    #[test]
+    fn test_process_lossless_and_lossy() {
+        let lossless = process(noise(300, 200), &ImageOptions::default()).unwrap();
+        assert_eq!(lossless.media_type, "image/webp");
+        assert_eq!((lossless.width, lossless.height), (300, 200));
+
+        let options = ImageOptions { quality: 60, max_dimension: 150, ..ImageOptions::default() };
+        let lossy = process(noise(300, 200), &options).unwrap();
+        assert_eq!(lossy.media_type, "image/jpeg");
+        assert_eq!((lossy.width, lossy.height), (150, 100));
+    }
+
+    #[test]
+    fn test_process_fits_byte_budget() {
+        let options = ImageOptions { max_bytes: Some(20_000), ..ImageOptions::default() };
+        let encoded = process(noise(1024, 1024), &options).unwrap();
+        assert!(encoded.data.len() <= 20_000, "{} bytes", encoded.data.len());
+    }
+
+    #[test]
+    fn test_split_tiles_covers_image() {
+        let img = noise(250, 120).to_rgba8();
+        let tiles = split_tiles(&img, 100, MAX_TILES);
+        assert_eq!(tiles.len(), 6);
+        let area: u32 = tiles.iter().map(|t| t.image.width() * t.image.height()).sum();
+        assert_eq!(area, 250 * 120);
+        assert_eq!((tiles[5].x, tiles[5].y), (200, 100));
+    }
+
+    #[test]
+    fn test_split_tiles_caps_count() {
+        let img = noise(250, 120).to_rgba8();
+        let tiles = split_tiles(&img, 1, 4);
+        assert!(tiles.len() <= 4);
+        let area: u32 = tiles.iter().map(|t| t.image.width() * t.image.height()).sum();
+        assert_eq!(area, 250 * 120);
+    }
+
+    #[test]
+    fn test_clamp_dimension() {
+        assert_eq!(clamp_dimension(1, 2560), MIN_DIMENSION);
+        assert_eq!(clamp_dimension(100_000, 2560), 2560);
+        assert_eq!(clamp_dimension(1024, 2560), 1024);
+        assert_eq!(clamp_dimension(1024, 0), MIN_DIMENSION);
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(1000, 750), 1000);
+        assert_eq!(estimate_tokens(1, 1), 1);
+    }
+}
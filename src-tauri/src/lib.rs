@@ -4,32 +4,79 @@
 //! The codebase is organized into the following modules:
 //!
 //! - `state`: Global application state and session persistence
+//! - `approvals`: Per-call user confirmations for agent actions
+//! - `audit`: Hash-chained audit log of agent tool activity
+//! - `bridge`: Localhost bridge letting the MCP server process call into the GUI
+//! - `checkpoints`: Workspace snapshots before each turn and rollback of agent edits
 //! - `claude_runner`: Claude CLI process management
 //! - `commands`: Tauri IPC commands exposed to the frontend
+//! - `emotion`: The `Emotion` enum shared by the MCP server, runners and bindings
+//! - `file_edits`: Per-turn unified diffs of agent file-editing tool calls
+//! - `guardrails`: Refuses risky working directories and unattended turns outside clean git repos
+//! - `helpers`: Agent-spawned helper mascot windows
+//! - `logging`: Redacting log macros (`log_line!`, `log_content!`)
+//! - `image_pipeline`: Shared image resizing/encoding for screenshots and attachments
 //! - `mcp_server`: MCP server for mascot control (run with --mcp flag)
+//! - `movement`: Resolves move_to anchors against monitor geometry
+//! - `opener`: Allow-listed opening of URLs and paths with the system handler
+//! - `permissions`: Permission prompts for Claude tool calls
+//! - `policy`: Declarative tool permission policy for both backends
+//! - `reminders`: Persisted reminders fired by a scheduler thread
+//! - `screenshots`: Screenshot consent and the local capture history
+//! - `sequences`: Scriptable animation sequences and the named sequence library
+//! - `settings`: User settings shared with the MCP server (screenshot privacy)
+//! - `tempfiles`: Private, owner-tracked temp files cleaned up at turn end and exit
+//! - `tts`: Offline text-to-speech through a local engine command
+//! - `utterance`: Speech-bubble utterances from the say tool
 
+#[macro_use]
+mod logging;
+mod approvals;
+mod audit;
+mod bridge;
+mod checkpoints;
 mod claude;
 mod codex;
 mod commands;
+mod emotion;
+mod file_edits;
+mod guardrails;
+mod helpers;
+mod image_pipeline;
 pub mod mcp_server;
+mod movement;
+mod opener;
+mod permissions;
+mod policy;
+mod reminders;
+mod screenshots;
+mod sequences;
+mod settings;
 mod state;
+mod tempfiles;
+mod tts;
+mod utterance;
 
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, WindowEvent,
+    Manager, RunEvent, WindowEvent,
 };
 
 
 use commands::{
     answer_agent_question, check_claude_cli, check_codex_cli, clear_agent_session,
     clear_claude_session_cmd, clear_codex_session_cmd, confirm_plan_mode_exit,
-    deny_plan_mode_exit, get_actual_cwd, get_backend_mode, get_codex_session_id,
-    get_recent_cwds, get_session_id, get_sidecar_cwd, is_dev_mode, is_supiki_mode,
-    open_image_in_viewer, pick_folder, quit_app, send_agent_message, set_backend_mode,
-    set_sidecar_cwd, stop_sidecar,
+    delete_screenshot, deny_plan_mode_exit, export_audit_turn, get_actual_cwd,
+    get_app_settings, get_approval_request, get_backend_mode, get_codex_sandbox,
+    get_codex_session_id, get_effective_policy, get_recent_cwds, get_screenshot_history,
+    get_session_id, get_sidecar_cwd, is_dev_mode, is_supiki_mode, list_checkpoints,
+    open_image_in_viewer, pick_folder, purge_screenshot_history, query_audit_log, quit_app,
+    respond_approval, restore_checkpoint, send_agent_message, set_app_settings,
+    set_backend_mode, set_language, set_sidecar_cwd, stop_sidecar, stop_speaking,
+    sync_chat_sessions, uninstall_codex_mcp_config, verify_audit_log,
 };
-use state::{DEV_MODE, SUPIKI_MODE, SIDECAR_CWD, RECENT_CWDS, load_cwd_from_disk, load_recent_cwds_from_disk};
+use state::{DEV_MODE, SUPIKI_MODE, SIDECAR_CWD, RECENT_CWDS, load_cwd_from_disk, load_recent_cwds_from_disk, spawn_mascot_snapshot_writer, update_mascot_position};
 
 /// Create the tauri-specta builder with all commands registered
 /// This is extracted so it can be reused for codegen
@@ -49,7 +96,24 @@ pub fn create_specta_builder() -> tauri_specta::Builder<tauri::Wry> {
         set_sidecar_cwd,
         get_sidecar_cwd,
         get_actual_cwd,
+        get_effective_policy,
+        get_codex_sandbox,
         get_recent_cwds,
+        sync_chat_sessions,
+        get_app_settings,
+        set_app_settings,
+        get_approval_request,
+        respond_approval,
+        query_audit_log,
+        verify_audit_log,
+        export_audit_turn,
+        get_screenshot_history,
+        delete_screenshot,
+        purge_screenshot_history,
+        list_checkpoints,
+        restore_checkpoint,
+        set_language,
+        stop_speaking,
         pick_folder,
         check_claude_cli,
         // Codex-related commands
@@ -58,8 +122,16 @@ pub fn create_specta_builder() -> tauri_specta::Builder<tauri::Wry> {
         set_backend_mode,
         get_codex_session_id,
         clear_codex_session_cmd,
+        uninstall_codex_mcp_config,
         clear_claude_session_cmd
     ])
+    // Event payloads the frontend listens to
+    .typ::<emotion::EmotionEvent>()
+    .typ::<approvals::ApprovalRequest>()
+    .typ::<tts::TtsAudioEvent>()
+    .typ::<guardrails::WorkspaceWarning>()
+    .typ::<checkpoints::CheckpointDiff>()
+    .typ::<file_edits::AgentFileChanges>()
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -69,11 +141,11 @@ pub fn run() {
         if let Some(exe_name) = exe_path.file_stem().and_then(|s| s.to_str()) {
             if exe_name.contains("_dev") || exe_name.contains("-dev") {
                 *DEV_MODE.lock().unwrap() = true;
-                println!("[Rust] DEV mode enabled via executable name: {}", exe_name);
+                log_line!("[Rust] DEV mode enabled via executable name: {}", exe_name);
             }
             if exe_name.contains("supiki") {
                 *SUPIKI_MODE.lock().unwrap() = true;
-                println!("[Rust] SUPIKI mode enabled via executable name: {}", exe_name);
+                log_line!("[Rust] SUPIKI mode enabled via executable name: {}", exe_name);
             }
         }
     }
@@ -82,28 +154,42 @@ pub fn run() {
     let args: Vec<String> = std::env::args().collect();
     if args.contains(&"--dev".to_string()) {
         *DEV_MODE.lock().unwrap() = true;
-        println!("[Rust] DEV mode enabled via --dev flag");
+        log_line!("[Rust] DEV mode enabled via --dev flag");
     }
 
     // Also check CLAWD_DEV_MODE environment variable
     if std::env::var("CLAWD_DEV_MODE").unwrap_or_default() == "1" {
         *DEV_MODE.lock().unwrap() = true;
-        println!("[Rust] DEV mode enabled via CLAWD_DEV_MODE env var");
+        log_line!("[Rust] DEV mode enabled via CLAWD_DEV_MODE env var");
     }
+    logging::init(*DEV_MODE.lock().unwrap());
+
+    // Opt-in Streamable HTTP transport for the MCP server (--mcp-http or SUPIKI_MCP_HTTP=1)
+    let mcp_http_port = if args.contains(&"--mcp-http".to_string())
+        || std::env::var("SUPIKI_MCP_HTTP").unwrap_or_default() == "1"
+    {
+        let port = std::env::var("SUPIKI_MCP_HTTP_PORT")
+            .ok()
+            .and_then(|p| p.parse::<u16>().ok())
+            .unwrap_or(mcp_server::http::DEFAULT_PORT);
+        Some(port)
+    } else {
+        None
+    };
 
     // Also check VITE_MASCOT_TYPE environment variable for supiki mode
     if std::env::var("VITE_MASCOT_TYPE").unwrap_or_default() == "supiki" {
         *SUPIKI_MODE.lock().unwrap() = true;
-        println!("[Rust] SUPIKI mode enabled via VITE_MASCOT_TYPE env var");
+        log_line!("[Rust] SUPIKI mode enabled via VITE_MASCOT_TYPE env var");
     }
 
     // Check if Claude CLI is available
     match claude::check_claude_available() {
         Ok(version) => {
-            println!("[Rust] Claude CLI available: {}", version);
+            log_line!("[Rust] Claude CLI available: {}", version);
         }
         Err(e) => {
-            eprintln!("[Rust] Warning: {}", e);
+            log_line!("[Rust] Warning: {}", e);
             // Continue anyway - user might install it later
         }
     }
@@ -111,16 +197,20 @@ pub fn run() {
     // Check if Codex CLI is available
     match codex::check_codex_available() {
         Ok(version) => {
-            println!("[Rust] Codex CLI available: {}", version);
+            log_line!("[Rust] Codex CLI available: {}", version);
         }
         Err(e) => {
-            eprintln!("[Rust] Warning: {}", e);
+            log_line!("[Rust] Warning: {}", e);
             // Continue anyway - user might install it later
         }
     }
 
     // Load persisted cwd from disk
     if let Some(cwd) = load_cwd_from_disk() {
+        // Turns without permission checks are kept out of it later on
+        if let Some((path, reason)) = guardrails::sensitive_dir(std::path::Path::new(&cwd)) {
+            log_line!("[Rust] Restored cwd {} is {}", path.display(), reason);
+        }
         *SIDECAR_CWD.lock().unwrap() = Some(cwd);
     }
 
@@ -129,7 +219,7 @@ pub fn run() {
         let loaded = load_recent_cwds_from_disk();
         if !loaded.is_empty() {
             *RECENT_CWDS.lock().unwrap() = loaded;
-            println!("[Rust] Loaded {} recent cwds from disk", RECENT_CWDS.lock().unwrap().len());
+            log_line!("[Rust] Loaded {} recent cwds from disk", RECENT_CWDS.lock().unwrap().len());
         }
     }
 
@@ -150,7 +240,7 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(builder.invoke_handler())
-        .setup(|app| {
+        .setup(move |app| {
             // Start with fresh session on each launch
             // (Don't load persisted session - each launch is a new conversation)
             // Note: Sessions are still saved for chat history feature
@@ -159,14 +249,22 @@ pub fn run() {
             let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
             let show = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
             let hide = MenuItem::with_id(app, "hide", "Hide", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show, &hide, &quit])?;
+            let pause_screenshots = CheckMenuItem::with_id(
+                app,
+                "pause_screenshots",
+                "Pause Screenshots",
+                true,
+                settings::load_settings().screenshots_paused,
+                None::<&str>,
+            )?;
+            let menu = Menu::with_items(app, &[&show, &hide, &pause_screenshots, &quit])?;
 
             // Create tray icon
             let _tray = TrayIconBuilder::new()
                 .icon(app.default_window_icon().unwrap().clone())
                 .menu(&menu)
                 .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id.as_ref() {
+                .on_menu_event(move |app, event| match event.id.as_ref() {
                     "quit" => {
                         // Close all windows properly before exiting
                         for (_, window) in app.webview_windows() {
@@ -185,6 +283,15 @@ pub fn run() {
                             let _ = window.hide();
                         }
                     }
+                    "pause_screenshots" => {
+                        match settings::update_settings(|s| s.screenshots_paused = !s.screenshots_paused) {
+                            Ok(updated) => {
+                                let _ = pause_screenshots.set_checked(updated.screenshots_paused);
+                                log_line!("[Rust] Screenshots paused: {}", updated.screenshots_paused);
+                            }
+                            Err(e) => log_line!("[Rust] {}", e),
+                        }
+                    }
                     _ => {}
                 })
                 .on_tray_icon_event(|tray, event| {
@@ -203,25 +310,69 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            // Remove temp files a crashed or killed run left behind
+            tempfiles::sweep_orphans();
+
+            // Fire reminders set by the agent (including ones due while the app was closed)
+            reminders::spawn_scheduler(app.handle().clone());
+
+            // Share mascot emotion/position with the MCP server's resources
+            spawn_mascot_snapshot_writer();
+
+            // Let the MCP server process reach the GUI for tools that need a reply
+            if let Err(e) = bridge::start(app.handle().clone()) {
+                log_line!("[Rust] {}", e);
+            }
+
+            // Host the MCP server over localhost HTTP for other local clients
+            if let Some(port) = mcp_http_port {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = mcp_server::http::serve(handle, port).await {
+                        log_line!("[Rust] MCP HTTP transport failed: {}", e);
+                    }
+                });
+            }
+
             Ok(())
         })
         .on_window_event(|window, event| {
+            // Track the mascot's position for the MCP server's resources
+            if let WindowEvent::Moved(position) = event {
+                if window.label() == "main" {
+                    update_mascot_position(position.x, position.y);
+                }
+            }
+
+            // Drop helpers whose window was closed by the user;
+            // closing an approval window without answering denies it
+            if let WindowEvent::Destroyed = event {
+                helpers::forget(window.label());
+                approvals::window_closed(window.label());
+            }
+
             // Only hide main window to tray - let other windows close normally
             if let WindowEvent::CloseRequested { api, .. } = event {
                 let label = window.label();
                 if label == "main" {
-                    println!("[Rust] Hiding main window to tray");
+                    log_line!("[Rust] Hiding main window to tray");
                     let _ = window.hide();
                     api.prevent_close();
                 }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            if let RunEvent::Exit = event {
+                tempfiles::release_all();
+            }
+        });
 }
 
 /// Run the MCP server (called when --mcp flag is passed)
 pub fn run_mcp_server() {
+    logging::init(false);
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
@@ -229,3 +380,48 @@ pub fn run_mcp_server() {
         .block_on(mcp_server::run())
         .expect("MCP server failed");
 }
+
+/// Print to the console that started the process
+/// Windows release builds use the GUI subsystem and get no console of their
+/// own, so the report would be invisible otherwise. Redirected output already
+/// has handles and is left alone.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+        fn GetStdHandle(std_handle: u32) -> *mut std::ffi::c_void;
+    }
+
+    // SAFETY: both calls take plain integers and only change this process's console
+    unsafe {
+        let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+        if handle.is_null() || handle as isize == -1 {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+}
+
+/// Run the MCP self-test (called when --mcp-selftest flag is passed)
+/// Returns the process exit code: 1 if a check failed, 2 if the test could not run
+pub fn run_mcp_selftest() -> i32 {
+    #[cfg(windows)]
+    attach_parent_console();
+
+    let result = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(mcp_server::selftest::run());
+    match result {
+        Ok(0) => 0,
+        Ok(_) => 1,
+        Err(e) => {
+            log_line!("[MCP] Self-test could not run: {}", e);
+            2
+        }
+    }
+}
@@ -1,6 +1,11 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Diagnose the MCP server without starting Tauri
+    if std::env::args().any(|a| a == "--mcp-selftest") {
+        std::process::exit(supiki_lib::run_mcp_selftest());
+    }
+
     // Check for MCP mode BEFORE starting Tauri
     // When launched with --mcp, run as an MCP server via stdio
     if std::env::args().any(|a| a == "--mcp") {
@@ -0,0 +1,255 @@
+//! Streamable HTTP transport for the mascot MCP server
+//!
+//! Hosted inside the running GUI process so editors and other agents on the
+//! same machine can drive the mascot without spawning `supiki --mcp`.
+//! Only binds to localhost and requires a bearer token that is stored in the
+//! app data directory. Requests from a browser page on another origin are
+//! refused, which blocks DNS-rebinding attacks.
+
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use rmcp::transport::streamable_http_server::{
+    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+};
+use tokio::net::TcpListener;
+
+use super::MascotService;
+use crate::state;
+
+/// Default localhost port for the HTTP transport
+pub const DEFAULT_PORT: u16 = 47823;
+
+/// Path the MCP endpoint is served under
+pub const MCP_PATH: &str = "/mcp";
+
+/// Length of the generated bearer token
+const TOKEN_LENGTH: usize = 32;
+
+/// Get the token file path (kept stable across restarts so client configs stay valid)
+pub fn get_token_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("mcp-http-token"))
+}
+
+/// Get the endpoint file path (tells local clients where the server listens)
+pub fn get_endpoint_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("mcp-http.json"))
+}
+
+/// Generate a random alphanumeric token
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Load the bearer token from disk, creating it on first use
+pub fn load_or_create_token() -> Result<String> {
+    let path = get_token_file_path().ok_or_else(|| anyhow::anyhow!("Could not find data directory"))?;
+    if let Ok(token) = fs::read_to_string(&path) {
+        let token = token.trim().to_string();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let token = generate_token();
+    state::write_secret(&path, &token)?;
+    Ok(token)
+}
+
+/// Whether an `Origin` header names a page served from this machine
+fn is_local_origin(origin: &str) -> bool {
+    let Some(rest) = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    else {
+        return false;
+    };
+    let (host, port) = match rest.strip_prefix("[::1]") {
+        Some(port) => ("[::1]", port),
+        None => rest.split_at(rest.find(':').unwrap_or(rest.len())),
+    };
+    let port_ok = match port.strip_prefix(':') {
+        Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()),
+        None => port.is_empty(),
+    };
+    port_ok && matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Reject requests from other origins and without the expected bearer token
+async fn require_token(State(token): State<Arc<String>>, request: Request, next: Next) -> Response {
+    // Clients outside a browser send no Origin; pages elsewhere must not get in
+    let foreign = request
+        .headers()
+        .get(header::ORIGIN)
+        .is_some_and(|value| !value.to_str().is_ok_and(is_local_origin));
+    if foreign {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| state::secrets_match(provided, &token));
+
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+/// Build the router serving the MCP endpoint behind token auth
+fn router(app: Option<tauri::AppHandle>, token: String) -> Router {
+    let service = StreamableHttpService::new(
+        move || Ok(MascotService::with_app(app.clone())),
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig::default(),
+    );
+
+    Router::new()
+        .nest_service(MCP_PATH, service)
+        .layer(middleware::from_fn_with_state(Arc::new(token), require_token))
+}
+
+/// Serve the MCP endpoint on an already bound listener
+pub async fn serve_on(listener: TcpListener, app: Option<tauri::AppHandle>, token: String) -> Result<()> {
+    axum::serve(listener, router(app, token)).await?;
+    Ok(())
+}
+
+/// Start the HTTP transport on localhost and record the endpoint for local clients
+pub async fn serve(app: tauri::AppHandle, port: u16) -> Result<()> {
+    let token = load_or_create_token()?;
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port))).await?;
+    let addr = listener.local_addr()?;
+    let url = format!("http://{}{}", addr, MCP_PATH);
+
+    if let Some(path) = get_endpoint_file_path() {
+        let endpoint = serde_json::json!({
+            "url": url,
+            "tokenFile": get_token_file_path(),
+        });
+        let _ = fs::write(&path, serde_json::to_string_pretty(&endpoint)?);
+    }
+
+    log_line!("[Rust] MCP HTTP transport listening on {}", url);
+    serve_on(listener, Some(app), token).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    /// Send an initialize request and return the raw response head
+    async fn initialize(addr: SocketAddr, token: Option<&str>) -> String {
+        initialize_from(addr, token, None).await
+    }
+
+    /// Like `initialize`, sent from a browser page at `origin`
+    async fn initialize_from(addr: SocketAddr, token: Option<&str>, origin: Option<&str>) -> String {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0.0.0"}}}"#;
+        let mut auth = token
+            .map(|t| format!("Authorization: Bearer {}\r\n", t))
+            .unwrap_or_default();
+        if let Some(origin) = origin {
+            auth.push_str(&format!("Origin: {}\r\n", origin));
+        }
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nAccept: application/json, text/event-stream\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            MCP_PATH,
+            addr,
+            auth,
+            body.len(),
+            body
+        );
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !response.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            response.extend_from_slice(&buf[..n]);
+        }
+        String::from_utf8_lossy(&response).to_string()
+    }
+
+    async fn start_server(token: &str) -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_on(listener, None, token.to_string()));
+        addr
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token();
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(token, generate_token());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_missing_token() {
+        let addr = start_server("secret").await;
+        let response = initialize(addr, None).await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{}", response);
+
+        let response = initialize(addr, Some("wrong")).await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{}", response);
+    }
+
+    #[test]
+    fn test_is_local_origin() {
+        assert!(is_local_origin("http://localhost:3000"));
+        assert!(is_local_origin("http://127.0.0.1"));
+        assert!(is_local_origin("https://[::1]:8443"));
+        assert!(!is_local_origin("http://evil.example:47823"));
+        assert!(!is_local_origin("http://localhost.evil.example"));
+        assert!(!is_local_origin("http://127.0.0.1.nip.io:47823"));
+        assert!(!is_local_origin("null"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_foreign_origin() {
+        let addr = start_server("secret").await;
+        let response = initialize_from(addr, Some("secret"), Some("http://rebound.example:47823")).await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{}", response);
+
+        let response = initialize_from(addr, Some("secret"), Some("http://localhost:5173")).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn test_initialize_with_token() {
+        let addr = start_server("secret").await;
+        let response = initialize(addr, Some("secret")).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.to_lowercase().contains("mcp-session-id"), "{}", response);
+    }
+}
@@ -0,0 +1,711 @@
+//! MCP server for controlling the Supiki desktop mascot.
+//!
+//! This server provides tools that Claude can use to control the mascot's
+//! emotions and movement. The actual mascot control happens when Tauri
+//! parses the tool_use events from Claude CLI's output stream.
+//!
+//! It also offers resources describing Supiki's context (see `resources`).
+//!
+//! This module is run when the executable is launched with the `--mcp` flag.
+//! The GUI can additionally host it over Streamable HTTP (see `http`); in that
+//! case there is no CLI stream to parse, so tools emit the events directly.
+//!
+//! `--mcp-selftest` runs every tool against an in-process client (see `selftest`).
+
+pub mod http;
+mod privacy;
+mod resources;
+pub mod selftest;
+mod watch;
+
+use std::future::Future;
+
+use anyhow::Result;
+use tauri::Emitter;
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, tool::Parameters},
+    model::*,
+    schemars,
+    service::RequestContext,
+    tool, tool_handler, tool_router, RoleServer, ServerHandler, ServiceExt,
+};
+use xcap::Monitor;
+
+use crate::bridge;
+use crate::emotion::{Emotion, EmotionEvent};
+use crate::helpers::{self, DismissHelperRequest, Helper, SpawnHelperRequest};
+use crate::image_pipeline::{self, ImageOptions};
+use crate::movement::{self, MoveToRequest};
+use crate::opener::{OpenPathRequest, OpenUrlRequest};
+use crate::permissions::{PermissionDecision, PermissionPromptRequest};
+use crate::reminders;
+use crate::screenshots::{self, ConsentReply};
+use crate::sequences::{self, PlaySequenceRequest};
+use crate::settings::{self, ScreenshotConsent};
+use crate::tts::{self, SpeakRequest};
+use crate::utterance::{self, SayRequest};
+
+/// Request to set the mascot's emotional expression
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct SetEmotionRequest {
+    /// The emotion to display
+    // Kept as a string so invalid values produce a helpful tool error
+    #[schemars(with = "Emotion")]
+    emotion: String,
+    /// Duration in milliseconds (default: 5000)
+    #[serde(default)]
+    duration_ms: Option<u32>,
+    /// Strength of the expression from 0.0 to 1.0 (default: 1.0)
+    #[serde(default)]
+    intensity: Option<f32>,
+    /// Emotion to return to after the duration (default: neutral)
+    #[serde(default)]
+    #[schemars(with = "Option<Emotion>")]
+    blend_to: Option<String>,
+}
+
+/// Request to capture a screenshot
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct CaptureScreenshotRequest {
+    /// Optional description of what to look for in the screenshot
+    #[serde(default)]
+    description: Option<String>,
+    /// Longest edge in pixels, at least 256 and at most the user's setting (the default)
+    #[serde(default)]
+    max_dimension: Option<u32>,
+    /// 1-99 for lossy JPEG, 100 for lossless WebP (defaults to the user's setting)
+    #[serde(default)]
+    quality: Option<u8>,
+    /// Convert to grayscale to save tokens
+    #[serde(default)]
+    grayscale: Option<bool>,
+    /// Target size of each encoded image in bytes
+    #[serde(default)]
+    max_bytes: Option<u32>,
+    /// Split the full-resolution capture into tiles of max_dimension instead of downscaling
+    /// (at most 16 tiles; tiles grow to stay within that and are then downscaled)
+    #[serde(default)]
+    tile: bool,
+}
+
+/// Request to set a reminder
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct SetReminderRequest {
+    /// What to remind the user about
+    message: String,
+    /// Minutes from now until the reminder is due
+    #[serde(default)]
+    minutes: Option<f64>,
+    /// Seconds from now until the reminder is due (added to minutes)
+    #[serde(default)]
+    seconds: Option<u64>,
+}
+
+/// Request to cancel a reminder
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct CancelReminderRequest {
+    /// The id of the reminder, as returned by set_reminder or list_reminders
+    id: String,
+}
+
+/// The mascot MCP server
+pub struct MascotService {
+    tool_router: ToolRouter<MascotService>,
+    subscriptions: resources::Subscriptions,
+    /// Set when hosted inside the GUI process (HTTP transport)
+    app: Option<tauri::AppHandle>,
+    /// Run by `--mcp-selftest`: screenshots skip the consent prompt and the history
+    self_test: bool,
+}
+
+#[tool_router]
+impl MascotService {
+    fn new() -> Self {
+        Self::with_app(None)
+    }
+
+    /// Create a service that drives the mascot through the given app handle
+    pub fn with_app(app: Option<tauri::AppHandle>) -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+            subscriptions: resources::Subscriptions::default(),
+            app,
+            self_test: false,
+        }
+    }
+
+    /// Create the service the self-test talks to
+    /// Its screenshots never leave the process, so nobody is asked and nothing is kept.
+    pub fn for_self_test() -> Self {
+        Self {
+            self_test: true,
+            ..Self::new()
+        }
+    }
+
+    /// Set the mascot's emotional expression.
+    /// Use this to make Supiki express different emotions like happy, sad, excited, etc.
+    #[tool(
+        description = "Set the mascot's emotional expression. Available emotions: neutral, happy, sad, excited, thinking, confused, surprised, curious. Optionally set an intensity (0.0-1.0) and an emotion to blend back to afterwards."
+    )]
+    async fn set_emotion(
+        &self,
+        Parameters(req): Parameters<SetEmotionRequest>,
+    ) -> Result<String, String> {
+        let event = EmotionEvent::new(
+            &req.emotion,
+            req.duration_ms,
+            req.intensity,
+            req.blend_to.as_deref(),
+        )?;
+
+        // Over stdio, Tauri parses tool_use from the CLI stream and emits the event
+        // to the frontend; over HTTP we are the GUI process and emit it ourselves
+        if let Some(app) = &self.app {
+            crate::state::update_mascot_emotion(event.emotion.as_str());
+            if let Err(e) = app.emit("agent-emotion", &event) {
+                log_line!("[MCP] Failed to emit agent-emotion: {}", e);
+            }
+        }
+
+        let blend = event.blend_to.unwrap_or_default();
+        Ok(format!(
+            "Emotion set to '{}' at intensity {} for {}ms, then blending back to '{}'. The mascot is now expressing this emotion.",
+            event.emotion, event.intensity, event.duration, blend
+        ))
+    }
+
+    /// Move the mascot to a position on screen.
+    /// Use this to make Supiki walk to different parts of the screen.
+    #[tool(
+        description = "Move the mascot on screen. Use a named target ('left', 'right', 'center', 'top', 'bottom', corners like 'top-left', 'focused_window' to stand next to the focused window, 'focused_window_title' to climb onto its title bar), absolute x/y coordinates, or dx/dy offsets. 'monitor' picks a monitor (1-based) for screen anchors, 'speed' scales walking speed and 'waypoints' adds further points to visit in order."
+    )]
+    async fn move_to(&self, Parameters(req): Parameters<MoveToRequest>) -> Result<String, String> {
+        req.validate()?;
+
+        // Over stdio the GUI resolves the move when it sees the tool_use in the CLI stream
+        let Some(app) = &self.app else {
+            return Ok(format!(
+                "Moving mascot through {} point(s). The mascot is now walking there.",
+                req.steps().count()
+            ));
+        };
+
+        let event = movement::emit_move(app, &req)?;
+        let path = event
+            .points
+            .iter()
+            .map(|p| match p.y {
+                Some(y) => format!("({:.0}, {:.0})", p.x, y),
+                None => format!("({:.0}, floor)", p.x),
+            })
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        Ok(format!("Moving mascot along {}. The mascot is now walking there.", path))
+    }
+
+    /// Say a short utterance in a speech bubble above the mascot.
+    /// Use this for quick remarks that should not become part of the chat.
+    #[tool(
+        description = "Show a short utterance (max 120 characters) in a timed speech bubble above the mascot. It is not added to the chat history, so use it for ambient or proactive remarks. Style can be 'normal', 'whisper' or 'shout'; window picks the speaking mascot ('main' or a mini mascot like 'mini-mascot-1')."
+    )]
+    async fn say(&self, Parameters(req): Parameters<SayRequest>) -> Result<String, String> {
+        // Over stdio the GUI shows the bubble when it sees the tool_use in the CLI stream
+        let event = match &self.app {
+            Some(app) => utterance::emit_say(app, &req)?,
+            None => req.to_event()?,
+        };
+        Ok(format!(
+            "Said \"{}\" from the {} mascot for {}ms.",
+            event.message, event.window, event.duration_ms
+        ))
+    }
+
+    /// Speak text aloud with Supiki's voice.
+    /// Use this when the user asked to hear something, not for every reply.
+    #[tool(
+        description = "Speak text aloud (max 1000 characters) with the mascot's offline text-to-speech voice, in the user's selected language. A new utterance replaces the one in progress."
+    )]
+    async fn speak(&self, Parameters(req): Parameters<SpeakRequest>) -> Result<String, String> {
+        let text = req.validate()?;
+        // Over stdio the GUI synthesizes the audio when it sees the tool_use in the CLI stream
+        if let Some(app) = &self.app {
+            tts::speak(app, text);
+        }
+        Ok(format!("Speaking {} character(s).", text.chars().count()))
+    }
+
+    /// Play a choreographed animation sequence on the main mascot.
+    /// Use this to make Supiki perform, e.g. celebrate a finished task.
+    #[tool(
+        description = "Play an animation sequence on the mascot: either a named sequence from list_sequences, or a list of actions such as {\"action\": \"jump\"}, {\"action\": \"wave\"}, {\"action\": \"spin\"}, {\"action\": \"sleep\", \"duration_ms\": 3000}, {\"action\": \"look_at\", \"x\": 800, \"y\": 400}, {\"action\": \"wait\", \"duration_ms\": 500}. A new sequence replaces the one in progress."
+    )]
+    async fn play_sequence(
+        &self,
+        Parameters(req): Parameters<PlaySequenceRequest>,
+    ) -> Result<String, String> {
+        // Over stdio the GUI plays the sequence when it sees the tool_use in the CLI stream
+        let event = match &self.app {
+            Some(app) => sequences::emit_sequence(app, &req)?,
+            None => req.to_event()?,
+        };
+        Ok(format!(
+            "Playing {} step(s) over {}ms.",
+            event.steps.len(),
+            event.total_ms
+        ))
+    }
+
+    /// List the named animation sequences in the library.
+    #[tool(description = "List the named animation sequences that play_sequence accepts")]
+    async fn list_sequences(&self) -> String {
+        let library = sequences::load_library();
+        if library.is_empty() {
+            return "No named sequences. Use play_sequence with a list of actions instead.".to_string();
+        }
+        library
+            .iter()
+            .map(|(name, sequence)| {
+                if sequence.description.is_empty() {
+                    format!("- {} ({} steps)", name, sequence.actions.len())
+                } else {
+                    format!("- {}: {} ({} steps)", name, sequence.description, sequence.actions.len())
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Stop the animation sequence in progress.
+    #[tool(description = "Stop the mascot's animation sequence in progress")]
+    async fn cancel_sequence(&self) -> Result<String, String> {
+        if let Some(app) = &self.app {
+            sequences::emit_cancel(app)?;
+        }
+        Ok("Sequence cancelled.".to_string())
+    }
+
+    /// Capture a screenshot of all monitors.
+    /// Use this when you need to see what the user is looking at across all displays.
+    #[tool(description = "Capture a screenshot of all monitors to see what the user is looking at")]
+    async fn capture_screenshot(
+        &self,
+        Parameters(req): Parameters<CaptureScreenshotRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let desc = req
+            .description
+            .unwrap_or_else(|| "general view".to_string());
+
+        let settings = settings::load_settings();
+        if settings.screenshots_paused {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Screenshots are currently paused by the user, so I can't look at the screen right now. Please ask the user to describe what they see, or to resume screenshots from the tray menu.",
+            )]));
+        }
+        let session_id = match self.screenshot_consent(&desc).await {
+            Ok(session_id) => session_id,
+            Err(message) => return Ok(CallToolResult::success(vec![Content::text(message)])),
+        };
+
+        // Helper to create error
+        let make_error = |msg: String| {
+            rmcp::ErrorData::new(
+                rmcp::model::ErrorCode::INTERNAL_ERROR,
+                msg,
+                None::<serde_json::Value>,
+            )
+        };
+
+        // Get all monitors
+        let monitors =
+            Monitor::all().map_err(|e| make_error(format!("Failed to get monitors: {}", e)))?;
+
+        if monitors.is_empty() {
+            return Err(make_error("No monitors found".to_string()));
+        }
+
+        // Capture all monitors and collect their images with positions
+        let mut captures: Vec<(i32, i32, image::RgbaImage)> = Vec::new();
+        for monitor in &monitors {
+            let x = monitor
+                .x()
+                .map_err(|e| make_error(format!("Failed to get monitor x position: {}", e)))?;
+            let y = monitor
+                .y()
+                .map_err(|e| make_error(format!("Failed to get monitor y position: {}", e)))?;
+            let img = monitor
+                .capture_image()
+                .map_err(|e| make_error(format!("Failed to capture monitor: {}", e)))?;
+            captures.push((x, y, img));
+        }
+
+        // Calculate the bounding box for all monitors
+        let mut min_x = i32::MAX;
+        let mut min_y = i32::MAX;
+        let mut max_x = i32::MIN;
+        let mut max_y = i32::MIN;
+
+        for (x, y, img) in &captures {
+            min_x = min_x.min(*x);
+            min_y = min_y.min(*y);
+            max_x = max_x.max(*x + img.width() as i32);
+            max_y = max_y.max(*y + img.height() as i32);
+        }
+
+        // Create a canvas that fits all monitors
+        let canvas_width = (max_x - min_x) as u32;
+        let canvas_height = (max_y - min_y) as u32;
+        let mut canvas = image::RgbaImage::new(canvas_width, canvas_height);
+
+        // Paste each monitor's capture onto the canvas at the correct position
+        for (x, y, img) in captures {
+            let paste_x = (x - min_x) as u32;
+            let paste_y = (y - min_y) as u32;
+            image::imageops::overlay(&mut canvas, &img, paste_x as i64, paste_y as i64);
+        }
+
+        // Hide denied windows (and Supiki's own windows) before anything leaves the machine
+        let rects = privacy::redacted_rects(&settings).map_err(make_error)?;
+        privacy::redact(&mut canvas, &rects, (min_x, min_y), settings.redaction_style);
+        if !self.self_test {
+            if let Err(e) = screenshots::record_capture(&canvas, "capture_screenshot", Some(&desc), session_id) {
+                log_line!("[MCP] Failed to record screenshot: {}", e);
+            }
+        }
+
+        let options = ImageOptions {
+            max_dimension: image_pipeline::clamp_dimension(
+                req.max_dimension.unwrap_or(settings.screenshot_max_dimension),
+                settings.screenshot_max_dimension,
+            ),
+            quality: req.quality.unwrap_or(settings.screenshot_quality).clamp(1, 100),
+            grayscale: req.grayscale.unwrap_or(settings.screenshot_grayscale),
+            max_bytes: req.max_bytes.or(settings.screenshot_max_bytes).map(|b| b as usize),
+            ..ImageOptions::default()
+        };
+
+        let monitor_count = monitors.len();
+        let (canvas_width, canvas_height) = (canvas.width(), canvas.height());
+        let images: Vec<(Option<(u32, u32)>, image::RgbaImage)> = if req.tile {
+            image_pipeline::split_tiles(&canvas, options.max_dimension, image_pipeline::MAX_TILES)
+                .into_iter()
+                .map(|tile| (Some((tile.x, tile.y)), tile.image))
+                .collect()
+        } else {
+            vec![(None, canvas)]
+        };
+
+        let mut content = vec![Content::text(format!(
+            "Screenshot captured from {} monitor(s) (looking for: {}). Here is what I can see on your screen:",
+            monitor_count, desc
+        ))];
+        let mut total_tokens = 0;
+        for (offset, img) in images {
+            let encoded = image_pipeline::process(image::DynamicImage::ImageRgba8(img), &options)
+                .map_err(make_error)?;
+            total_tokens += encoded.estimated_tokens();
+            if let Some((x, y)) = offset {
+                content.push(Content::text(format!(
+                    "Tile at ({}, {}): {}x{}",
+                    x, y, encoded.width, encoded.height
+                )));
+            }
+            let dimensions = (encoded.width, encoded.height);
+            content.push(Content::image(encoded.to_base64(), encoded.media_type));
+            if offset.is_none() {
+                content.push(Content::text(format!(
+                    "Captured {}x{}, sent as {}x{}.",
+                    canvas_width, canvas_height, dimensions.0, dimensions.1
+                )));
+            }
+        }
+        content.push(Content::text(format!(
+            "Estimated image cost: ~{} tokens.",
+            total_tokens
+        )));
+
+        Ok(CallToolResult::success(content))
+    }
+
+    /// Wait until part of the screen changes.
+    /// Use this instead of repeated screenshots when waiting for long-running work.
+    #[tool(
+        description = "Wait until the screen changes, e.g. to notice when a build, test run or download finishes. Samples a monitor (or a region of it, in physical pixels relative to the monitor) every interval_ms and returns a screenshot as soon as the changed fraction exceeds threshold, or a note when timeout_seconds expires."
+    )]
+    async fn wait_for_screen_change(
+        &self,
+        Parameters(req): Parameters<watch::WaitForScreenChangeRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        // wait_for_change answers for paused screenshots itself, without asking
+        let description = req.description.as_deref().unwrap_or("screen changes");
+        let session_id = if settings::load_settings().screenshots_paused {
+            None
+        } else {
+            match self.screenshot_consent(description).await {
+                Ok(session_id) => session_id,
+                Err(message) => return Ok(CallToolResult::success(vec![Content::text(message)])),
+            }
+        };
+        // Errors go back to the model as a failed tool call rather than a protocol error
+        Ok(match watch::wait_for_change(&req, session_id, !self.self_test).await {
+            Ok(content) => CallToolResult::success(content),
+            Err(e) => CallToolResult::error(vec![Content::text(e)]),
+        })
+    }
+
+    /// Set a reminder that fires after a delay.
+    /// Use this when the user asks to be reminded of something later.
+    #[tool(
+        description = "Set a reminder. When it is due, the mascot walks on screen, shows the message and plays a sound. Reminders survive an app restart."
+    )]
+    async fn set_reminder(
+        &self,
+        Parameters(req): Parameters<SetReminderRequest>,
+    ) -> Result<String, String> {
+        let delay = reminders::reminder_delay(req.minutes, req.seconds)?;
+
+        let reminder = reminders::add_reminder(&req.message, delay)?;
+        Ok(format!(
+            "Reminder '{}' set (id: {}), due in {} second(s).",
+            reminder.message,
+            reminder.id,
+            delay.as_secs()
+        ))
+    }
+
+    /// List pending reminders.
+    #[tool(description = "List pending reminders with their ids and due times")]
+    async fn list_reminders(&self) -> String {
+        let pending = reminders::list_reminders();
+        if pending.is_empty() {
+            return "No pending reminders.".to_string();
+        }
+
+        let now = reminders::now_ms();
+        let lines: Vec<String> = pending
+            .iter()
+            .map(|r| {
+                let remaining = r.due_at.saturating_sub(now) / 1000;
+                format!("- {}: '{}' (due in {}s)", r.id, r.message, remaining)
+            })
+            .collect();
+        format!("Pending reminders:\n{}", lines.join("\n"))
+    }
+
+    /// Cancel a pending reminder.
+    #[tool(description = "Cancel a pending reminder by id")]
+    async fn cancel_reminder(
+        &self,
+        Parameters(req): Parameters<CancelReminderRequest>,
+    ) -> Result<String, String> {
+        let removed = reminders::cancel_reminder(&req.id)?;
+        Ok(format!("Reminder '{}' ({}) cancelled.", removed.message, removed.id))
+    }
+
+    /// Spawn a helper mascot for a subtask.
+    /// Use this to show parallel work, e.g. one helper per thing being investigated.
+    #[tool(
+        description = "Spawn a small helper mascot next to Supiki to represent a subtask. At most 5 helpers can be active. Returns the helper id, which works as the 'window' of the say tool and for dismiss_helper."
+    )]
+    async fn spawn_helper(
+        &self,
+        Parameters(req): Parameters<SpawnHelperRequest>,
+    ) -> Result<String, String> {
+        let params = serde_json::json!({ "name": req.name, "emotion": req.emotion });
+        let (helper, active) = helper_reply(self.call_gui("spawn_helper", params).await?)?;
+        let helper = helper.ok_or("Missing helper in reply")?;
+        Ok(format!(
+            "Spawned helper '{}' (id: {}).\n{}",
+            helper.name,
+            helper.id,
+            helpers::describe(&active)
+        ))
+    }
+
+    /// Dismiss a helper mascot once its subtask is done.
+    #[tool(description = "Dismiss a helper mascot by id")]
+    async fn dismiss_helper(
+        &self,
+        Parameters(req): Parameters<DismissHelperRequest>,
+    ) -> Result<String, String> {
+        let params = serde_json::json!({ "id": req.id });
+        let (helper, active) = helper_reply(self.call_gui("dismiss_helper", params).await?)?;
+        let helper = helper.ok_or("Missing helper in reply")?;
+        Ok(format!(
+            "Dismissed helper '{}' ({}).\n{}",
+            helper.name,
+            helper.id,
+            helpers::describe(&active)
+        ))
+    }
+
+    /// List the helper mascots that are currently on screen.
+    #[tool(description = "List the active helper mascots with their ids")]
+    async fn list_helpers(&self) -> Result<String, String> {
+        let (_, active) = helper_reply(self.call_gui("list_helpers", serde_json::Value::Null).await?)?;
+        Ok(helpers::describe(&active))
+    }
+
+    /// Open a URL in the user's default browser.
+    #[tool(
+        description = "Open a URL in the user's default browser, e.g. a documentation page. Links outside the user's allow list need their confirmation; a refusal is returned as an error."
+    )]
+    async fn open_url(&self, Parameters(req): Parameters<OpenUrlRequest>) -> Result<String, String> {
+        let reply = self.call_gui("open_url", serde_json::json!({ "url": req.url })).await?;
+        Ok(reply.as_str().unwrap_or("Opened.").to_string())
+    }
+
+    /// Open a local file or folder with its default application.
+    #[tool(
+        description = "Open a local file or folder (absolute path) with its default application, e.g. to reveal a generated file. Paths outside the user's allowed folders need their confirmation; a refusal is returned as an error."
+    )]
+    async fn open_path(&self, Parameters(req): Parameters<OpenPathRequest>) -> Result<String, String> {
+        let reply = self.call_gui("open_path", serde_json::json!({ "path": req.path })).await?;
+        Ok(reply.as_str().unwrap_or("Opened.").to_string())
+    }
+
+    /// Ask the user whether Claude may use a tool.
+    /// Claude CLI calls this itself through --permission-prompt-tool; it is not meant for the model.
+    #[tool(
+        description = "Permission prompt for Claude CLI (--permission-prompt-tool). Asks the user whether a tool call may run and returns {\"behavior\": \"allow\", \"updatedInput\": ...} or {\"behavior\": \"deny\", \"message\": ...}."
+    )]
+    async fn approve_permission(
+        &self,
+        Parameters(mut req): Parameters<PermissionPromptRequest>,
+    ) -> Result<String, String> {
+        req.validate()?;
+        if req.cwd.is_none() {
+            req.cwd = std::env::current_dir()
+                .ok()
+                .map(|d| d.to_string_lossy().to_string());
+        }
+        let params = serde_json::to_value(&req).map_err(|e| e.to_string())?;
+        // Without the GUI nobody can answer, so the call is denied
+        let decision = match self.call_gui("permission_prompt", params).await {
+            Ok(reply) => match reply.as_str() {
+                Some(decision) => decision.to_string(),
+                None => PermissionDecision::deny("Invalid reply from the permission prompt").to_json(),
+            },
+            Err(e) => PermissionDecision::deny(format!("Could not ask the user: {}", e)).to_json(),
+        };
+        Ok(decision)
+    }
+}
+
+impl MascotService {
+    /// Ask the running app whether the screen may be captured
+    /// Returns the requesting session, or the message for the model when refused
+    async fn screenshot_consent(&self, description: &str) -> Result<Option<String>, String> {
+        if self.self_test {
+            return Ok(None);
+        }
+        let params = serde_json::json!({ "description": description });
+        match self.call_gui("screenshot_consent", params).await {
+            Ok(value) => {
+                let reply: ConsentReply =
+                    serde_json::from_value(value).map_err(|e| format!("Invalid consent reply: {}", e))?;
+                if reply.allowed {
+                    Ok(reply.session_id)
+                } else {
+                    Err("The user declined the screenshot. Please ask them to describe what they see instead.".to_string())
+                }
+            }
+            // Without the app nobody can ask, so only "always" lets the capture through
+            Err(_) if settings::load_settings().screenshot_consent == ScreenshotConsent::Always => Ok(None),
+            Err(e) => Err(format!(
+                "I need the user's permission for screenshots, but could not ask ({}). Please ask them to describe what they see instead.",
+                e
+            )),
+        }
+    }
+
+    /// Call into the GUI: directly when hosted there, otherwise over the bridge
+    async fn call_gui(&self, method: &'static str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+        match &self.app {
+            Some(app) => {
+                let app = app.clone();
+                tokio::task::spawn_blocking(move || bridge::dispatch(&app, method, params))
+                    .await
+                    .map_err(|e| format!("GUI call failed: {}", e))?
+            }
+            None => bridge::call_async(method, params).await,
+        }
+    }
+}
+
+/// Split a helper bridge reply into the affected helper and the live helpers
+fn helper_reply(value: serde_json::Value) -> Result<(Option<Helper>, Vec<Helper>), String> {
+    #[derive(serde::Deserialize)]
+    struct Reply {
+        #[serde(default)]
+        helper: Option<Helper>,
+        #[serde(default)]
+        helpers: Vec<Helper>,
+    }
+    let reply: Reply = serde_json::from_value(value).map_err(|e| format!("Invalid helper reply: {}", e))?;
+    Ok((reply.helper, reply.helpers))
+}
+
+#[tool_handler]
+impl ServerHandler for MascotService {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, rmcp::ErrorData> {
+        Ok(ListResourcesResult::with_all_items(resources::list()))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, rmcp::ErrorData> {
+        resources::read(&request.uri)
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        if !resources::exists(&request.uri) {
+            return Err(rmcp::ErrorData::resource_not_found(
+                format!("Unknown resource: {}", request.uri),
+                None,
+            ));
+        }
+        self.subscriptions.subscribe(request.uri, context.peer);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        self.subscriptions.unsubscribe(&request.uri);
+        Ok(())
+    }
+}
+
+/// Run the MCP server via stdio
+/// This is called when the executable is launched with --mcp flag
+pub async fn run() -> Result<()> {
+    // Serve via stdio (Claude CLI spawns this process)
+    let transport = (tokio::io::stdin(), tokio::io::stdout());
+    let service = MascotService::new().serve(transport).await?;
+    service.waiting().await?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! Screenshot privacy filtering
+//!
+//! Windows matching the user's deny list (and optionally Supiki's own windows)
+//! are blacked out or blurred on the stitched screenshot before it is encoded.
+//! If those windows can't be found or measured, the capture is refused.
+
+use image::RgbaImage;
+use xcap::Window;
+
+use crate::settings::{AppSettings, RedactionStyle};
+
+/// Blur strength used for the blur redaction style
+const BLUR_SIGMA: f32 = 24.0;
+
+/// Executable/app names that belong to Supiki itself
+const SUPIKI_APP_NAMES: &[&str] = &["supiki", "clawd"];
+
+/// A window rectangle in global screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Whether a window's app name or title matches a deny list entry (case-insensitive)
+pub fn matches_deny_list(app_name: &str, title: &str, deny_list: &[String]) -> bool {
+    let app_name = app_name.to_lowercase();
+    let title = title.to_lowercase();
+    deny_list
+        .iter()
+        .map(|entry| entry.trim().to_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| app_name.contains(&entry) || title.contains(&entry))
+}
+
+/// Whether a window belongs to Supiki
+fn is_supiki_window(app_name: &str, pid: u32) -> bool {
+    let app_name = app_name.to_lowercase();
+    pid == std::process::id() || SUPIKI_APP_NAMES.iter().any(|name| app_name.contains(name))
+}
+
+fn window_rect(window: &Window) -> Option<Rect> {
+    Some(Rect {
+        x: window.x().ok()?,
+        y: window.y().ok()?,
+        width: window.width().ok()?,
+        height: window.height().ok()?,
+    })
+}
+
+/// Rectangles of visible windows that must be hidden from screenshots
+/// Fails when there are rules but the windows can't be listed or a matching
+/// window can't be measured, so the capture is refused rather than sent
+/// unredacted.
+pub fn redacted_rects(settings: &AppSettings) -> Result<Vec<Rect>, String> {
+    if settings.screenshot_deny_list.iter().all(|e| e.trim().is_empty()) && !settings.redact_supiki_windows {
+        return Ok(Vec::new());
+    }
+
+    let windows = Window::all().map_err(|e| {
+        log_line!("[MCP] Failed to list windows for redaction: {}", e);
+        format!("Screenshot refused: windows to redact could not be listed ({})", e)
+    })?;
+
+    windows
+        .iter()
+        .filter(|w| !w.is_minimized().unwrap_or(false))
+        .filter(|w| {
+            let app_name = w.app_name().unwrap_or_default();
+            let title = w.title().unwrap_or_default();
+            let pid = w.pid().unwrap_or(0);
+            matches_deny_list(&app_name, &title, &settings.screenshot_deny_list)
+                || (settings.redact_supiki_windows && is_supiki_window(&app_name, pid))
+        })
+        .map(|w| {
+            window_rect(w).ok_or_else(|| {
+                let title = w.title().unwrap_or_default();
+                log_line!("[MCP] Failed to measure a window to redact");
+                log_content!("[MCP] Unmeasured window: {}", title);
+                "Screenshot refused: a window to redact could not be measured".to_string()
+            })
+        })
+        .collect()
+}
+
+/// Hide the given rectangles on a canvas whose top-left corner is at `origin`
+pub fn redact(canvas: &mut RgbaImage, rects: &[Rect], origin: (i32, i32), style: RedactionStyle) {
+    for rect in rects {
+        // Clip the window rectangle to the canvas
+        let left = (rect.x - origin.0).max(0) as u32;
+        let top = (rect.y - origin.1).max(0) as u32;
+        let right = ((rect.x - origin.0) as i64 + rect.width as i64).clamp(0, canvas.width() as i64) as u32;
+        let bottom = ((rect.y - origin.1) as i64 + rect.height as i64).clamp(0, canvas.height() as i64) as u32;
+        if left >= right || top >= bottom {
+            continue;
+        }
+
+        match style {
+            RedactionStyle::Blackout => {
+                for y in top..bottom {
+                    for x in left..right {
+                        canvas.put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+                    }
+                }
+            }
+            RedactionStyle::Blur => {
+                let region = image::imageops::crop_imm(canvas, left, top, right - left, bottom - top).to_image();
+                let blurred = image::imageops::blur(&region, BLUR_SIGMA);
+                image::imageops::replace(canvas, &blurred, left as i64, top as i64);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_deny_list() {
+        let deny = vec!["1Password".to_string(), "private chat".to_string(), " ".to_string()];
+        assert!(matches_deny_list("1password", "Vault", &deny));
+        assert!(matches_deny_list("Slack", "Private Chat - Slack", &deny));
+        assert!(!matches_deny_list("Code", "main.rs", &deny));
+    }
+
+    #[test]
+    fn test_redact_blackout_clips_to_canvas() {
+        let mut canvas = RgbaImage::from_pixel(10, 10, image::Rgba([255, 255, 255, 255]));
+        let rects = [Rect { x: 95, y: 95, width: 20, height: 20 }];
+        redact(&mut canvas, &rects, (90, 90), RedactionStyle::Blackout);
+        assert_eq!(canvas.get_pixel(4, 4), &image::Rgba([255, 255, 255, 255]));
+        assert_eq!(canvas.get_pixel(5, 5), &image::Rgba([0, 0, 0, 255]));
+        assert_eq!(canvas.get_pixel(9, 9), &image::Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_redact_ignores_offscreen_rects() {
+        let mut canvas = RgbaImage::from_pixel(10, 10, image::Rgba([255, 255, 255, 255]));
+        let rects = [Rect { x: -50, y: 0, width: 20, height: 20 }];
+        redact(&mut canvas, &rects, (0, 0), RedactionStyle::Blur);
+        assert!(canvas.pixels().all(|p| p == &image::Rgba([255, 255, 255, 255])));
+    }
+}
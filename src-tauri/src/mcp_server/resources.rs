@@ -0,0 +1,229 @@
+//! MCP resources exposing Supiki's context
+//!
+//! Resources are read from the files the GUI process persists in the app data
+//! directory, so any MCP client can read the mascot's state without extra tool
+//! calls. Subscribed clients are notified when the backing files change.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use rmcp::{
+    model::{
+        AnnotateAble, RawResource, ReadResourceResult, Resource, ResourceContents,
+        ResourceUpdatedNotificationParam,
+    },
+    Peer, RoleServer,
+};
+
+use crate::reminders;
+use crate::state;
+
+/// Current mascot emotion and window position
+pub const MASCOT_STATE_URI: &str = "supiki://mascot/state";
+
+/// Current working directory and recent working directories
+pub const WORKSPACE_URI: &str = "supiki://workspace";
+
+/// Saved chat sessions from the chat history
+pub const SESSIONS_URI: &str = "supiki://sessions";
+
+/// Pending reminders
+pub const REMINDERS_URI: &str = "supiki://reminders";
+
+/// How often subscribed resources are checked for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// All resources offered by the server
+pub fn list() -> Vec<Resource> {
+    [
+        (MASCOT_STATE_URI, "mascot-state", "Current mascot emotion and screen position"),
+        (WORKSPACE_URI, "workspace", "Current working directory and recent working directories"),
+        (SESSIONS_URI, "chat-sessions", "Saved chat sessions from Supiki's chat history"),
+        (REMINDERS_URI, "reminders", "Pending reminders set through set_reminder"),
+    ]
+    .into_iter()
+    .map(|(uri, name, description)| {
+        let mut resource = RawResource::new(uri, name);
+        resource.description = Some(description.to_string());
+        resource.mime_type = Some("application/json".to_string());
+        resource.no_annotation()
+    })
+    .collect()
+}
+
+/// Read a resource as JSON text
+pub fn read(uri: &str) -> Result<ReadResourceResult, rmcp::ErrorData> {
+    let value = match uri {
+        MASCOT_STATE_URI => match state::load_mascot_snapshot() {
+            Some(snapshot) => serde_json::to_value(snapshot).unwrap_or_default(),
+            None => serde_json::json!({ "emotion": "neutral", "x": null, "y": null }),
+        },
+        WORKSPACE_URI => serde_json::json!({
+            "cwd": state::load_cwd_from_disk(),
+            "recentCwds": state::load_recent_cwds_from_disk(),
+        }),
+        SESSIONS_URI => serde_json::to_value(state::load_chat_sessions_from_disk()).unwrap_or_default(),
+        REMINDERS_URI => serde_json::to_value(reminders::list_reminders()).unwrap_or_default(),
+        _ => {
+            return Err(rmcp::ErrorData::resource_not_found(
+                format!("Unknown resource: {}", uri),
+                None,
+            ))
+        }
+    };
+
+    let text = serde_json::to_string_pretty(&value).unwrap_or_default();
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::text(text, uri)],
+    })
+}
+
+/// Whether the server offers a resource with this uri
+pub fn exists(uri: &str) -> bool {
+    matches!(uri, MASCOT_STATE_URI | WORKSPACE_URI | SESSIONS_URI | REMINDERS_URI)
+}
+
+/// Files whose changes affect a resource
+fn source_paths(uri: &str) -> Vec<PathBuf> {
+    let paths = match uri {
+        MASCOT_STATE_URI => vec![state::get_mascot_state_file_path()],
+        WORKSPACE_URI => vec![
+            state::get_cwd_file_path(),
+            state::get_recent_cwds_file_path(),
+        ],
+        SESSIONS_URI => vec![state::get_chat_sessions_file_path()],
+        REMINDERS_URI => vec![reminders::get_reminders_file_path()],
+        _ => Vec::new(),
+    };
+    paths.into_iter().flatten().collect()
+}
+
+/// Modification times of a resource's backing files, used to detect changes
+fn fingerprint(uri: &str) -> Vec<Option<SystemTime>> {
+    source_paths(uri)
+        .iter()
+        .map(|path| path.metadata().and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// Resource subscriptions for one client connection
+#[derive(Clone, Default)]
+pub struct Subscriptions {
+    uris: Arc<Mutex<HashSet<String>>>,
+    /// Last fingerprint of each subscribed resource
+    seen: Arc<Mutex<HashMap<String, Vec<Option<SystemTime>>>>>,
+    watching: Arc<AtomicBool>,
+}
+
+impl Subscriptions {
+    /// Subscribe to a resource, starting the change watcher on first use
+    pub fn subscribe(&self, uri: String, peer: Peer<RoleServer>) {
+        self.uris.lock().unwrap().insert(uri);
+        if !self.watching.swap(true, Ordering::SeqCst) {
+            tokio::spawn(watch(self.clone(), peer));
+        }
+    }
+
+    /// Stop receiving updates for a resource
+    pub fn unsubscribe(&self, uri: &str) {
+        self.uris.lock().unwrap().remove(uri);
+        self.seen.lock().unwrap().remove(uri);
+    }
+
+    /// Subscribed resources that changed since the last check, or None once
+    /// nothing is subscribed (the watcher then stops)
+    fn changed(&self) -> Option<Vec<String>> {
+        let uris = self.uris.lock().unwrap();
+        if uris.is_empty() {
+            // Cleared under the lock, so a new subscription starts a new watcher
+            self.watching.store(false, Ordering::SeqCst);
+            return None;
+        }
+        let mut seen = self.seen.lock().unwrap();
+        let changed = uris
+            .iter()
+            .filter(|uri| {
+                let current = fingerprint(uri);
+                seen.insert(uri.to_string(), current.clone())
+                    .is_some_and(|previous| previous != current)
+            })
+            .cloned()
+            .collect();
+        Some(changed)
+    }
+}
+
+/// Poll subscribed resources and notify the client when one changes
+/// Stops when nothing is subscribed anymore or the session is closed.
+async fn watch(subscriptions: Subscriptions, peer: Peer<RoleServer>) {
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        if peer.is_transport_closed() {
+            log_line!("[MCP] Stopping resource watcher: session closed");
+            subscriptions.watching.store(false, Ordering::SeqCst);
+            return;
+        }
+        let Some(changed) = subscriptions.changed() else {
+            return;
+        };
+        for uri in changed {
+            if let Err(e) = peer
+                .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                .await
+            {
+                log_line!("[MCP] Stopping resource watcher: {}", e);
+                subscriptions.watching.store(false, Ordering::SeqCst);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_resources() {
+        let uris: Vec<String> = list().into_iter().map(|r| r.raw.uri).collect();
+        assert_eq!(uris, [MASCOT_STATE_URI, WORKSPACE_URI, SESSIONS_URI, REMINDERS_URI]);
+        assert!(uris.iter().all(|uri| exists(uri)));
+    }
+
+    #[test]
+    fn test_read_unknown_resource() {
+        assert!(read("supiki://nope").is_err());
+        assert!(!exists("supiki://nope"));
+    }
+
+    #[test]
+    fn test_subscriptions_track_changes() {
+        let subscriptions = Subscriptions::default();
+        subscriptions.watching.store(true, Ordering::SeqCst);
+        subscriptions.uris.lock().unwrap().insert(WORKSPACE_URI.to_string());
+        // The first check only records the fingerprint
+        assert_eq!(subscriptions.changed(), Some(Vec::new()));
+        assert!(subscriptions.seen.lock().unwrap().contains_key(WORKSPACE_URI));
+
+        subscriptions.unsubscribe(WORKSPACE_URI);
+        assert!(subscriptions.seen.lock().unwrap().is_empty());
+        assert_eq!(subscriptions.changed(), None);
+        assert!(!subscriptions.watching.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_read_workspace_is_json() {
+        let result = read(WORKSPACE_URI).unwrap();
+        match &result.contents[0] {
+            ResourceContents::TextResourceContents { text, .. } => {
+                let value: serde_json::Value = serde_json::from_str(text).unwrap();
+                assert!(value.get("recentCwds").is_some());
+            }
+            _ => panic!("Expected text contents"),
+        }
+    }
+}
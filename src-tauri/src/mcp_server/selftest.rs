@@ -0,0 +1,281 @@
+//! Self-test of the MCP server (`--mcp-selftest`)
+//!
+//! Connects an in-process MCP client to `MascotService`, lists the tools and
+//! calls each of them with sample input, including a real screenshot of this
+//! display. Sample inputs are chosen to leave nothing behind: tools that would
+//! create reminders, spawn helpers or open files get input they must reject,
+//! and screenshots skip the consent prompt and the capture history.
+//! Tools that need the running app only have to fail cleanly without it.
+
+use std::time::Instant;
+
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use rmcp::ServiceExt;
+use serde_json::json;
+
+use super::MascotService;
+use crate::bridge;
+
+/// Longest tool output shown in the report, in characters
+const MAX_DETAIL_CHARS: usize = 80;
+
+/// What a sample call has to return to pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    /// A successful result
+    Success,
+    /// A tool error; the sample input is invalid on purpose
+    ToolError,
+    /// A successful result with at least one image
+    Image,
+    /// Success when the app is running, otherwise a tool error
+    Gui,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Skip,
+}
+
+/// Outcome of one check in the report
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+}
+
+/// A tool call with sample input
+struct Case {
+    tool: &'static str,
+    args: serde_json::Value,
+    expect: Expect,
+}
+
+fn cases() -> Vec<Case> {
+    let case = |tool, args, expect| Case { tool, args, expect };
+    vec![
+        case("set_emotion", json!({ "emotion": "happy", "duration_ms": 1000 }), Expect::Success),
+        case("set_emotion", json!({ "emotion": "grumpy" }), Expect::ToolError),
+        case("move_to", json!({ "target": "center" }), Expect::Success),
+        case("say", json!({ "message": "Self-test" }), Expect::Success),
+        case("speak", json!({ "text": "Self-test" }), Expect::Success),
+        case("play_sequence", json!({ "actions": [{ "action": "wave" }] }), Expect::Success),
+        case("list_sequences", json!({}), Expect::Success),
+        case("cancel_sequence", json!({}), Expect::Success),
+        case("capture_screenshot", json!({ "description": "self-test", "max_dimension": 256 }), Expect::Image),
+        case("wait_for_screen_change", json!({ "interval_ms": 250, "timeout_seconds": 1 }), Expect::Success),
+        case("set_reminder", json!({ "message": "Self-test", "minutes": -1 }), Expect::ToolError),
+        case("list_reminders", json!({}), Expect::Success),
+        case("cancel_reminder", json!({ "id": "selftest-missing" }), Expect::ToolError),
+        case("spawn_helper", json!({ "name": "" }), Expect::ToolError),
+        case("dismiss_helper", json!({ "id": "selftest-missing" }), Expect::ToolError),
+        case("list_helpers", json!({}), Expect::Gui),
+        case("open_url", json!({ "url": "javascript:alert(1)" }), Expect::ToolError),
+        case("open_path", json!({ "path": "relative/path" }), Expect::ToolError),
+        case("approve_permission", json!({ "tool_name": "" }), Expect::ToolError),
+    ]
+}
+
+/// First line of the result's text, shortened for the report
+fn summary(result: &CallToolResult) -> String {
+    let text = result
+        .content
+        .iter()
+        .find_map(|c| c.as_text().map(|t| t.text.clone()))
+        .unwrap_or_default();
+    let line = text.lines().next().unwrap_or("");
+    if line.chars().count() > MAX_DETAIL_CHARS {
+        format!("{}...", line.chars().take(MAX_DETAIL_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Judge a tool result against the expectation
+fn evaluate(expect: Expect, gui_running: bool, result: &CallToolResult) -> (Status, String) {
+    let is_error = result.is_error == Some(true);
+    let detail = summary(result);
+    match expect {
+        Expect::Success | Expect::Gui if !is_error => (Status::Pass, detail),
+        Expect::Gui if !gui_running => (Status::Skip, "app is not running".to_string()),
+        Expect::Success | Expect::Gui => (Status::Fail, detail),
+        Expect::ToolError if is_error => (Status::Pass, format!("rejected: {}", detail)),
+        Expect::ToolError => (Status::Fail, format!("sample input was not rejected: {}", detail)),
+        Expect::Image if is_error => (Status::Fail, detail),
+        Expect::Image if result.content.iter().any(|c| c.as_image().is_some()) => {
+            (Status::Pass, "captured an image".to_string())
+        }
+        // e.g. screenshots are paused
+        Expect::Image => (Status::Skip, detail),
+    }
+}
+
+/// Render the report and the totals line
+pub fn format_report(checks: &[Check]) -> String {
+    let mut lines: Vec<String> = checks
+        .iter()
+        .map(|check| {
+            let status = match check.status {
+                Status::Pass => "PASS",
+                Status::Fail => "FAIL",
+                Status::Skip => "SKIP",
+            };
+            format!("[{}] {}: {}", status, check.name, check.detail)
+        })
+        .collect();
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    lines.push(format!(
+        "{} passed, {} failed, {} skipped",
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Skip)
+    ));
+    lines.join("\n")
+}
+
+/// Run all checks against an in-process server
+pub async fn run_checks() -> anyhow::Result<Vec<Check>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(async move {
+        let service = MascotService::for_self_test().serve(server_transport).await?;
+        service.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    let gui_running = bridge::call_async("list_helpers", serde_json::Value::Null)
+        .await
+        .is_ok();
+    let mut checks = vec![Check {
+        name: "app".to_string(),
+        status: Status::Pass,
+        detail: if gui_running {
+            "running, GUI tools are tested".to_string()
+        } else {
+            "not running, GUI tools only have to fail cleanly".to_string()
+        },
+    }];
+
+    let listed: Vec<String> = client
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|t| t.name.to_string())
+        .collect();
+    checks.push(Check {
+        name: "tools/list".to_string(),
+        status: if listed.is_empty() { Status::Fail } else { Status::Pass },
+        detail: format!("{} tools", listed.len()),
+    });
+
+    let cases = cases();
+    for name in listed.iter().filter(|n| !cases.iter().any(|c| c.tool == n.as_str())) {
+        checks.push(Check {
+            name: name.clone(),
+            status: Status::Skip,
+            detail: "no sample input".to_string(),
+        });
+    }
+
+    for case in &cases {
+        let (status, detail) = if !listed.iter().any(|n| n == case.tool) {
+            (Status::Fail, "not listed by the server".to_string())
+        } else {
+            let started = Instant::now();
+            let result = client
+                .call_tool(CallToolRequestParam {
+                    name: case.tool.into(),
+                    arguments: case.args.as_object().cloned(),
+                })
+                .await;
+            let (status, detail) = match result {
+                Ok(result) => evaluate(case.expect, gui_running, &result),
+                Err(e) => (Status::Fail, format!("call failed: {}", e)),
+            };
+            (status, format!("{} ({} ms)", detail, started.elapsed().as_millis()))
+        };
+        checks.push(Check {
+            name: case.tool.to_string(),
+            status,
+            detail,
+        });
+    }
+
+    client.cancel().await?;
+    server.abort();
+    Ok(checks)
+}
+
+/// Print the self-test report and return the number of failed checks
+pub async fn run() -> anyhow::Result<usize> {
+    println!("Supiki MCP self-test");
+    let checks = run_checks().await?;
+    println!("{}", format_report(&checks));
+    Ok(checks.iter().filter(|c| c.status == Status::Fail).count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+
+    #[test]
+    fn test_cases_cover_every_tool() {
+        let cases = cases();
+        for tool in MascotService::tool_router().list_all() {
+            assert!(
+                cases.iter().any(|c| c.tool == tool.name),
+                "no self-test case for {}",
+                tool.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_policy_lists_every_mascot_tool() {
+        let mut tools: Vec<String> = MascotService::tool_router()
+            .list_all()
+            .into_iter()
+            .map(|tool| format!("mcp__mascot__{}", tool.name))
+            .filter(|tool| tool != crate::policy::PERMISSION_PROMPT_TOOL)
+            .collect();
+        let mut listed: Vec<String> = crate::policy::MASCOT_TOOLS
+            .iter()
+            .map(|tool| format!("mcp__mascot__{}", tool))
+            .collect();
+        tools.sort();
+        listed.sort();
+        assert_eq!(tools, listed);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let ok = CallToolResult::success(vec![Content::text("done")]);
+        let err = CallToolResult::error(vec![Content::text("Supiki is not running")]);
+        let image = CallToolResult::success(vec![Content::image("AAAA", "image/jpeg")]);
+
+        assert_eq!(evaluate(Expect::Success, false, &ok).0, Status::Pass);
+        assert_eq!(evaluate(Expect::Success, false, &err).0, Status::Fail);
+        assert_eq!(evaluate(Expect::ToolError, false, &err).0, Status::Pass);
+        assert_eq!(evaluate(Expect::ToolError, false, &ok).0, Status::Fail);
+        assert_eq!(evaluate(Expect::Image, false, &image).0, Status::Pass);
+        assert_eq!(evaluate(Expect::Image, false, &ok).0, Status::Skip);
+        assert_eq!(evaluate(Expect::Gui, false, &err).0, Status::Skip);
+        assert_eq!(evaluate(Expect::Gui, true, &err).0, Status::Fail);
+        assert_eq!(evaluate(Expect::Gui, true, &ok).0, Status::Pass);
+    }
+
+    #[test]
+    fn test_format_report() {
+        let check = |name: &str, status| Check {
+            name: name.to_string(),
+            status,
+            detail: "x".to_string(),
+        };
+        let report = format_report(&[check("say", Status::Pass), check("open_url", Status::Skip)]);
+        assert_eq!(report, "[PASS] say: x\n[SKIP] open_url: x\n1 passed, 0 failed, 1 skipped");
+    }
+}
@@ -0,0 +1,296 @@
+//! Watching the screen for changes
+//!
+//! `wait_for_screen_change` samples a monitor (or a region of it) at an
+//! interval and compares a small grayscale fingerprint of each sample with
+//! the first one, so the model can wait for a build or download without
+//! polling screenshots.
+
+use std::time::{Duration, Instant};
+
+use image::imageops::{self, FilterType};
+use image::{GrayImage, RgbaImage};
+use rmcp::model::Content;
+use rmcp::schemars;
+use xcap::Monitor;
+
+use super::privacy;
+use crate::image_pipeline::{self, ImageOptions};
+use crate::screenshots;
+use crate::settings::{self, AppSettings};
+
+/// Returned when the user paused screenshots
+const PAUSED_MESSAGE: &str = "Screenshots are paused by the user, so I can't watch the screen. Ask the user to tell you when it is done, or to resume screenshots from the tray menu.";
+
+/// Longest edge of the fingerprint thumbnail
+const FINGERPRINT_SIZE: u32 = 96;
+
+/// Luminance difference (0-255) above which a fingerprint pixel counts as changed
+const PIXEL_TOLERANCE: u8 = 24;
+
+pub const DEFAULT_THRESHOLD: f64 = 0.02;
+pub const DEFAULT_INTERVAL_MS: u32 = 1000;
+pub const DEFAULT_TIMEOUT_SECONDS: u32 = 300;
+const INTERVAL_RANGE_MS: (u32, u32) = (250, 10_000);
+const MAX_TIMEOUT_SECONDS: u32 = 1800;
+
+/// A rectangle relative to the monitor's top-left corner, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, schemars::JsonSchema)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Request to wait until the screen changes
+#[derive(Debug, Clone, Default, serde::Deserialize, schemars::JsonSchema)]
+pub struct WaitForScreenChangeRequest {
+    /// What you are waiting for, e.g. "the build to finish"
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Monitor to watch (1-based, default: the primary monitor)
+    #[serde(default)]
+    pub monitor: Option<u32>,
+    /// Only watch this part of the monitor (e.g. a terminal window)
+    #[serde(default)]
+    pub region: Option<Region>,
+    /// Fraction of the watched area that must change, 0.0-1.0 (default: 0.02)
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    /// Milliseconds between samples, 250-10000 (default: 1000)
+    #[serde(default)]
+    pub interval_ms: Option<u32>,
+    /// Give up after this many seconds, at most 1800 (default: 300)
+    #[serde(default)]
+    pub timeout_seconds: Option<u32>,
+}
+
+impl WaitForScreenChangeRequest {
+    /// Validate the request and fill in defaults: (threshold, interval_ms, timeout_seconds)
+    pub fn resolved(&self) -> Result<(f64, u32, u32), String> {
+        let threshold = self.threshold.unwrap_or(DEFAULT_THRESHOLD);
+        if !(threshold > 0.0 && threshold <= 1.0) {
+            return Err(format!("threshold must be in (0, 1], got {}", threshold));
+        }
+        let interval_ms = self.interval_ms.unwrap_or(DEFAULT_INTERVAL_MS);
+        if !(INTERVAL_RANGE_MS.0..=INTERVAL_RANGE_MS.1).contains(&interval_ms) {
+            return Err(format!(
+                "interval_ms must be between {} and {}, got {}",
+                INTERVAL_RANGE_MS.0, INTERVAL_RANGE_MS.1, interval_ms
+            ));
+        }
+        let timeout_seconds = self.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+        if timeout_seconds == 0 || timeout_seconds > MAX_TIMEOUT_SECONDS {
+            return Err(format!(
+                "timeout_seconds must be between 1 and {}, got {}",
+                MAX_TIMEOUT_SECONDS, timeout_seconds
+            ));
+        }
+        Ok((threshold, interval_ms, timeout_seconds))
+    }
+}
+
+/// Small grayscale thumbnail used to compare samples
+pub fn fingerprint(img: &RgbaImage) -> GrayImage {
+    let (width, height) = img.dimensions();
+    let scale = FINGERPRINT_SIZE as f64 / width.max(height).max(1) as f64;
+    let (w, h) = if scale < 1.0 {
+        (
+            ((width as f64 * scale).round() as u32).max(1),
+            ((height as f64 * scale).round() as u32).max(1),
+        )
+    } else {
+        (width.max(1), height.max(1))
+    };
+    // Area averaging smooths out cursor blinks and anti-aliasing noise
+    let gray = imageops::grayscale(img);
+    imageops::resize(&gray, w, h, FilterType::Triangle)
+}
+
+/// Fraction of fingerprint pixels that changed noticeably (1.0 if sizes differ)
+pub fn difference(a: &GrayImage, b: &GrayImage) -> f64 {
+    if a.dimensions() != b.dimensions() || a.as_raw().is_empty() {
+        return 1.0;
+    }
+    let changed = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .filter(|(x, y)| x.abs_diff(**y) > PIXEL_TOLERANCE)
+        .count();
+    changed as f64 / a.as_raw().len() as f64
+}
+
+/// Capture the watched monitor or region, with denied windows redacted
+pub fn capture(
+    monitor: Option<u32>,
+    region: Option<Region>,
+    settings: &AppSettings,
+) -> Result<RgbaImage, String> {
+    let monitors = Monitor::all().map_err(|e| format!("Failed to get monitors: {}", e))?;
+    let target = match monitor {
+        Some(index) => monitors
+            .get((index as usize).wrapping_sub(1))
+            .ok_or_else(|| format!("Monitor {} not found ({} available)", index, monitors.len()))?,
+        None => monitors
+            .iter()
+            .find(|m| m.is_primary().unwrap_or(false))
+            .or(monitors.first())
+            .ok_or("No monitors found")?,
+    };
+
+    let x = target.x().map_err(|e| e.to_string())?;
+    let y = target.y().map_err(|e| e.to_string())?;
+    let mut img = target
+        .capture_image()
+        .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+
+    let rects = privacy::redacted_rects(settings)?;
+    privacy::redact(&mut img, &rects, (x, y), settings.redaction_style);
+
+    match region {
+        None => Ok(img),
+        Some(r) => {
+            if r.width == 0
+                || r.height == 0
+                || r.x.saturating_add(r.width) > img.width()
+                || r.y.saturating_add(r.height) > img.height()
+            {
+                return Err(format!(
+                    "Region {}x{} at ({}, {}) is outside the {}x{} monitor",
+                    r.width,
+                    r.height,
+                    r.x,
+                    r.y,
+                    img.width(),
+                    img.height()
+                ));
+            }
+            Ok(imageops::crop_imm(&img, r.x, r.y, r.width, r.height).to_image())
+        }
+    }
+}
+
+/// Sample the screen until it changes or the timeout expires
+/// The returned screenshot is kept in the capture history under `session_id` if `record` is set
+pub async fn wait_for_change(
+    req: &WaitForScreenChangeRequest,
+    session_id: Option<String>,
+    record: bool,
+) -> Result<Vec<Content>, String> {
+    let (threshold, interval_ms, timeout_seconds) = req.resolved()?;
+
+    let settings = settings::load_settings();
+    if settings.screenshots_paused {
+        return Ok(vec![Content::text(PAUSED_MESSAGE)]);
+    }
+    let baseline = fingerprint(&capture(req.monitor, req.region, &settings)?);
+
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs(timeout_seconds as u64);
+    let interval = Duration::from_millis(interval_ms as u64);
+    let (sample, changed_by, settings) = loop {
+        if Instant::now() + interval > deadline {
+            return Ok(vec![Content::text(format!(
+                "No significant screen change within {} second(s).",
+                timeout_seconds
+            ))]);
+        }
+        tokio::time::sleep(interval).await;
+
+        // Re-read settings so pausing takes effect mid-wait
+        let settings = settings::load_settings();
+        if settings.screenshots_paused {
+            return Ok(vec![Content::text(PAUSED_MESSAGE)]);
+        }
+        let sample = capture(req.monitor, req.region, &settings)?;
+        let diff = difference(&baseline, &fingerprint(&sample));
+        if diff >= threshold {
+            break (sample, diff, settings);
+        }
+    };
+
+    if record {
+        if let Err(e) = screenshots::record_capture(&sample, "wait_for_screen_change", req.description.as_deref(), session_id) {
+            log_line!("[MCP] Failed to record screenshot: {}", e);
+        }
+    }
+
+    let options = ImageOptions {
+        max_dimension: settings.screenshot_max_dimension,
+        quality: settings.screenshot_quality.clamp(1, 100),
+        grayscale: settings.screenshot_grayscale,
+        max_bytes: settings.screenshot_max_bytes.map(|b| b as usize),
+        ..ImageOptions::default()
+    };
+    let encoded = image_pipeline::process(image::DynamicImage::ImageRgba8(sample), &options)?;
+    let waited_for = req
+        .description
+        .as_ref()
+        .map(|d| format!(" (waiting for: {})", d))
+        .unwrap_or_default();
+    Ok(vec![
+        Content::text(format!(
+            "The screen changed by {:.0}% after {:.1}s{}. Here is the new state:",
+            changed_by * 100.0,
+            started.elapsed().as_secs_f64(),
+            waited_for
+        )),
+        Content::image(encoded.to_base64(), encoded.media_type),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn test_fingerprint_size() {
+        let fp = fingerprint(&solid(1920, 1080, 0));
+        assert_eq!(fp.dimensions(), (96, 54));
+        let fp = fingerprint(&solid(40, 30, 0));
+        assert_eq!(fp.dimensions(), (40, 30));
+    }
+
+    #[test]
+    fn test_difference() {
+        let a = fingerprint(&solid(200, 100, 10));
+        assert_eq!(difference(&a, &a), 0.0);
+
+        // Small brightness drift stays under the tolerance
+        let drift = fingerprint(&solid(200, 100, 20));
+        assert_eq!(difference(&a, &drift), 0.0);
+
+        // Half of the image turning white is a 50% change
+        let mut half = solid(200, 100, 10);
+        for (x, _, pixel) in half.enumerate_pixels_mut() {
+            if x >= 100 {
+                *pixel = Rgba([255, 255, 255, 255]);
+            }
+        }
+        let diff = difference(&a, &fingerprint(&half));
+        assert!((0.45..=0.55).contains(&diff), "{}", diff);
+
+        let other_size = fingerprint(&solid(100, 100, 10));
+        assert_eq!(difference(&a, &other_size), 1.0);
+    }
+
+    #[test]
+    fn test_request_defaults_and_limits() {
+        let req = WaitForScreenChangeRequest::default();
+        assert_eq!(
+            req.resolved().unwrap(),
+            (DEFAULT_THRESHOLD, DEFAULT_INTERVAL_MS, DEFAULT_TIMEOUT_SECONDS)
+        );
+
+        let bad = |req: WaitForScreenChangeRequest| req.resolved().is_err();
+        assert!(bad(WaitForScreenChangeRequest { threshold: Some(0.0), ..Default::default() }));
+        assert!(bad(WaitForScreenChangeRequest { interval_ms: Some(10), ..Default::default() }));
+        assert!(bad(WaitForScreenChangeRequest { timeout_seconds: Some(7200), ..Default::default() }));
+    }
+}
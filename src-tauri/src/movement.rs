@@ -0,0 +1,448 @@
+//! Mascot movement targets
+//!
+//! `move_to` accepts absolute points, relative offsets, named anchors and
+//! waypoints. Anchors are resolved here against real monitor and window
+//! geometry (in the GUI process) before `clawd-move` is emitted, so the
+//! frontend only has to follow a list of logical-pixel points.
+
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+/// Default walking speed multiplier
+pub const DEFAULT_SPEED: f64 = 1.0;
+
+/// Allowed range for the speed multiplier
+const SPEED_RANGE: (f64, f64) = (0.25, 4.0);
+
+/// Named anchors accepted in `target`
+pub const ANCHORS: &[&str] = &[
+    "left",
+    "right",
+    "center",
+    "top",
+    "bottom",
+    "top-left",
+    "top-right",
+    "bottom-left",
+    "bottom-right",
+    "focused_window",
+    "focused_window_title",
+];
+
+/// One step of a movement
+#[derive(Debug, Clone, Default, Deserialize, schemars::JsonSchema)]
+pub struct MoveStep {
+    /// Named anchor: "left", "right", "center", "top", "bottom", "top-left", "top-right",
+    /// "bottom-left", "bottom-right", "focused_window" (next to the focused window),
+    /// "focused_window_title" (on top of its title bar), or an x-coordinate number
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Absolute x in logical pixels
+    #[serde(default)]
+    pub x: Option<f64>,
+    /// Absolute y in logical pixels (omit to stay on the floor)
+    #[serde(default)]
+    pub y: Option<f64>,
+    /// Horizontal offset from the anchor or the previous position
+    #[serde(default)]
+    pub dx: Option<f64>,
+    /// Vertical offset from the anchor or the previous position
+    #[serde(default)]
+    pub dy: Option<f64>,
+    /// 1-based monitor index for screen anchors (default: the mascot's monitor)
+    #[serde(default)]
+    pub monitor: Option<u32>,
+}
+
+/// Request to move the mascot
+#[derive(Debug, Clone, Default, Deserialize, schemars::JsonSchema)]
+pub struct MoveToRequest {
+    #[serde(flatten)]
+    pub step: MoveStep,
+    /// Walking speed multiplier (0.25-4.0, default 1.0)
+    #[serde(default)]
+    pub speed: Option<f64>,
+    /// Further points to visit after the first one, in order
+    #[serde(default)]
+    pub waypoints: Vec<MoveStep>,
+}
+
+impl MoveToRequest {
+    /// Parse the input of a move_to tool_use from the CLI stream
+    pub fn from_tool_input(input: &serde_json::Value) -> Result<Self, String> {
+        serde_json::from_value(input.clone()).map_err(|e| format!("Invalid move_to input: {}", e))
+    }
+
+    /// Shorthand for moving to a named anchor
+    pub fn anchor(target: &str) -> Self {
+        Self {
+            step: MoveStep {
+                target: Some(target.to_string()),
+                ..MoveStep::default()
+            },
+            ..Self::default()
+        }
+    }
+
+    /// All steps in order
+    pub fn steps(&self) -> impl Iterator<Item = &MoveStep> {
+        std::iter::once(&self.step).chain(self.waypoints.iter())
+    }
+
+    /// Validate the request without resolving it against screen geometry
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(speed) = self.speed {
+            if !(SPEED_RANGE.0..=SPEED_RANGE.1).contains(&speed) {
+                return Err(format!(
+                    "Speed must be between {} and {}, got {}",
+                    SPEED_RANGE.0, SPEED_RANGE.1, speed
+                ));
+            }
+        }
+        for step in self.steps() {
+            if step.target.is_none() && step.x.is_none() && step.y.is_none() && step.dx.is_none() && step.dy.is_none() {
+                return Err("Each move step needs a target, x/y coordinates or a dx/dy offset".to_string());
+            }
+            if let Some(target) = &step.target {
+                if !ANCHORS.contains(&target.as_str()) && target.parse::<f64>().is_err() {
+                    return Err(format!(
+                        "Unknown target '{}'. Valid anchors: {}, or an x-coordinate",
+                        target,
+                        ANCHORS.join(", ")
+                    ));
+                }
+            }
+            if step.monitor == Some(0) {
+                return Err("Monitor indexes start at 1".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A rectangle in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A resolved point for the mascot window's top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MovePoint {
+    pub x: f64,
+    /// None keeps the mascot on the floor
+    pub y: Option<f64>,
+}
+
+/// Payload of the `clawd-move` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MoveEvent {
+    /// Always "path"; older payloads used "left"/"right"/"center"/"coordinates"
+    pub target: String,
+    /// Final x, for listeners that only understand horizontal moves
+    pub x: Option<f64>,
+    pub points: Vec<MovePoint>,
+    pub speed: f64,
+}
+
+/// Screen geometry needed to resolve anchors
+#[derive(Debug, Clone)]
+pub struct Geometry {
+    pub monitors: Vec<Rect>,
+    /// Index into `monitors` of the mascot's monitor
+    pub current_monitor: usize,
+    /// Current mascot window bounds
+    pub mascot: Rect,
+    pub focused_window: Option<Rect>,
+}
+
+impl Geometry {
+    fn monitor(&self, index: Option<u32>) -> Result<Rect, String> {
+        match index {
+            Some(n) => self
+                .monitors
+                .get(n as usize - 1)
+                .copied()
+                .ok_or_else(|| format!("Monitor {} not found ({} connected)", n, self.monitors.len())),
+            None => self
+                .monitors
+                .get(self.current_monitor)
+                .copied()
+                .ok_or_else(|| "No monitors found".to_string()),
+        }
+    }
+
+    /// Bounding box of all monitors
+    fn desktop(&self) -> Rect {
+        let left = self.monitors.iter().map(|m| m.x).fold(f64::MAX, f64::min);
+        let top = self.monitors.iter().map(|m| m.y).fold(f64::MAX, f64::min);
+        let right = self.monitors.iter().map(|m| m.x + m.width).fold(f64::MIN, f64::max);
+        let bottom = self.monitors.iter().map(|m| m.y + m.height).fold(f64::MIN, f64::max);
+        Rect {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        }
+    }
+}
+
+/// Resolve a named anchor to a point
+fn resolve_anchor(anchor: &str, monitor: Rect, geometry: &Geometry) -> Result<MovePoint, String> {
+    let (w, h) = (geometry.mascot.width, geometry.mascot.height);
+    let left = monitor.x;
+    let right = monitor.x + monitor.width - w;
+    let center = monitor.x + (monitor.width - w) / 2.0;
+    let top = Some(monitor.y);
+
+    let point = match anchor {
+        "left" | "bottom-left" => MovePoint { x: left, y: None },
+        "right" | "bottom-right" => MovePoint { x: right, y: None },
+        "center" | "bottom" => MovePoint { x: center, y: None },
+        "top" => MovePoint { x: center, y: top },
+        "top-left" => MovePoint { x: left, y: top },
+        "top-right" => MovePoint { x: right, y: top },
+        "focused_window" | "focused_window_title" => {
+            let window = geometry
+                .focused_window
+                .ok_or("No focused window found")?;
+            if anchor == "focused_window" {
+                // Stand just right of the window, or left of it if there is no room
+                let beside = if window.x + window.width + w <= monitor.x + monitor.width {
+                    window.x + window.width
+                } else {
+                    window.x - w
+                };
+                MovePoint { x: beside, y: None }
+            } else {
+                MovePoint {
+                    x: window.x + (window.width - w) / 2.0,
+                    y: Some(window.y - h),
+                }
+            }
+        }
+        other => match other.parse::<f64>() {
+            Ok(x) => MovePoint { x, y: None },
+            Err(_) => return Err(format!("Unknown target '{}'", other)),
+        },
+    };
+    Ok(point)
+}
+
+/// Resolve one step relative to the previous point
+fn resolve_step(step: &MoveStep, from: MovePoint, geometry: &Geometry) -> Result<MovePoint, String> {
+    let mut point = match &step.target {
+        Some(anchor) => resolve_anchor(anchor, geometry.monitor(step.monitor)?, geometry)?,
+        None => from,
+    };
+    if let Some(x) = step.x {
+        point.x = x;
+    }
+    if let Some(y) = step.y {
+        point.y = Some(y);
+    }
+    if let Some(dx) = step.dx {
+        point.x += dx;
+    }
+    if let Some(dy) = step.dy {
+        // Relative vertical moves start from the current height, even on the floor
+        point.y = Some(point.y.unwrap_or(geometry.mascot.y) + dy);
+    }
+
+    // Keep the mascot fully on screen (or at the top left if the screen is smaller)
+    let desktop = geometry.desktop();
+    point.x = point
+        .x
+        .min(desktop.x + desktop.width - geometry.mascot.width)
+        .max(desktop.x);
+    point.y = point.y.map(|y| {
+        y.min(desktop.y + desktop.height - geometry.mascot.height)
+            .max(desktop.y)
+    });
+    Ok(point)
+}
+
+/// Resolve a request into a path of points
+pub fn resolve(request: &MoveToRequest, geometry: &Geometry) -> Result<MoveEvent, String> {
+    request.validate()?;
+    if geometry.monitors.is_empty() {
+        return Err("No monitors found".to_string());
+    }
+
+    let mut from = MovePoint {
+        x: geometry.mascot.x,
+        y: None,
+    };
+    let mut points = Vec::new();
+    for step in request.steps() {
+        from = resolve_step(step, from, geometry)?;
+        points.push(from);
+    }
+
+    Ok(MoveEvent {
+        target: "path".to_string(),
+        x: points.last().map(|p| p.x),
+        points,
+        speed: request.speed.unwrap_or(DEFAULT_SPEED),
+    })
+}
+
+/// Read monitor, mascot and focused window geometry in logical pixels
+pub fn current_geometry(app: &tauri::AppHandle) -> Result<Geometry, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Main window not found")?;
+    // The frontend converts everything with the mascot window's scale factor; match it
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let rects: Vec<Rect> = monitors
+        .iter()
+        .map(|m| Rect {
+            x: m.position().x as f64 / scale,
+            y: m.position().y as f64 / scale,
+            width: m.size().width as f64 / scale,
+            height: m.size().height as f64 / scale,
+        })
+        .collect();
+    let current_monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|current| monitors.iter().position(|m| m.position() == current.position()))
+        .unwrap_or(0);
+
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let mascot = Rect {
+        x: position.x as f64 / scale,
+        y: position.y as f64 / scale,
+        width: size.width as f64 / scale,
+        height: size.height as f64 / scale,
+    };
+
+    Ok(Geometry {
+        monitors: rects,
+        current_monitor,
+        mascot,
+        focused_window: focused_window(scale),
+    })
+}
+
+/// Bounds of the focused window of another application
+fn focused_window(scale: f64) -> Option<Rect> {
+    let own_pid = std::process::id();
+    xcap::Window::all()
+        .ok()?
+        .into_iter()
+        .find(|w| {
+            w.is_focused().unwrap_or(false)
+                && !w.is_minimized().unwrap_or(false)
+                && w.pid().map(|pid| pid != own_pid).unwrap_or(true)
+        })
+        .and_then(|w| {
+            Some(Rect {
+                x: w.x().ok()? as f64 / scale,
+                y: w.y().ok()? as f64 / scale,
+                width: w.width().ok()? as f64 / scale,
+                height: w.height().ok()? as f64 / scale,
+            })
+        })
+}
+
+/// Resolve a request against the current screen and emit `clawd-move`
+pub fn emit_move(app: &tauri::AppHandle, request: &MoveToRequest) -> Result<MoveEvent, String> {
+    let event = resolve(request, &current_geometry(app)?)?;
+    app.emit("clawd-move", &event).map_err(|e| e.to_string())?;
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry() -> Geometry {
+        Geometry {
+            monitors: vec![
+                Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 },
+                Rect { x: 1920.0, y: 0.0, width: 1280.0, height: 1024.0 },
+            ],
+            current_monitor: 0,
+            mascot: Rect { x: 100.0, y: 900.0, width: 160.0, height: 140.0 },
+            focused_window: Some(Rect { x: 400.0, y: 200.0, width: 800.0, height: 600.0 }),
+        }
+    }
+
+    fn request(json: serde_json::Value) -> MoveToRequest {
+        MoveToRequest::from_tool_input(&json).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_targets() {
+        let event = resolve(&request(serde_json::json!({ "target": "right" })), &geometry()).unwrap();
+        assert_eq!(event.points, [MovePoint { x: 1760.0, y: None }]);
+
+        let event = resolve(&request(serde_json::json!({ "target": "500" })), &geometry()).unwrap();
+        assert_eq!(event.x, Some(500.0));
+    }
+
+    #[test]
+    fn test_monitor_anchor() {
+        let req = request(serde_json::json!({ "target": "bottom", "monitor": 2 }));
+        let event = resolve(&req, &geometry()).unwrap();
+        assert_eq!(event.points, [MovePoint { x: 2480.0, y: None }]);
+
+        let req = request(serde_json::json!({ "target": "center", "monitor": 3 }));
+        assert!(resolve(&req, &geometry()).is_err());
+    }
+
+    #[test]
+    fn test_focused_window_title() {
+        let req = request(serde_json::json!({ "target": "focused_window_title" }));
+        let event = resolve(&req, &geometry()).unwrap();
+        assert_eq!(event.points, [MovePoint { x: 720.0, y: Some(60.0) }]);
+    }
+
+    #[test]
+    fn test_relative_waypoints_and_clamping() {
+        let req = request(serde_json::json!({
+            "dx": 200,
+            "speed": 2.0,
+            "waypoints": [{ "dx": 100, "dy": -300 }, { "x": -50 }]
+        }));
+        let event = resolve(&req, &geometry()).unwrap();
+        assert_eq!(
+            event.points,
+            [
+                MovePoint { x: 300.0, y: None },
+                MovePoint { x: 400.0, y: Some(600.0) },
+                MovePoint { x: 0.0, y: Some(600.0) },
+            ]
+        );
+        assert_eq!(event.speed, 2.0);
+    }
+
+    #[test]
+    fn test_small_desktop_and_no_monitors() {
+        let mut small = geometry();
+        small.monitors = vec![Rect { x: 0.0, y: 0.0, width: 100.0, height: 100.0 }];
+        let req = request(serde_json::json!({ "dx": 50, "dy": 50 }));
+        let event = resolve(&req, &small).unwrap();
+        assert_eq!(event.points, [MovePoint { x: 0.0, y: Some(0.0) }]);
+
+        small.monitors.clear();
+        assert!(resolve(&req, &small).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_input() {
+        assert!(request(serde_json::json!({ "target": "moon" })).validate().is_err());
+        assert!(request(serde_json::json!({ "target": "left", "speed": 10 })).validate().is_err());
+        assert!(request(serde_json::json!({ "speed": 1 })).validate().is_err());
+        assert!(request(serde_json::json!({ "target": "left", "monitor": 0 })).validate().is_err());
+    }
+}
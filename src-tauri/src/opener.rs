@@ -0,0 +1,250 @@
+//! Opening URLs and local paths with the system handler
+//!
+//! The agent can open a docs page or reveal a file through the `open_url` and
+//! `open_path` MCP tools. Targets on the allow list in the settings open right
+//! away; anything else needs a per-call confirmation from the user.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use rmcp::schemars;
+use serde::Deserialize;
+use tauri::Url;
+
+use crate::approvals;
+use crate::settings::{load_settings, AppSettings};
+
+/// Schemes that are never opened (they run code or read arbitrary data)
+const BLOCKED_SCHEMES: &[&str] = &["javascript", "data", "file", "vbscript"];
+
+/// Request to open a URL
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct OpenUrlRequest {
+    /// The URL to open in the default browser
+    pub url: String,
+}
+
+/// Request to open a local file or folder
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct OpenPathRequest {
+    /// Absolute path (or ~/...) of the file or folder to open
+    pub path: String,
+}
+
+/// Whether a target may be opened right away
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Allowed,
+    /// Outside the allow list; the reason is shown to the user
+    NeedsConfirmation(String),
+}
+
+/// Check a URL against the allow list
+pub fn check_url(url: &str, settings: &AppSettings) -> Result<(Url, Verdict), String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    let scheme = parsed.scheme();
+    if BLOCKED_SCHEMES.contains(&scheme) {
+        return Err(format!("Opening '{}:' URLs is not allowed", scheme));
+    }
+
+    if !settings
+        .open_allowed_schemes
+        .iter()
+        .any(|s| s.eq_ignore_ascii_case(scheme))
+    {
+        let verdict = Verdict::NeedsConfirmation(format!("The '{}' scheme is not on the allow list", scheme));
+        return Ok((parsed, verdict));
+    }
+
+    // Schemes like mailto: have no host; the scheme alone decides
+    let verdict = match parsed.host_str() {
+        Some(host) if !domain_allowed(host, &settings.open_allowed_domains) => {
+            Verdict::NeedsConfirmation(format!("{} is not on the allow list", host))
+        }
+        _ => Verdict::Allowed,
+    };
+    Ok((parsed, verdict))
+}
+
+/// Whether a host is an allowed domain or one of its subdomains
+fn domain_allowed(host: &str, domains: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    domains.iter().any(|domain| {
+        let domain = domain.trim().trim_start_matches("*.").to_ascii_lowercase();
+        !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+    })
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~") {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') => {
+            match dirs::home_dir() {
+                Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+                None => PathBuf::from(path),
+            }
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Check a local path against the allow list
+pub fn check_path(path: &str, settings: &AppSettings) -> Result<(PathBuf, Verdict), String> {
+    let expanded = expand_home(path.trim());
+    if !expanded.is_absolute() {
+        return Err(format!("Path '{}' must be absolute", path));
+    }
+    // Resolve symlinks and `..` so prefixes can't be escaped
+    let resolved = expanded
+        .canonicalize()
+        .map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+
+    let allowed = settings
+        .open_allowed_path_prefixes
+        .iter()
+        .filter_map(|prefix| expand_home(prefix).canonicalize().ok())
+        .any(|prefix| resolved.starts_with(prefix));
+    let verdict = if allowed {
+        Verdict::Allowed
+    } else {
+        Verdict::NeedsConfirmation(format!("{} is outside the allowed folders", resolved.display()))
+    };
+    Ok((resolved, verdict))
+}
+
+/// Program and arguments that open `target` with the default handler
+/// The target is passed as a single argument and never through a shell, so
+/// characters like `&`, `|` or `^` in an agent-supplied URL can't run commands
+/// (as they would with `cmd /C start`).
+fn system_command(target: &str) -> (&'static str, Vec<&str>) {
+    #[cfg(target_os = "windows")]
+    let command = ("rundll32", vec!["url.dll,FileProtocolHandler", target]);
+
+    #[cfg(target_os = "macos")]
+    let command = ("open", vec![target]);
+
+    #[cfg(target_os = "linux")]
+    let command = ("xdg-open", vec![target]);
+
+    command
+}
+
+/// Open a URL or path with the system's default handler
+pub fn open_with_system(target: &str) -> Result<(), String> {
+    let (program, args) = system_command(target);
+    Command::new(program)
+        .args(args)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open '{}': {}", target, e))
+}
+
+/// Ask the user when the verdict requires it; refusals become tool errors
+fn confirm(app: &tauri::AppHandle, verdict: Verdict, title: &str, target: &str) -> Result<(), String> {
+    match verdict {
+        Verdict::Allowed => Ok(()),
+        Verdict::NeedsConfirmation(reason) => {
+            let detail = format!("{}\n\n{}", target, reason);
+            if approvals::request(app, title, &detail) {
+                Ok(())
+            } else {
+                Err(format!("The user declined to open {}", target))
+            }
+        }
+    }
+}
+
+/// Open a URL for the agent (blocks while asking for confirmation)
+pub fn open_url(app: &tauri::AppHandle, request: &OpenUrlRequest) -> Result<String, String> {
+    let (url, verdict) = check_url(&request.url, &load_settings())?;
+    confirm(app, verdict, "Open link?", url.as_str())?;
+    open_with_system(url.as_str())?;
+    log_line!("[Rust] Opened URL for agent");
+    log_content!("[Rust] Opened URL: {}", url);
+    Ok(format!("Opened {} in the default browser.", url))
+}
+
+/// Open a local path for the agent (blocks while asking for confirmation)
+pub fn open_path(app: &tauri::AppHandle, request: &OpenPathRequest) -> Result<String, String> {
+    let (path, verdict) = check_path(&request.path, &load_settings())?;
+    let target = path.to_string_lossy().to_string();
+    confirm(app, verdict, "Open file?", &target)?;
+    open_with_system(&target)?;
+    log_line!("[Rust] Opened path for agent");
+    log_content!("[Rust] Opened path: {}", target);
+    Ok(format!("Opened {} with the default application.", target))
+}
+
+/// Bridge handler for open_url
+pub fn open_url_from_params(app: &tauri::AppHandle, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    let request: OpenUrlRequest =
+        serde_json::from_value(params).map_err(|e| format!("Invalid open_url params: {}", e))?;
+    open_url(app, &request).map(serde_json::Value::String)
+}
+
+/// Bridge handler for open_path
+pub fn open_path_from_params(app: &tauri::AppHandle, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    let request: OpenPathRequest =
+        serde_json::from_value(params).map_err(|e| format!("Invalid open_path params: {}", e))?;
+    open_path(app, &request).map(serde_json::Value::String)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_url_allow_list() {
+        let settings = AppSettings::default();
+        let (_, verdict) = check_url("https://docs.rs/serde", &settings).unwrap();
+        assert_eq!(verdict, Verdict::Allowed);
+        let (_, verdict) = check_url("https://gist.github.com/x", &settings).unwrap();
+        assert_eq!(verdict, Verdict::Allowed);
+        let (_, verdict) = check_url("https://notgithub.com/", &settings).unwrap();
+        assert!(matches!(verdict, Verdict::NeedsConfirmation(_)));
+        let (_, verdict) = check_url("http://docs.rs/", &settings).unwrap();
+        assert!(matches!(verdict, Verdict::NeedsConfirmation(_)));
+    }
+
+    #[test]
+    fn test_check_url_rejects() {
+        let settings = AppSettings::default();
+        assert!(check_url("not a url", &settings).is_err());
+        assert!(check_url("javascript:alert(1)", &settings).is_err());
+        assert!(check_url("file:///etc/passwd", &settings).is_err());
+    }
+
+    #[test]
+    fn test_system_command_passes_metacharacters_through() {
+        let settings = AppSettings::default();
+        let (url, _) = check_url("https://github.com/x&calc.exe|whoami^%PATH%", &settings).unwrap();
+        let (program, args) = system_command(url.as_str());
+        assert!(!["cmd", "sh", "bash", "powershell"].contains(&program));
+        assert_eq!(args.last(), Some(&url.as_str()));
+        assert!(url.as_str().contains("&calc.exe"));
+    }
+
+    #[test]
+    fn test_check_path_prefixes() {
+        let dir = std::env::temp_dir().join(format!("supiki-opener-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("inner")).unwrap();
+        let mut settings = AppSettings::default();
+
+        let target = dir.join("inner");
+        let (_, verdict) = check_path(&target.to_string_lossy(), &settings).unwrap();
+        assert!(matches!(verdict, Verdict::NeedsConfirmation(_)));
+
+        settings.open_allowed_path_prefixes = vec![dir.to_string_lossy().to_string()];
+        let (_, verdict) = check_path(&target.to_string_lossy(), &settings).unwrap();
+        assert_eq!(verdict, Verdict::Allowed);
+
+        // `..` can't escape the allowed folder
+        let escaped = dir.join("inner").join("..").join("..");
+        let (_, verdict) = check_path(&escaped.to_string_lossy(), &settings).unwrap();
+        assert!(matches!(verdict, Verdict::NeedsConfirmation(_)));
+
+        assert!(check_path("relative/path", &settings).is_err());
+        assert!(check_path(&dir.join("missing").to_string_lossy(), &settings).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,204 @@
+//! Permission prompts for Claude tool calls
+//!
+//! Outside dev mode Claude CLI is started with `--permission-prompt-tool`
+//! pointing at the mascot MCP server's `approve_permission` tool. The tool
+//! forwards each request over the bridge to the GUI, which asks the user in an
+//! approval window and replies with the allow/deny JSON Claude expects.
+//! The permission policy (see `policy`) is applied first, so only calls it
+//! leaves at "ask" reach the user. An "always allow" answer is saved in the
+//! policy as an allow rule for that exact command or file in the workspace.
+//! The policy denies `approve_permission` itself, so the model can't call it
+//! to raise prompts or collect "always allow" grants for calls it made up.
+
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+use crate::approvals::{self, ApprovalDecision};
+use crate::audit;
+use crate::policy::{self, PolicyAction};
+use crate::state::{DEV_MODE, SIDECAR_CWD};
+
+/// Longest tool input shown in the approval window, in characters
+const MAX_INPUT_CHARS: usize = 300;
+
+/// A permission request from Claude CLI
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PermissionPromptRequest {
+    /// Name of the tool Claude wants to use
+    pub tool_name: String,
+    /// The tool's input
+    #[serde(default)]
+    pub input: serde_json::Value,
+    #[serde(default)]
+    pub tool_use_id: Option<String>,
+    /// Working directory of the agent (filled in by the MCP server)
+    #[serde(default)]
+    pub cwd: Option<String>,
+}
+
+/// Reply format of a permission prompt tool
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "behavior", rename_all = "lowercase")]
+pub enum PermissionDecision {
+    Allow {
+        #[serde(rename = "updatedInput")]
+        updated_input: serde_json::Value,
+    },
+    Deny {
+        message: String,
+    },
+}
+
+impl PermissionDecision {
+    pub fn allow(input: &serde_json::Value) -> Self {
+        PermissionDecision::Allow {
+            updated_input: input.clone(),
+        }
+    }
+
+    pub fn deny(message: impl Into<String>) -> Self {
+        PermissionDecision::Deny {
+            message: message.into(),
+        }
+    }
+
+    /// The JSON text returned to Claude CLI
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl PermissionPromptRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tool_name.trim().is_empty() {
+            return Err("tool_name must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Human-readable summary of the input (the command, path or raw JSON)
+    pub fn describe_input(&self) -> String {
+        let field = |key: &str| self.input.get(key).and_then(|v| v.as_str());
+        let text = field("command")
+            .or_else(|| field("file_path"))
+            .or_else(|| field("url"))
+            .or_else(|| field("pattern"))
+            .map(str::to_string)
+            .unwrap_or_else(|| self.input.to_string());
+        if text.chars().count() > MAX_INPUT_CHARS {
+            format!("{}...", text.chars().take(MAX_INPUT_CHARS).collect::<String>())
+        } else {
+            text
+        }
+    }
+}
+
+/// Ask the user about a tool call (blocks until they answer)
+pub fn prompt(app: &tauri::AppHandle, request: &PermissionPromptRequest) -> PermissionDecision {
+    if let Err(e) = request.validate() {
+        return PermissionDecision::deny(e);
+    }
+
+    let cwd = SIDECAR_CWD.lock().unwrap().clone().or_else(|| request.cwd.clone());
+    let is_dev = *DEV_MODE.lock().unwrap();
+    let policy = policy::effective_policy(cwd.as_deref(), is_dev);
+    let matched = policy.matching_action(&request.tool_name, &request.input);
+    match matched.unwrap_or(policy.default_action) {
+        PolicyAction::Allow => {
+            audit::record_permission(&request.tool_name, &request.input, "allowed by policy");
+            return PermissionDecision::allow(&request.input);
+        }
+        PolicyAction::Deny => {
+            audit::record_permission(&request.tool_name, &request.input, "denied by policy");
+            return PermissionDecision::deny(format!(
+                "{} is denied by the user's permission policy",
+                request.tool_name
+            ))
+        }
+        PolicyAction::Ask => {}
+    }
+    // "Always allow" is offered only when a rule for this workspace and exact
+    // command or file could answer next time; a matching ask rule outranks it
+    let grant = cwd
+        .as_deref()
+        .filter(|_| matched != Some(PolicyAction::Ask))
+        .and_then(|cwd| policy::grant_rule(&request.tool_name, &request.input, cwd));
+
+    let mut detail = request.describe_input();
+    if let Some(cwd) = &cwd {
+        detail = format!("{}\n\nin {}", detail, cwd);
+    }
+    let title = format!("Allow {}?", request.tool_name);
+
+    match approvals::ask(app, &title, &detail, grant.is_some()) {
+        ApprovalDecision::Deny => PermissionDecision::deny(format!(
+            "The user denied permission to use {}",
+            request.tool_name
+        )),
+        ApprovalDecision::Allow => PermissionDecision::allow(&request.input),
+        ApprovalDecision::AlwaysAllow => {
+            if let (Some(cwd), Some(rule)) = (cwd.as_deref(), grant) {
+                if let Err(e) = policy::save_workspace_rule(cwd, rule) {
+                    log_line!("[Rust] Failed to remember permission: {}", e);
+                }
+            }
+            PermissionDecision::allow(&request.input)
+        }
+    }
+}
+
+/// Bridge handler for permission_prompt
+pub fn prompt_from_params(app: &tauri::AppHandle, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    let request: PermissionPromptRequest = serde_json::from_value(params)
+        .map_err(|e| format!("Invalid permission_prompt params: {}", e))?;
+    Ok(serde_json::Value::String(prompt(app, &request).to_json()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(tool_name: &str, input: serde_json::Value) -> PermissionPromptRequest {
+        PermissionPromptRequest {
+            tool_name: tool_name.to_string(),
+            input,
+            tool_use_id: None,
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_decision_json() {
+        let allow = PermissionDecision::allow(&json!({ "command": "ls" }));
+        assert_eq!(
+            allow.to_json(),
+            r#"{"behavior":"allow","updatedInput":{"command":"ls"}}"#
+        );
+        let deny = PermissionDecision::deny("no");
+        assert_eq!(deny.to_json(), r#"{"behavior":"deny","message":"no"}"#);
+    }
+
+    #[test]
+    fn test_describe_input() {
+        assert_eq!(request("Bash", json!({ "command": "cargo test" })).describe_input(), "cargo test");
+        assert_eq!(request("Edit", json!({ "file_path": "/tmp/a.rs", "old_string": "x" })).describe_input(), "/tmp/a.rs");
+        assert_eq!(request("Task", json!({ "n": 1 })).describe_input(), r#"{"n":1}"#);
+
+        let long = request("Bash", json!({ "command": "x".repeat(MAX_INPUT_CHARS + 10) }));
+        assert_eq!(long.describe_input().chars().count(), MAX_INPUT_CHARS + 3);
+    }
+
+    #[test]
+    fn test_request_parsing_and_validation() {
+        let parsed: PermissionPromptRequest = serde_json::from_value(json!({
+            "tool_name": "Write",
+            "input": { "file_path": "/tmp/x" },
+            "tool_use_id": "toolu_1"
+        }))
+        .unwrap();
+        assert!(parsed.validate().is_ok());
+        assert_eq!(parsed.tool_use_id.as_deref(), Some("toolu_1"));
+        assert!(request(" ", json!({})).validate().is_err());
+    }
+}
@@ -0,0 +1,427 @@
+//! Reminders and timers
+//!
+//! Reminders are created by the agent through the mascot MCP server
+//! (`set_reminder`, `list_reminders`, `cancel_reminder`) and persisted to disk,
+//! so they survive an app restart. The GUI process runs a scheduler thread that
+//! fires due reminders as Tauri events. Both processes change the file, so
+//! each change holds a lock file and replaces the file with an atomic rename.
+//! A file that fails to parse is never overwritten, so its reminders survive.
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::movement::{self, MoveToRequest};
+
+/// How often the scheduler checks for due reminders
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest delay accepted for a reminder (one year)
+pub const MAX_DELAY_MINUTES: f64 = 525_600.0;
+
+/// How long to wait for the other process to release the lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A lock file older than this was left by a process that died holding it
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// A persisted reminder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    pub id: String,
+    pub message: String,
+    /// Unix timestamp in milliseconds when the reminder is due
+    pub due_at: u64,
+    /// Unix timestamp in milliseconds when the reminder was created
+    pub created_at: u64,
+}
+
+/// Event emitted to the frontend when a reminder is due
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderDueEvent {
+    pub id: String,
+    pub message: String,
+    pub due_at: u64,
+}
+
+/// Current time as a Unix timestamp in milliseconds
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Get the reminders file path for persistence
+pub fn get_reminders_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("reminders.json"))
+}
+
+/// Read the reminders file (empty if missing), failing if it doesn't parse
+fn read_reminders(path: &Path) -> Result<Vec<Reminder>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse reminders file: {}", e))
+        }
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Load reminders from disk (empty if missing or unreadable)
+pub fn load_reminders() -> Vec<Reminder> {
+    let Some(path) = get_reminders_file_path() else {
+        return Vec::new();
+    };
+    read_reminders(&path).unwrap_or_else(|e| {
+        log_line!("[Rust] {}", e);
+        Vec::new()
+    })
+}
+
+/// An exclusive lock file, removed when dropped
+struct FileLock(PathBuf);
+
+impl FileLock {
+    fn acquire(path: &Path) -> Result<Self, String> {
+        let started = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => return Ok(FileLock(path.to_path_buf())),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(path)
+                        .and_then(|m| m.modified())
+                        .map(|modified| modified.elapsed().unwrap_or_default() > STALE_LOCK_AGE)
+                        .unwrap_or(false);
+                    if stale {
+                        let _ = fs::remove_file(path);
+                    } else if started.elapsed() > LOCK_TIMEOUT {
+                        return Err("Timed out waiting for the reminders lock".to_string());
+                    } else {
+                        thread::sleep(Duration::from_millis(20));
+                    }
+                }
+                Err(e) => return Err(format!("Failed to lock reminders: {}", e)),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Replace `path` with `content` through a temp file and rename, so a reader
+/// never sees a half-written file
+fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let temp = path.with_extension(format!("json.tmp-{}", std::process::id()));
+    fs::write(&temp, content).map_err(|e| format!("Failed to write reminders: {}", e))?;
+    fs::rename(&temp, path).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("Failed to replace reminders file: {}", e)
+    })
+}
+
+/// Load, change and save the reminders while holding the lock
+/// A reminders file that doesn't parse is left alone rather than overwritten.
+fn update_reminders<T>(f: impl FnOnce(&mut Vec<Reminder>) -> Result<T, String>) -> Result<T, String> {
+    let path = get_reminders_file_path().ok_or("Could not find data directory")?;
+    update_reminders_at(&path, f)
+}
+
+fn update_reminders_at<T>(
+    path: &Path,
+    f: impl FnOnce(&mut Vec<Reminder>) -> Result<T, String>,
+) -> Result<T, String> {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _lock = FileLock::acquire(&path.with_extension("json.lock"))?;
+
+    let mut reminders =
+        read_reminders(path).map_err(|e| format!("Not changing the reminders file: {}", e))?;
+    let before = reminders.clone();
+    let result = f(&mut reminders)?;
+    if reminders != before {
+        let content = serde_json::to_string_pretty(&reminders)
+            .map_err(|e| format!("Failed to serialize reminders: {}", e))?;
+        write_atomic(path, &content)?;
+    }
+    Ok(result)
+}
+
+/// The delay of a reminder set `minutes` plus `seconds` from now
+/// Rejects negative or non-finite minutes and totals over `MAX_DELAY_MINUTES`.
+pub fn reminder_delay(minutes: Option<f64>, seconds: Option<u64>) -> Result<Duration, String> {
+    let out_of_range = || format!("The delay must be between 0 and {} minutes", MAX_DELAY_MINUTES);
+    let minutes = minutes.unwrap_or(0.0);
+    if !(0.0..=MAX_DELAY_MINUTES).contains(&minutes) {
+        return Err(out_of_range());
+    }
+    Duration::from_secs_f64(minutes * 60.0)
+        .checked_add(Duration::from_secs(seconds.unwrap_or(0)))
+        .filter(|delay| delay.as_secs_f64() <= MAX_DELAY_MINUTES * 60.0)
+        .ok_or_else(out_of_range)
+}
+
+/// Create a reminder due after `delay` and persist it
+pub fn add_reminder(message: &str, delay: Duration) -> Result<Reminder, String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("Reminder message must not be empty".to_string());
+    }
+    if delay.is_zero() {
+        return Err("Reminder delay must be greater than zero".to_string());
+    }
+    if delay.as_secs_f64() > MAX_DELAY_MINUTES * 60.0 {
+        return Err(format!("Reminder delay must be at most {} minutes", MAX_DELAY_MINUTES));
+    }
+
+    let reminder = update_reminders(|reminders| {
+        let created_at = now_ms();
+        let reminder = Reminder {
+            id: next_reminder_id(reminders, created_at),
+            message: message.to_string(),
+            due_at: created_at + delay.as_millis() as u64,
+            created_at,
+        };
+        reminders.push(reminder.clone());
+        Ok(reminder)
+    })?;
+
+    log_line!("[Rust] Reminder {} set for {}", reminder.id, reminder.due_at);
+    Ok(reminder)
+}
+
+/// List pending reminders, soonest first
+pub fn list_reminders() -> Vec<Reminder> {
+    let mut reminders = load_reminders();
+    reminders.sort_by_key(|r| r.due_at);
+    reminders
+}
+
+/// Cancel a pending reminder by id
+pub fn cancel_reminder(id: &str) -> Result<Reminder, String> {
+    let removed = update_reminders(|reminders| {
+        let index = reminders
+            .iter()
+            .position(|r| r.id == id)
+            .ok_or_else(|| format!("No pending reminder with id '{}'", id))?;
+        Ok(reminders.remove(index))
+    })?;
+
+    log_line!("[Rust] Reminder {} cancelled", removed.id);
+    Ok(removed)
+}
+
+/// Split reminders into (due, pending) relative to `now`
+fn split_due(reminders: Vec<Reminder>, now: u64) -> (Vec<Reminder>, Vec<Reminder>) {
+    reminders.into_iter().partition(|r| r.due_at <= now)
+}
+
+/// Remove due reminders from the store and return them
+pub fn take_due_reminders(now: u64) -> Vec<Reminder> {
+    let result = update_reminders(|reminders| {
+        let (due, pending) = split_due(std::mem::take(reminders), now);
+        *reminders = pending;
+        Ok(due)
+    });
+    result.unwrap_or_else(|e| {
+        log_line!("[Rust] {}", e);
+        Vec::new()
+    })
+}
+
+/// Size and modification time of the reminders file, to notice changes
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// When the earliest reminder in the file is due, if any
+fn earliest_due(path: &Path) -> Option<u64> {
+    read_reminders(path).ok()?.iter().map(|r| r.due_at).min()
+}
+
+/// Generate an id that is not used by any existing reminder
+fn next_reminder_id(existing: &[Reminder], seed: u64) -> String {
+    let mut candidate = seed;
+    loop {
+        let id = format!("rem-{}", candidate);
+        if !existing.iter().any(|r| r.id == id) {
+            return id;
+        }
+        candidate += 1;
+    }
+}
+
+/// Spawn the scheduler thread that fires due reminders
+/// Overdue reminders (e.g. due while the app was closed) fire on the first tick.
+/// The earliest due time is cached until the file changes, so the file is
+/// only locked and rewritten when a reminder is actually due.
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    let Some(path) = get_reminders_file_path() else {
+        return;
+    };
+    thread::spawn(move || {
+        let mut cached: Option<((SystemTime, u64), Option<u64>)> = None;
+        loop {
+            let next_due = match (file_stamp(&path), &cached) {
+                (Some(stamp), Some((seen, due))) if *seen == stamp => *due,
+                (Some(stamp), _) => {
+                    let due = earliest_due(&path);
+                    cached = Some((stamp, due));
+                    due
+                }
+                (None, _) => {
+                    cached = None;
+                    None
+                }
+            };
+            let now = now_ms();
+            if next_due.is_some_and(|due| due <= now) {
+                for reminder in take_due_reminders(now) {
+                    fire_reminder(&app, reminder);
+                }
+                cached = None;
+            }
+            thread::sleep(SCHEDULER_INTERVAL);
+        }
+    });
+}
+
+/// Bring the mascot on screen and notify the frontend about a due reminder
+fn fire_reminder(app: &tauri::AppHandle, reminder: Reminder) {
+    log_line!("[Rust] Reminder due: {}", reminder.id);
+    log_content!("[Rust] Reminder {} message: {}", reminder.id, reminder.message);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+    }
+
+    // Walk to the middle of the screen so the reminder is noticed
+    if let Err(e) = movement::emit_move(app, &MoveToRequest::anchor("center")) {
+        log_line!("[Rust] Failed to move mascot for reminder: {}", e);
+    }
+    let _ = app.emit(
+        "reminder-due",
+        ReminderDueEvent {
+            id: reminder.id,
+            message: reminder.message,
+            due_at: reminder.due_at,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(id: &str, due_at: u64) -> Reminder {
+        Reminder {
+            id: id.to_string(),
+            message: "check the deploy".to_string(),
+            due_at,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_get_reminders_file_path() {
+        if let Some(p) = get_reminders_file_path() {
+            assert!(p.ends_with("reminders.json"));
+            assert!(p.to_string_lossy().contains("supiki"));
+        }
+    }
+
+    #[test]
+    fn test_split_due() {
+        let reminders = vec![reminder("a", 100), reminder("b", 200), reminder("c", 300)];
+        let (due, pending) = split_due(reminders, 200);
+        assert_eq!(due.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, "c");
+    }
+
+    #[test]
+    fn test_next_reminder_id_skips_existing() {
+        let existing = vec![reminder("rem-5", 0), reminder("rem-6", 0)];
+        assert_eq!(next_reminder_id(&existing, 5), "rem-7");
+        assert_eq!(next_reminder_id(&existing, 9), "rem-9");
+    }
+
+    #[test]
+    fn test_file_lock_is_exclusive() {
+        let path = std::env::temp_dir().join(format!("supiki-reminders-{}.lock", std::process::id()));
+        let lock = FileLock::acquire(&path).unwrap();
+        assert!(path.exists());
+        assert!(OpenOptions::new().write(true).create_new(true).open(&path).is_err());
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_the_file() {
+        let path = std::env::temp_dir().join(format!("supiki-reminders-{}.json", std::process::id()));
+        fs::write(&path, "old").unwrap();
+        write_atomic(&path, "[]").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[]");
+        assert!(!path.with_extension(format!("json.tmp-{}", std::process::id())).exists());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_unparseable_file_is_not_overwritten() {
+        let path = std::env::temp_dir().join(format!("supiki-reminders-broken-{}.json", std::process::id()));
+        fs::write(&path, "[{\"id\": ").unwrap();
+        assert_eq!(earliest_due(&path), None);
+        let result = update_reminders_at(&path, |reminders| {
+            reminders.push(reminder("rem-1", 1));
+            Ok(())
+        });
+        assert!(result.unwrap_err().contains("Not changing"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[{\"id\": ");
+
+        fs::write(&path, "[]").unwrap();
+        update_reminders_at(&path, |reminders| {
+            reminders.extend([reminder("rem-2", 20), reminder("rem-1", 10)]);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(earliest_due(&path), Some(10));
+        assert!(file_stamp(&path).is_some());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_reminder_delay() {
+        assert_eq!(reminder_delay(Some(1.5), Some(10)), Ok(Duration::from_secs(100)));
+        assert_eq!(reminder_delay(None, None), Ok(Duration::ZERO));
+        assert_eq!(
+            reminder_delay(Some(MAX_DELAY_MINUTES), None),
+            Ok(Duration::from_secs(MAX_DELAY_MINUTES as u64 * 60))
+        );
+        assert!(reminder_delay(Some(-1.0), None).is_err());
+        assert!(reminder_delay(Some(f64::NAN), None).is_err());
+        // Seconds count towards the limit and can't overflow it
+        assert!(reminder_delay(Some(MAX_DELAY_MINUTES), Some(1)).is_err());
+        assert!(reminder_delay(Some(1.0), Some(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_reminder_serialization_round_trip() {
+        let original = reminder("rem-1", 42);
+        let json = serde_json::to_string(&original).unwrap();
+        assert!(json.contains("dueAt"));
+        let parsed: Reminder = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, original);
+    }
+}
@@ -0,0 +1,292 @@
+//! Screenshot consent and capture history
+//!
+//! Before `capture_screenshot` or `wait_for_screen_change` looks at the
+//! screen, the MCP server asks the running app over the bridge. The
+//! `screenshotConsent` setting decides whether the app always allows, asks
+//! every time, or asks once per agent session. Without the app only "always"
+//! lets a capture through.
+//!
+//! Every capture is kept as a small thumbnail in `supiki/screenshots/` with
+//! its time, tool and requesting session, so the user can review and purge
+//! what the agent has seen.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+use crate::approvals::{self, ApprovalDecision};
+use crate::image_pipeline::{self, ImageOptions};
+use crate::settings::{load_settings, update_settings, ScreenshotConsent};
+use crate::state::{self, BackendMode, BACKEND_MODE, CODEX_SESSION_ID, SESSION_ID};
+
+const INDEX_FILE_NAME: &str = "history.jsonl";
+
+/// Longest edge of a history thumbnail
+const THUMBNAIL_SIZE: u32 = 240;
+
+/// Oldest captures beyond this are deleted
+const MAX_HISTORY: usize = 200;
+
+/// Set when the user allowed screenshots for the current session
+static SESSION_GRANTED: AtomicBool = AtomicBool::new(false);
+
+/// The app's answer to a consent request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentReply {
+    pub allowed: bool,
+    /// Agent session that asked, recorded in the history
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// A capture in the history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRecord {
+    pub id: String,
+    /// Unix timestamp in milliseconds
+    pub timestamp: f64,
+    /// Tool that took the screenshot
+    pub tool: String,
+    pub description: Option<String>,
+    pub session_id: Option<String>,
+    /// Size of the capture before it was scaled down
+    pub width: u32,
+    pub height: u32,
+    /// Thumbnail as a data URL (filled in when listing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+/// Get the screenshot history directory
+pub fn get_history_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("screenshots"))
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+fn current_session_id() -> Option<String> {
+    match *BACKEND_MODE.lock().unwrap() {
+        BackendMode::Claude => SESSION_ID.lock().unwrap().clone(),
+        BackendMode::Codex => CODEX_SESSION_ID.lock().unwrap().clone(),
+    }
+}
+
+/// Forget a "for this session" consent (called when the session is cleared)
+pub fn revoke_session_grant() {
+    SESSION_GRANTED.store(false, Ordering::Relaxed);
+}
+
+/// Decide whether the agent may look at the screen (blocks while asking)
+pub fn consent(app: &tauri::AppHandle, description: Option<&str>) -> ConsentReply {
+    let mode = load_settings().screenshot_consent;
+    let allowed = match mode {
+        ScreenshotConsent::Always => true,
+        ScreenshotConsent::Session if SESSION_GRANTED.load(Ordering::Relaxed) => true,
+        ScreenshotConsent::Session | ScreenshotConsent::Ask => {
+            let detail = match description {
+                Some(d) => format!("The agent wants a screenshot (looking for: {}).", d),
+                None => "The agent wants a screenshot.".to_string(),
+            };
+            match approvals::ask(app, "Let Supiki see your screen?", &detail, true) {
+                ApprovalDecision::Deny => false,
+                ApprovalDecision::Allow => {
+                    if mode == ScreenshotConsent::Session {
+                        SESSION_GRANTED.store(true, Ordering::Relaxed);
+                    }
+                    true
+                }
+                ApprovalDecision::AlwaysAllow => {
+                    if let Err(e) = update_settings(|s| s.screenshot_consent = ScreenshotConsent::Always) {
+                        log_line!("[Rust] Failed to remember screenshot consent: {}", e);
+                    }
+                    true
+                }
+            }
+        }
+    };
+    ConsentReply {
+        allowed,
+        session_id: current_session_id(),
+    }
+}
+
+/// Bridge handler for screenshot_consent
+pub fn consent_from_params(app: &tauri::AppHandle, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    let description = params.get("description").and_then(|d| d.as_str());
+    serde_json::to_value(consent(app, description)).map_err(|e| e.to_string())
+}
+
+fn read_index(dir: &std::path::Path) -> Vec<CaptureRecord> {
+    fs::read_to_string(dir.join(INDEX_FILE_NAME))
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn write_index(dir: &std::path::Path, records: &[CaptureRecord]) -> Result<(), String> {
+    let path = dir.join(INDEX_FILE_NAME);
+    let content: String = records
+        .iter()
+        .filter_map(|r| serde_json::to_string(r).ok())
+        .map(|line| line + "\n")
+        .collect();
+    fs::write(&path, content).map_err(|e| format!("Failed to write screenshot history: {}", e))?;
+    state::restrict_to_owner(&path);
+    Ok(())
+}
+
+fn thumbnail_path(dir: &std::path::Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.jpg", id))
+}
+
+/// Keep a thumbnail of a capture in the history
+pub fn record_capture(
+    img: &RgbaImage,
+    tool: &str,
+    description: Option<&str>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let dir = get_history_dir().ok_or("Could not find data directory")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create screenshot history: {}", e))?;
+
+    let options = ImageOptions {
+        max_dimension: THUMBNAIL_SIZE,
+        quality: 70,
+        ..ImageOptions::default()
+    };
+    let encoded = image_pipeline::process(image::DynamicImage::ImageRgba8(img.clone()), &options)?;
+    let timestamp = now_ms();
+    let id = format!("{:015}-{}", timestamp as u64, rand::random::<u16>());
+    let path = thumbnail_path(&dir, &id);
+    fs::write(&path, &encoded.data).map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+    state::restrict_to_owner(&path);
+
+    let record = CaptureRecord {
+        id,
+        timestamp,
+        tool: tool.to_string(),
+        description: description.map(str::to_string),
+        session_id,
+        width: img.width(),
+        height: img.height(),
+        thumbnail: None,
+    };
+    let index = dir.join(INDEX_FILE_NAME);
+    let line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&index)
+        .map_err(|e| format!("Failed to open screenshot history: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write screenshot history: {}", e))?;
+    state::restrict_to_owner(&index);
+
+    let records = read_index(&dir);
+    if records.len() > MAX_HISTORY {
+        let (dropped, kept) = records.split_at(records.len() - MAX_HISTORY);
+        for record in dropped {
+            let _ = fs::remove_file(thumbnail_path(&dir, &record.id));
+        }
+        write_index(&dir, kept)?;
+    }
+    Ok(())
+}
+
+/// All captures, newest first, with their thumbnails
+pub fn history() -> Vec<CaptureRecord> {
+    let Some(dir) = get_history_dir() else {
+        return Vec::new();
+    };
+    let mut records: Vec<CaptureRecord> = read_index(&dir)
+        .into_iter()
+        .filter_map(|mut record| {
+            let bytes = fs::read(thumbnail_path(&dir, &record.id)).ok()?;
+            record.thumbnail = Some(format!("data:image/jpeg;base64,{}", STANDARD.encode(bytes)));
+            Some(record)
+        })
+        .collect();
+    records.reverse();
+    records
+}
+
+/// Delete one capture from the history
+pub fn delete(id: &str) -> Result<(), String> {
+    let dir = get_history_dir().ok_or("Could not find data directory")?;
+    let records = read_index(&dir);
+    if !records.iter().any(|r| r.id == id) {
+        return Err(format!("No screenshot with id '{}'", id));
+    }
+    let _ = fs::remove_file(thumbnail_path(&dir, id));
+    let kept: Vec<CaptureRecord> = records.into_iter().filter(|r| r.id != id).collect();
+    write_index(&dir, &kept)
+}
+
+/// Delete the whole history, returning how many captures were removed
+pub fn purge() -> Result<u32, String> {
+    let Some(dir) = get_history_dir() else {
+        return Ok(0);
+    };
+    let count = read_index(&dir).len() as u32;
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to purge screenshot history: {}", e))?;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_history_dir() {
+        if let Some(dir) = get_history_dir() {
+            assert!(dir.ends_with("screenshots"));
+            assert!(dir.to_string_lossy().contains("supiki"));
+        }
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        let dir = std::env::temp_dir().join(format!("supiki-screenshots-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let record = |id: &str| CaptureRecord {
+            id: id.to_string(),
+            timestamp: 1.0,
+            tool: "capture_screenshot".to_string(),
+            description: Some("the build".to_string()),
+            session_id: None,
+            width: 1920,
+            height: 1080,
+            thumbnail: None,
+        };
+        write_index(&dir, &[record("a"), record("b")]).unwrap();
+        let records = read_index(&dir);
+        assert_eq!(records, [record("a"), record("b")]);
+        // Thumbnails are never written to the index
+        let content = fs::read_to_string(dir.join(INDEX_FILE_NAME)).unwrap();
+        assert!(!content.contains("thumbnail"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_consent_reply_parsing() {
+        let reply: ConsentReply = serde_json::from_str(r#"{"allowed":true}"#).unwrap();
+        assert!(reply.allowed);
+        assert_eq!(reply.session_id, None);
+    }
+}
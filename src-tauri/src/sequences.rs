@@ -0,0 +1,299 @@
+//! Scriptable mascot animation sequences
+//!
+//! The agent choreographs Supiki with a short list of actions (jump, wave,
+//! spin, sleep, look at a point, wait). Sequences are validated here and sent
+//! to the main window as a single timeline event with a start offset for every
+//! step. Named sequences can be stored as JSON files in the app data
+//! directory (`supiki/sequences/<name>.json`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+/// Most actions accepted in one sequence
+pub const MAX_ACTIONS: usize = 32;
+
+/// Longest total sequence duration in milliseconds
+pub const MAX_TOTAL_MS: u32 = 60_000;
+
+/// Longest single wait or sleep in milliseconds
+const MAX_STEP_MS: u32 = 15_000;
+
+const JUMP_MS: u32 = 600;
+const WAVE_MS: u32 = 1200;
+const SPIN_MS: u32 = 800;
+const LOOK_AT_MS: u32 = 500;
+const DEFAULT_SLEEP_MS: u32 = 3000;
+
+/// A single step of a sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SequenceAction {
+    /// Jump in place
+    Jump,
+    /// Wave hello
+    Wave,
+    /// Spin around once
+    Spin,
+    /// Doze off for a while
+    Sleep {
+        #[serde(default)]
+        duration_ms: Option<u32>,
+    },
+    /// Turn to face a screen point (logical pixels)
+    LookAt { x: f64, y: f64 },
+    /// Do nothing for a while
+    Wait { duration_ms: u32 },
+}
+
+impl SequenceAction {
+    /// How long the step takes in milliseconds
+    pub fn duration_ms(&self) -> u32 {
+        match self {
+            SequenceAction::Jump => JUMP_MS,
+            SequenceAction::Wave => WAVE_MS,
+            SequenceAction::Spin => SPIN_MS,
+            SequenceAction::Sleep { duration_ms } => duration_ms.unwrap_or(DEFAULT_SLEEP_MS),
+            SequenceAction::LookAt { .. } => LOOK_AT_MS,
+            SequenceAction::Wait { duration_ms } => *duration_ms,
+        }
+    }
+}
+
+/// A named sequence from the library
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedSequence {
+    #[serde(default)]
+    pub description: String,
+    pub actions: Vec<SequenceAction>,
+}
+
+/// Request to play a sequence
+#[derive(Debug, Clone, Default, Deserialize, schemars::JsonSchema)]
+pub struct PlaySequenceRequest {
+    /// Name of a sequence from the library (see list_sequences)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Actions to play in order, when not using a named sequence
+    #[serde(default)]
+    pub actions: Option<Vec<SequenceAction>>,
+}
+
+/// One step of a timeline, with its offset from the start
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineStep {
+    pub at_ms: u32,
+    pub duration_ms: u32,
+    #[serde(flatten)]
+    pub action: SequenceAction,
+}
+
+/// Payload of the `mascot-sequence` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceEvent {
+    pub name: Option<String>,
+    pub steps: Vec<TimelineStep>,
+    pub total_ms: u32,
+}
+
+/// Get the directory holding named sequences
+pub fn get_sequences_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("sequences"))
+}
+
+/// Load all named sequences (invalid files are skipped)
+pub fn load_library() -> BTreeMap<String, NamedSequence> {
+    let mut library = BTreeMap::new();
+    let Some(dir) = get_sequences_dir() else {
+        return library;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return library;
+    };
+
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(String::from) else {
+            continue;
+        };
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<NamedSequence>(&content).map_err(|e| e.to_string()))
+            .and_then(|sequence| build_timeline(&sequence.actions).map(|_| sequence));
+        match parsed {
+            Ok(sequence) => {
+                library.insert(name, sequence);
+            }
+            Err(e) => log_line!("[Rust] Skipping sequence {:?}: {}", path, e),
+        }
+    }
+    library
+}
+
+/// Validate actions and compute the start offset of every step
+pub fn build_timeline(actions: &[SequenceAction]) -> Result<Vec<TimelineStep>, String> {
+    if actions.is_empty() {
+        return Err("A sequence needs at least one action".to_string());
+    }
+    if actions.len() > MAX_ACTIONS {
+        return Err(format!(
+            "A sequence can have at most {} actions, got {}",
+            MAX_ACTIONS,
+            actions.len()
+        ));
+    }
+
+    let mut at_ms: u32 = 0;
+    let mut steps = Vec::with_capacity(actions.len());
+    for (index, action) in actions.iter().enumerate() {
+        let duration_ms = action.duration_ms();
+        if duration_ms == 0 || duration_ms > MAX_STEP_MS {
+            return Err(format!(
+                "Step {} must last between 1 and {} ms, got {}",
+                index + 1,
+                MAX_STEP_MS,
+                duration_ms
+            ));
+        }
+        if let SequenceAction::LookAt { x, y } = action {
+            if !x.is_finite() || !y.is_finite() {
+                return Err(format!("Step {} has an invalid look_at point", index + 1));
+            }
+        }
+        steps.push(TimelineStep {
+            at_ms,
+            duration_ms,
+            action: action.clone(),
+        });
+        at_ms += duration_ms;
+    }
+
+    if at_ms > MAX_TOTAL_MS {
+        return Err(format!(
+            "A sequence can last at most {} ms, got {}",
+            MAX_TOTAL_MS, at_ms
+        ));
+    }
+    Ok(steps)
+}
+
+impl PlaySequenceRequest {
+    /// Parse the input of a play_sequence tool_use from the CLI stream
+    pub fn from_tool_input(input: &serde_json::Value) -> Result<Self, String> {
+        serde_json::from_value(input.clone()).map_err(|e| format!("Invalid play_sequence input: {}", e))
+    }
+
+    /// Resolve the request (looking up named sequences) into a timeline event
+    pub fn to_event(&self) -> Result<SequenceEvent, String> {
+        let actions = match (&self.name, &self.actions) {
+            (Some(_), Some(_)) => return Err("Pass either a name or actions, not both".to_string()),
+            (None, None) => return Err("Pass a sequence name or a list of actions".to_string()),
+            (None, Some(actions)) => actions.clone(),
+            (Some(name), None) => {
+                let mut library = load_library();
+                library
+                    .remove(name)
+                    .map(|sequence| sequence.actions)
+                    .ok_or_else(|| {
+                        let names: Vec<_> = library.keys().cloned().collect();
+                        format!(
+                            "No sequence named '{}'. Available: {}",
+                            name,
+                            if names.is_empty() { "none".to_string() } else { names.join(", ") }
+                        )
+                    })?
+            }
+        };
+
+        let steps = build_timeline(&actions)?;
+        let total_ms = steps.last().map(|s| s.at_ms + s.duration_ms).unwrap_or(0);
+        Ok(SequenceEvent {
+            name: self.name.clone(),
+            steps,
+            total_ms,
+        })
+    }
+}
+
+/// Play a sequence on the main mascot, replacing any sequence in progress
+pub fn emit_sequence(app: &tauri::AppHandle, request: &PlaySequenceRequest) -> Result<SequenceEvent, String> {
+    let event = request.to_event()?;
+    app.emit("mascot-sequence", &event).map_err(|e| e.to_string())?;
+    Ok(event)
+}
+
+/// Stop the sequence in progress, if any
+pub fn emit_cancel(app: &tauri::AppHandle) -> Result<(), String> {
+    app.emit("mascot-sequence-cancel", ()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(json: serde_json::Value) -> Vec<SequenceAction> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_build_timeline_offsets() {
+        let steps = build_timeline(&actions(serde_json::json!([
+            { "action": "jump" },
+            { "action": "wait", "duration_ms": 250 },
+            { "action": "look_at", "x": 10, "y": 20 },
+            { "action": "sleep" }
+        ])))
+        .unwrap();
+        let offsets: Vec<u32> = steps.iter().map(|s| s.at_ms).collect();
+        assert_eq!(offsets, [0, JUMP_MS, JUMP_MS + 250, JUMP_MS + 250 + LOOK_AT_MS]);
+        assert_eq!(steps[3].duration_ms, DEFAULT_SLEEP_MS);
+    }
+
+    #[test]
+    fn test_build_timeline_limits() {
+        assert!(build_timeline(&[]).is_err());
+        assert!(build_timeline(&vec![SequenceAction::Jump; MAX_ACTIONS + 1]).is_err());
+        assert!(build_timeline(&[SequenceAction::Wait { duration_ms: 0 }]).is_err());
+        assert!(build_timeline(&vec![SequenceAction::Wait { duration_ms: MAX_STEP_MS }; 5]).is_err());
+    }
+
+    #[test]
+    fn test_unknown_action_rejected() {
+        let parsed: Result<Vec<SequenceAction>, _> =
+            serde_json::from_value(serde_json::json!([{ "action": "moonwalk" }]));
+        assert!(parsed.is_err());
+    }
+
+    #[test]
+    fn test_request_requires_exactly_one_source() {
+        assert!(PlaySequenceRequest::default().to_event().is_err());
+        let both = PlaySequenceRequest {
+            name: Some("greet".to_string()),
+            actions: Some(vec![SequenceAction::Wave]),
+        };
+        assert!(both.to_event().is_err());
+    }
+
+    #[test]
+    fn test_timeline_step_serialization() {
+        let event = PlaySequenceRequest {
+            name: None,
+            actions: Some(vec![SequenceAction::LookAt { x: 1.0, y: 2.0 }]),
+        }
+        .to_event()
+        .unwrap();
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["totalMs"], LOOK_AT_MS);
+        assert_eq!(json["steps"][0]["action"], "look_at");
+        assert_eq!(json["steps"][0]["atMs"], 0);
+        assert_eq!(json["steps"][0]["x"], 1.0);
+    }
+}
@@ -0,0 +1,236 @@
+//! User settings shared between the GUI and the MCP server
+//!
+//! Settings are persisted as JSON in the app data directory. The MCP server
+//! runs in a separate process, so it re-reads the file on every tool call
+//! instead of caching it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// How redacted screenshot regions are hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionStyle {
+    #[default]
+    Blackout,
+    Blur,
+}
+
+/// Codex `--sandbox` mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "kebab-case")]
+pub enum CodexSandbox {
+    ReadOnly,
+    WorkspaceWrite,
+    DangerFullAccess,
+}
+
+impl CodexSandbox {
+    /// The value passed to `--sandbox`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CodexSandbox::ReadOnly => "read-only",
+            CodexSandbox::WorkspaceWrite => "workspace-write",
+            CodexSandbox::DangerFullAccess => "danger-full-access",
+        }
+    }
+}
+
+/// What happens when the working directory is set to a root, home or system directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum SensitiveCwdAction {
+    Refuse,
+    #[default]
+    Confirm,
+    Allow,
+}
+
+/// When the agent may take screenshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotConsent {
+    Always,
+    /// Ask before every screenshot
+    Ask,
+    /// Ask once per agent session
+    #[default]
+    Session,
+}
+
+/// Persisted app settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// When set, capture_screenshot politely refuses
+    pub screenshots_paused: bool,
+    /// App names or window titles (case-insensitive substrings) hidden from screenshots
+    pub screenshot_deny_list: Vec<String>,
+    /// Hide Supiki's own windows (mascot, chat) from screenshots
+    pub redact_supiki_windows: bool,
+    pub redaction_style: RedactionStyle,
+    /// Longest edge of screenshots sent to the agent
+    pub screenshot_max_dimension: u32,
+    /// 1-99 encodes lossy JPEG, 100 encodes lossless WebP
+    pub screenshot_quality: u8,
+    pub screenshot_grayscale: bool,
+    /// Target size of an encoded screenshot in bytes
+    pub screenshot_max_bytes: Option<u32>,
+    pub screenshot_consent: ScreenshotConsent,
+    /// URL schemes the agent may open without asking
+    pub open_allowed_schemes: Vec<String>,
+    /// Domains (and their subdomains) the agent may open without asking
+    pub open_allowed_domains: Vec<String>,
+    /// Directories whose files the agent may open without asking
+    pub open_allowed_path_prefixes: Vec<String>,
+    /// Speak the agent's answers with the TTS engine
+    pub read_answers_aloud: bool,
+    /// TTS engine command; text goes to stdin, `{voice}` and `{output}` are substituted
+    pub tts_command: String,
+    /// TTS voice per UI language code
+    pub tts_voices: BTreeMap<String, String>,
+    /// Codex sandbox mode; unset follows the permission policy
+    pub codex_sandbox: Option<CodexSandbox>,
+    /// Let commands in the workspace-write sandbox use the network
+    pub codex_network_access: bool,
+    /// Directories Codex may write to besides the workspace (workspace-write only)
+    pub codex_writable_roots: Vec<String>,
+    /// Extra regular expressions masked in the backend log
+    pub log_redact_patterns: Vec<String>,
+    pub sensitive_cwd_action: SensitiveCwdAction,
+    /// Only skip permission checks in a git repository with a clean tree
+    pub require_clean_git_repo: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            screenshots_paused: false,
+            screenshot_deny_list: [
+                "1Password",
+                "Bitwarden",
+                "KeePass",
+                "LastPass",
+                "Keychain Access",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            redact_supiki_windows: true,
+            redaction_style: RedactionStyle::Blackout,
+            screenshot_max_dimension: 2560,
+            screenshot_quality: 100,
+            screenshot_grayscale: false,
+            screenshot_max_bytes: Some(1_000_000),
+            screenshot_consent: ScreenshotConsent::Session,
+            open_allowed_schemes: vec!["https".to_string()],
+            open_allowed_domains: [
+                "docs.rs",
+                "doc.rust-lang.org",
+                "developer.mozilla.org",
+                "github.com",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            open_allowed_path_prefixes: Vec::new(),
+            read_answers_aloud: false,
+            tts_command: "espeak-ng -v {voice} -w {output} --stdin".to_string(),
+            tts_voices: [
+                ("en", "en-us"),
+                ("ko", "ko"),
+                ("ja", "ja"),
+                ("zh", "cmn"),
+                ("es", "es"),
+                ("fr", "fr-fr"),
+                ("de", "de"),
+            ]
+            .into_iter()
+            .map(|(language, voice)| (language.to_string(), voice.to_string()))
+            .collect(),
+            codex_sandbox: None,
+            codex_network_access: false,
+            codex_writable_roots: Vec::new(),
+            log_redact_patterns: Vec::new(),
+            sensitive_cwd_action: SensitiveCwdAction::Confirm,
+            require_clean_git_repo: false,
+        }
+    }
+}
+
+/// Get the settings file path for persistence
+pub fn get_settings_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("settings.json"))
+}
+
+/// Load settings from disk (defaults if missing or unreadable)
+pub fn load_settings() -> AppSettings {
+    let Some(path) = get_settings_file_path() else {
+        return AppSettings::default();
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            log_line!("[Rust] Failed to parse settings file: {}", e);
+            AppSettings::default()
+        }),
+        Err(_) => AppSettings::default(),
+    }
+}
+
+/// Save settings to disk
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let path = get_settings_file_path().ok_or("Could not find data directory")?;
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+/// Load, modify and save settings in one step
+pub fn update_settings(f: impl FnOnce(&mut AppSettings)) -> Result<AppSettings, String> {
+    let mut settings = load_settings();
+    f(&mut settings);
+    save_settings(&settings)?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_settings_file_path() {
+        if let Some(p) = get_settings_file_path() {
+            assert!(p.ends_with("settings.json"));
+            assert!(p.to_string_lossy().contains("supiki"));
+        }
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: AppSettings = serde_json::from_str(r#"{"screenshotsPaused":true}"#).unwrap();
+        assert!(settings.screenshots_paused);
+        assert!(settings.redact_supiki_windows);
+        assert_eq!(settings.redaction_style, RedactionStyle::Blackout);
+        assert!(!settings.screenshot_deny_list.is_empty());
+        assert_eq!(settings.screenshot_max_dimension, 2560);
+    }
+
+    #[test]
+    fn test_redaction_style_serialization() {
+        let json = serde_json::to_string(&RedactionStyle::Blur).unwrap();
+        assert_eq!(json, "\"blur\"");
+    }
+
+    #[test]
+    fn test_codex_sandbox_serialization() {
+        let json = serde_json::to_string(&CodexSandbox::DangerFullAccess).unwrap();
+        assert_eq!(json, "\"danger-full-access\"");
+        assert_eq!(json.trim_matches('"'), CodexSandbox::DangerFullAccess.as_str());
+    }
+}
@@ -1,8 +1,13 @@
 //! Global application state and session persistence
 
-use std::fs;
-use std::path::PathBuf;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
 
 /// Backend mode enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -21,6 +26,9 @@ pub static SESSION_ID: Mutex<Option<String>> = Mutex::new(None);
 /// Current Codex session/thread ID (separate from Claude session)
 pub static CODEX_SESSION_ID: Mutex<Option<String>> = Mutex::new(None);
 
+/// UI language code last sent by the frontend (used for the TTS voice)
+pub static LANGUAGE: Mutex<Option<String>> = Mutex::new(None);
+
 /// Dev mode flag (Claude Code features enabled)
 pub static DEV_MODE: Mutex<bool> = Mutex::new(false);
 
@@ -36,6 +44,42 @@ pub static RECENT_CWDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
 /// Maximum number of recent cwds to store
 pub const MAX_RECENT_CWDS: usize = 5;
 
+/// Snapshot of the mascot's visible state, shared with the MCP server via disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MascotSnapshot {
+    pub emotion: String,
+    pub x: i32,
+    pub y: i32,
+    /// Unix timestamp in milliseconds of the last change
+    pub updated_at: u64,
+}
+
+/// Latest mascot snapshot and whether it still needs to be written to disk
+pub static MASCOT_SNAPSHOT: Mutex<(MascotSnapshot, bool)> = Mutex::new((
+    MascotSnapshot {
+        emotion: String::new(),
+        x: 0,
+        y: 0,
+        updated_at: 0,
+    },
+    false,
+));
+
+/// How often a changed mascot snapshot is flushed to disk
+const MASCOT_SNAPSHOT_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Summary of a saved chat session, synced from the frontend's history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSessionSummary {
+    pub id: String,
+    pub title: String,
+    /// Unix timestamp in milliseconds of the last message
+    pub updated_at: f64,
+    pub message_count: u32,
+}
+
 /// Get the session file path for persistence
 pub fn get_session_file_path() -> Option<PathBuf> {
     dirs::data_local_dir().map(|d| d.join("supiki").join("session.txt"))
@@ -48,7 +92,7 @@ pub fn save_session_to_disk(session_id: &str) {
             let _ = fs::create_dir_all(parent);
         }
         let _ = fs::write(&path, session_id);
-        println!("[Rust] Session saved to {:?}", path);
+        log_line!("[Rust] Session saved to {:?}", path);
     }
 }
 
@@ -69,7 +113,7 @@ pub fn save_cwd_to_disk(cwd: &str) {
             let _ = fs::create_dir_all(parent);
         }
         let _ = fs::write(&path, cwd);
-        println!("[Rust] CWD saved to {:?}", path);
+        log_line!("[Rust] CWD saved to {:?}", path);
     }
 }
 
@@ -81,10 +125,10 @@ pub fn load_cwd_from_disk() -> Option<String> {
             let cwd = cwd.trim().to_string();
             // Validate that the directory still exists
             if std::path::Path::new(&cwd).is_dir() {
-                println!("[Rust] CWD loaded from {:?}: {}", path, cwd);
+                log_line!("[Rust] CWD loaded from {:?}: {}", path, cwd);
                 Some(cwd)
             } else {
-                println!("[Rust] Saved CWD no longer exists: {}", cwd);
+                log_line!("[Rust] Saved CWD no longer exists: {}", cwd);
                 None
             }
         }
@@ -124,6 +168,145 @@ pub fn load_recent_cwds_from_disk() -> Vec<String> {
     }
 }
 
+/// Write a secret (like a token) without it ever being readable by other users
+/// The content goes into a new owner-only file that then replaces `path`,
+/// the same way temp files are created.
+pub fn write_secret(path: &Path, content: &str) -> std::io::Result<()> {
+    let temp = path.with_extension(format!("tmp-{}", std::process::id()));
+    let _ = fs::remove_file(&temp);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let result = options
+        .open(&temp)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .and_then(|_| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Compare a provided secret with the expected one in constant time
+pub fn secrets_match(provided: &str, expected: &str) -> bool {
+    let (provided, expected) = (provided.as_bytes(), expected.as_bytes());
+    provided.len() == expected.len()
+        && provided
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Make a file readable by the current user only (used for secrets like tokens)
+#[cfg(unix)]
+pub fn restrict_to_owner(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+}
+
+/// Make a file readable by the current user only (used for secrets like tokens)
+#[cfg(not(unix))]
+pub fn restrict_to_owner(_path: &Path) {
+    // The app data directory is already per-user on Windows
+}
+
+/// Get the mascot state file path (read by the MCP server)
+pub fn get_mascot_state_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("mascot-state.json"))
+}
+
+/// Load the last mascot snapshot written by the GUI process
+pub fn load_mascot_snapshot() -> Option<MascotSnapshot> {
+    let path = get_mascot_state_file_path()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Record the mascot's current emotion (flushed to disk by the snapshot writer)
+pub fn update_mascot_emotion(emotion: &str) {
+    let mut guard = MASCOT_SNAPSHOT.lock().unwrap();
+    if guard.0.emotion != emotion {
+        guard.0.emotion = emotion.to_string();
+        guard.0.updated_at = unix_time_ms();
+        guard.1 = true;
+    }
+}
+
+/// Record the mascot window's current position (flushed to disk by the snapshot writer)
+pub fn update_mascot_position(x: i32, y: i32) {
+    let mut guard = MASCOT_SNAPSHOT.lock().unwrap();
+    if (guard.0.x, guard.0.y) != (x, y) {
+        guard.0.x = x;
+        guard.0.y = y;
+        guard.0.updated_at = unix_time_ms();
+        guard.1 = true;
+    }
+}
+
+/// Spawn a thread that periodically writes the mascot snapshot to disk when it changed
+/// Window moves fire many times per second while walking, so writes are batched here
+pub fn spawn_mascot_snapshot_writer() {
+    thread::spawn(|| loop {
+        thread::sleep(MASCOT_SNAPSHOT_FLUSH_INTERVAL);
+
+        let snapshot = {
+            let mut guard = MASCOT_SNAPSHOT.lock().unwrap();
+            if !guard.1 {
+                continue;
+            }
+            guard.1 = false;
+            guard.0.clone()
+        };
+
+        if let Some(path) = get_mascot_state_file_path() {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(content) = serde_json::to_string_pretty(&snapshot) {
+                let _ = fs::write(&path, content);
+            }
+        }
+    });
+}
+
+/// Get the chat sessions index file path (read by the MCP server)
+pub fn get_chat_sessions_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("chat-sessions.json"))
+}
+
+/// Save the chat sessions index to disk
+pub fn save_chat_sessions_to_disk(sessions: &[ChatSessionSummary]) {
+    if let Some(path) = get_chat_sessions_file_path() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(sessions) {
+            let _ = fs::write(&path, content);
+        }
+    }
+}
+
+/// Load the chat sessions index from disk
+pub fn load_chat_sessions_from_disk() -> Vec<ChatSessionSummary> {
+    get_chat_sessions_file_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Current time as a Unix timestamp in milliseconds
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 /// Save Codex session ID to disk
 pub fn save_codex_session_to_disk(session_id: &str) {
     if let Some(path) = get_codex_session_file_path() {
@@ -131,7 +314,7 @@ pub fn save_codex_session_to_disk(session_id: &str) {
             let _ = fs::create_dir_all(parent);
         }
         let _ = fs::write(&path, session_id);
-        println!("[Rust] Codex session saved to {:?}", path);
+        log_line!("[Rust] Codex session saved to {:?}", path);
     }
 }
 
@@ -252,4 +435,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mascot_snapshot_marks_dirty_only_on_change() {
+        update_mascot_position(10, 20);
+        MASCOT_SNAPSHOT.lock().unwrap().1 = false;
+
+        update_mascot_position(10, 20);
+        assert!(!MASCOT_SNAPSHOT.lock().unwrap().1);
+
+        update_mascot_emotion("happy");
+        let guard = MASCOT_SNAPSHOT.lock().unwrap();
+        assert!(guard.1);
+        assert_eq!(guard.0.emotion, "happy");
+        assert_eq!((guard.0.x, guard.0.y), (10, 20));
+    }
+
+    #[test]
+    fn test_secrets_match() {
+        assert!(secrets_match("abc123", "abc123"));
+        assert!(!secrets_match("abc124", "abc123"));
+        assert!(!secrets_match("abc", "abc123"));
+        assert!(!secrets_match("", "abc123"));
+    }
+
+    #[test]
+    fn test_write_secret() {
+        let dir = std::env::temp_dir().join(format!("supiki-state-secret-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("token");
+        write_secret(&path, "first").unwrap();
+        write_secret(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_chat_session_summary_uses_camel_case() {
+        let summary = ChatSessionSummary {
+            id: "session-1".to_string(),
+            title: "New Chat".to_string(),
+            updated_at: 1.0,
+            message_count: 2,
+        };
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(json.contains("updatedAt"));
+        assert!(json.contains("messageCount"));
+    }
 }
@@ -0,0 +1,212 @@
+//! Private temporary files
+//!
+//! Files the agents or the system viewer need on disk (the Claude MCP config,
+//! Codex image attachments, images opened in the viewer) are created in
+//! `supiki/tmp/` with random names and user-only permissions. Each file is
+//! registered under an owner: a turn's files are removed when its process
+//! exits, the rest when the app exits. Files left behind by a crashed or
+//! killed instance are swept at startup.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Files untouched for this long are treated as left behind by another run
+const ORPHAN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Who a temp file belongs to, deciding when it is removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempOwner {
+    /// One agent turn, released when its process exits
+    Turn(u64),
+    /// Images opened in the system viewer, kept until the app exits
+    Viewer,
+}
+
+static NEXT_TURN: AtomicU64 = AtomicU64::new(1);
+
+/// Files created by this process
+static TEMP_FILES: Mutex<Vec<(TempOwner, PathBuf)>> = Mutex::new(Vec::new());
+
+/// Get the app's temp directory
+pub fn get_temp_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("tmp"))
+}
+
+/// A new owner for the files of one agent turn
+pub fn new_turn() -> TempOwner {
+    TempOwner::Turn(NEXT_TURN.fetch_add(1, Ordering::Relaxed))
+}
+
+fn ensure_dir(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(dir, fs::Permissions::from_mode(0o700));
+    }
+    Ok(())
+}
+
+/// Create a new file that only the current user can read
+fn create_in(dir: &Path, owner: TempOwner, label: &str, extension: &str, data: &[u8]) -> Result<PathBuf, String> {
+    ensure_dir(dir)?;
+    let path = dir.join(format!("{}-{:016x}.{}", label, rand::random::<u64>(), extension));
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    TEMP_FILES.lock().unwrap().push((owner, path.clone()));
+
+    if let Err(e) = file.write_all(data) {
+        drop(file);
+        remove(|_| false, Some(&path));
+        return Err(format!("Failed to write temp file: {}", e));
+    }
+    Ok(path)
+}
+
+/// Write `data` to a new private temp file, e.g. `create(owner, "mcp-config", "json", ..)`
+pub fn create(owner: TempOwner, label: &str, extension: &str, data: &[u8]) -> Result<PathBuf, String> {
+    let dir = get_temp_dir().ok_or("Could not find data directory")?;
+    create_in(&dir, owner, label, extension, data)
+}
+
+/// Delete the registered files matching `owned`, plus `path` if given
+fn remove(owned: impl Fn(TempOwner) -> bool, path: Option<&Path>) -> usize {
+    let removed: Vec<PathBuf> = {
+        let mut files = TEMP_FILES.lock().unwrap();
+        let (removed, kept) = files
+            .drain(..)
+            .partition(|(owner, p)| owned(*owner) || Some(p.as_path()) == path);
+        *files = kept;
+        removed.into_iter().map(|(_, p)| p).collect()
+    };
+    for path in &removed {
+        let _ = fs::remove_file(path);
+    }
+    removed.len()
+}
+
+/// Delete the files of an owner (called when a turn ends)
+pub fn release(owner: TempOwner) {
+    let count = remove(|o| o == owner, None);
+    if count > 0 {
+        log_line!("[Rust] Removed {} temp file(s) of {:?}", count, owner);
+    }
+}
+
+/// Delete every file this process created (called on app exit)
+pub fn release_all() {
+    let count = remove(|_| true, None);
+    if count > 0 {
+        log_line!("[Rust] Removed {} temp file(s) on exit", count);
+    }
+}
+
+/// Delete stale files in `dir` that this process did not create
+fn sweep_in(dir: &Path, max_age: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let registered: Vec<PathBuf> = TEMP_FILES.lock().unwrap().iter().map(|(_, p)| p.clone()).collect();
+    let now = SystemTime::now();
+    entries
+        .flatten()
+        .filter(|entry| !registered.contains(&entry.path()))
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .map(|modified| now.duration_since(modified).unwrap_or_default() >= max_age)
+                .unwrap_or(false)
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// Remove files left behind by earlier runs (call once at startup)
+pub fn sweep_orphans() {
+    let Some(dir) = get_temp_dir() else {
+        return;
+    };
+    let count = sweep_in(&dir, ORPHAN_AGE);
+    if count > 0 {
+        log_line!("[Rust] Swept {} orphaned temp file(s)", count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("supiki-tempfiles-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_get_temp_dir() {
+        if let Some(dir) = get_temp_dir() {
+            assert!(dir.ends_with("tmp"));
+            assert!(dir.to_string_lossy().contains("supiki"));
+        }
+    }
+
+    #[test]
+    fn test_create_is_unique_and_private() {
+        let dir = test_dir("create");
+        let owner = new_turn();
+        let a = create_in(&dir, owner, "image", "png", b"a").unwrap();
+        let b = create_in(&dir, owner, "image", "png", b"b").unwrap();
+        assert_ne!(a, b);
+        assert_eq!(fs::read(&a).unwrap(), b"a");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&a).unwrap().permissions().mode() & 0o777, 0o600);
+            assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+        release(owner);
+        assert!(!a.exists() && !b.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_release_only_touches_the_owner() {
+        let dir = test_dir("owners");
+        let (first, second) = (new_turn(), new_turn());
+        let a = create_in(&dir, first, "mcp-config", "json", b"{}").unwrap();
+        let b = create_in(&dir, second, "mcp-config", "json", b"{}").unwrap();
+        release(first);
+        assert!(!a.exists());
+        assert!(b.exists());
+        release(second);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sweep_skips_own_files() {
+        let dir = test_dir("sweep");
+        let owner = new_turn();
+        let own = create_in(&dir, owner, "image", "png", b"x").unwrap();
+        let orphan = dir.join("image-leftover.png");
+        fs::write(&orphan, b"x").unwrap();
+
+        assert_eq!(sweep_in(&dir, ORPHAN_AGE), 0);
+        assert_eq!(sweep_in(&dir, Duration::ZERO), 1);
+        assert!(own.exists());
+        assert!(!orphan.exists());
+        release(owner);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,381 @@
+//! Offline text-to-speech through a local engine command
+//!
+//! Text is piped on stdin to the command configured in the settings
+//! (espeak-ng by default, piper works too) which writes a WAV file. Generated
+//! audio is cached per utterance in `supiki/tts-cache`. Longer text is split
+//! into sentence chunks that are synthesized in order, and each chunk is sent
+//! to the main window as a `tts-audio` event as soon as it is ready.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::Emitter;
+
+use crate::settings::{load_settings, AppSettings};
+use crate::state::LANGUAGE;
+
+/// Longest text accepted by the speak tool, in characters
+pub const MAX_SPEAK_CHARS: usize = 1000;
+
+/// Longest answer read aloud; the rest is skipped
+const MAX_ANSWER_CHARS: usize = 2000;
+
+/// Target length of a synthesized chunk, in characters
+const CHUNK_CHARS: usize = 200;
+
+/// Most cached audio files kept on disk
+const MAX_CACHE_FILES: usize = 200;
+
+/// Bumped on every new utterance so stale synthesis threads stop early
+static GENERATION: AtomicU32 = AtomicU32::new(0);
+
+/// Request to speak text aloud
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct SpeakRequest {
+    /// What to say (at most 1000 characters)
+    pub text: String,
+}
+
+/// Payload of the `tts-audio` event, one per synthesized chunk
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsAudioEvent {
+    /// Utterance the chunk belongs to; a new utterance replaces the old one
+    pub utterance: u32,
+    pub index: u32,
+    pub is_last: bool,
+    /// WAV audio as a data URL
+    pub audio: String,
+}
+
+impl SpeakRequest {
+    /// Parse the input of a speak tool_use from the CLI stream
+    pub fn from_tool_input(input: &serde_json::Value) -> Result<Self, String> {
+        serde_json::from_value(input.clone()).map_err(|e| format!("Invalid speak input: {}", e))
+    }
+
+    /// Validate the text and return it trimmed
+    pub fn validate(&self) -> Result<&str, String> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return Err("Text must not be empty".to_string());
+        }
+        let chars = text.chars().count();
+        if chars > MAX_SPEAK_CHARS {
+            return Err(format!(
+                "Text is {} characters; keep it under {}",
+                chars, MAX_SPEAK_CHARS
+            ));
+        }
+        Ok(text)
+    }
+}
+
+/// Get the audio cache directory
+pub fn get_cache_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("supiki").join("tts-cache"))
+}
+
+/// Voice for a UI language code, falling back to English
+pub fn voice_for(settings: &AppSettings, language: &str) -> String {
+    settings
+        .tts_voices
+        .get(language)
+        .or_else(|| settings.tts_voices.get("en"))
+        .cloned()
+        .unwrap_or_else(|| "en".to_string())
+}
+
+/// Strip markdown that sounds bad when read aloud (code blocks, headings, emphasis)
+pub fn speakable_text(markdown: &str) -> String {
+    let mut text = String::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        let line = line.trim_start_matches(|c: char| c == '#' || c == '>' || c.is_whitespace());
+        let line = line.strip_prefix("- ").unwrap_or(line);
+        text.push_str(&line.replace(['*', '`'], ""));
+        text.push('\n');
+    }
+    text.trim().to_string()
+}
+
+/// Split text into sentence chunks of roughly `CHUNK_CHARS` characters
+pub fn split_chunks(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        current.push(c);
+        if matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '\n') {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+
+    let mut chunks: Vec<String> = Vec::new();
+    for sentence in sentences.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        match chunks.last_mut() {
+            Some(last) if last.chars().count() + sentence.chars().count() < CHUNK_CHARS => {
+                last.push(' ');
+                last.push_str(sentence);
+            }
+            _ => chunks.push(sentence.to_string()),
+        }
+    }
+    chunks
+}
+
+/// Cache key for an utterance (engine command, voice and text)
+/// SHA-256 keeps the key stable across Rust releases, so the cache stays valid.
+fn cache_key(command: &str, voice: &str, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [command, voice, text] {
+        // Length prefixes keep ("a b", "c") and ("a", "b c") apart
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Split a command line into words
+/// Single or double quotes group words with spaces (e.g. `"C:\Program Files\piper\piper.exe"`);
+/// backslashes are kept as they are, so Windows paths need no escaping.
+fn split_command(template: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for c in template.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("Unclosed quote in TTS command: {}", template));
+    }
+    words.extend(current);
+    Ok(words)
+}
+
+/// Build the engine command, substituting `{voice}` and `{output}`
+/// Placeholders are filled in after splitting, so values with spaces stay one argument.
+fn engine_command(template: &str, voice: &str, output: &Path) -> Result<Command, String> {
+    let mut parts = split_command(template)?.into_iter().map(|part| {
+        part.replace("{voice}", voice)
+            .replace("{output}", &output.to_string_lossy())
+    });
+    let program = parts.next().ok_or("No TTS command configured")?;
+    let mut command = Command::new(program);
+    command.args(parts);
+
+    // On Windows, hide the terminal window
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    Ok(command)
+}
+
+/// Synthesize one chunk, reusing the cached audio when present
+pub fn synthesize(settings: &AppSettings, voice: &str, text: &str) -> Result<Vec<u8>, String> {
+    let dir = get_cache_dir().ok_or("Could not find data directory")?;
+    let path = dir.join(format!("{}.wav", cache_key(&settings.tts_command, voice, text)));
+    if let Ok(audio) = fs::read(&path) {
+        return Ok(audio);
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create TTS cache: {}", e))?;
+
+    let mut child = engine_command(&settings.tts_command, voice, &path)?
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start TTS engine ({}): {}", settings.tts_command, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to send text to TTS engine: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("TTS engine failed: {}", e))?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&path);
+        return Err(format!(
+            "TTS engine failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let audio = fs::read(&path).map_err(|e| format!("TTS engine wrote no audio: {}", e))?;
+    prune_cache(&dir);
+    Ok(audio)
+}
+
+/// Drop the oldest cached files beyond `MAX_CACHE_FILES`
+fn prune_cache(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    if files.len() <= MAX_CACHE_FILES {
+        return;
+    }
+    files.sort();
+    for (_, path) in files.iter().take(files.len() - MAX_CACHE_FILES) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Speak text on the main mascot in the selected language, replacing any
+/// utterance in progress. Synthesis runs on a background thread.
+pub fn speak(app: &tauri::AppHandle, text: &str) {
+    let chunks = split_chunks(text);
+    if chunks.is_empty() {
+        return;
+    }
+    let utterance = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let settings = load_settings();
+    let language = LANGUAGE.lock().unwrap().clone().unwrap_or_else(|| "en".to_string());
+    let voice = voice_for(&settings, &language);
+    let app = app.clone();
+
+    thread::spawn(move || {
+        let count = chunks.len();
+        for (index, chunk) in chunks.iter().enumerate() {
+            if GENERATION.load(Ordering::SeqCst) != utterance {
+                return;
+            }
+            let audio = match synthesize(&settings, &voice, chunk) {
+                Ok(audio) => audio,
+                Err(e) => {
+                    log_line!("[Rust] {}", e);
+                    return;
+                }
+            };
+            if GENERATION.load(Ordering::SeqCst) != utterance {
+                return;
+            }
+            let event = TtsAudioEvent {
+                utterance,
+                index: index as u32,
+                is_last: index + 1 == count,
+                audio: format!("data:audio/wav;base64,{}", STANDARD.encode(audio)),
+            };
+            let _ = app.emit("tts-audio", &event);
+        }
+    });
+}
+
+/// Read an agent answer aloud when the user enabled it
+pub fn read_answer(app: &tauri::AppHandle, answer: &str) {
+    if !load_settings().read_answers_aloud {
+        return;
+    }
+    let text: String = speakable_text(answer).chars().take(MAX_ANSWER_CHARS).collect();
+    speak(app, &text);
+}
+
+/// Stop the utterance in progress
+pub fn stop(app: &tauri::AppHandle) {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    let _ = app.emit("tts-stop", ());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_chunks() {
+        assert!(split_chunks("   ").is_empty());
+        assert_eq!(split_chunks("Hi. How are you?"), ["Hi. How are you?"]);
+
+        let long = "This sentence is fairly long and keeps going. ".repeat(10);
+        let chunks = split_chunks(&long);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= CHUNK_CHARS));
+        assert_eq!(split_chunks("こんにちは。元気？"), ["こんにちは。 元気？"]);
+    }
+
+    #[test]
+    fn test_speakable_text() {
+        let markdown = "# Done\nI fixed **two** bugs in `lib.rs`:\n```rust\nfn main() {}\n```\n- first\n> quoted";
+        assert_eq!(
+            speakable_text(markdown),
+            "Done\nI fixed two bugs in lib.rs:\nfirst\nquoted"
+        );
+    }
+
+    #[test]
+    fn test_voice_for_language() {
+        let settings = AppSettings::default();
+        assert_eq!(voice_for(&settings, "ja"), "ja");
+        assert_eq!(voice_for(&settings, "xx"), voice_for(&settings, "en"));
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_inputs() {
+        let key = cache_key("espeak-ng", "en", "hello");
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, cache_key("espeak-ng", "en", "hello"));
+        assert_ne!(cache_key("a b", "c", ""), cache_key("a", "b c", ""));
+        assert_ne!(key, cache_key("espeak-ng", "ja", "hello"));
+        assert_ne!(key, cache_key("piper", "en", "hello"));
+    }
+
+    #[test]
+    fn test_engine_command_placeholders() {
+        let command = engine_command("espeak-ng -v {voice} -w {output} --stdin", "ko", Path::new("/tmp/a.wav")).unwrap();
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(command.get_program(), "espeak-ng");
+        assert_eq!(args, ["-v", "ko", "-w", "/tmp/a.wav", "--stdin"]);
+        assert!(engine_command("  ", "en", Path::new("x")).is_err());
+
+        // Quoted paths and substituted values with spaces stay single arguments
+        let command = engine_command(
+            r#""C:\Program Files\piper\piper.exe" --model '{voice}.onnx' --output_file {output}"#,
+            "my voice",
+            Path::new("/tmp/tts cache/a.wav"),
+        )
+        .unwrap();
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(command.get_program(), r"C:\Program Files\piper\piper.exe");
+        assert_eq!(args, ["--model", "my voice.onnx", "--output_file", "/tmp/tts cache/a.wav"]);
+        assert!(engine_command("piper \"unclosed", "en", Path::new("x")).is_err());
+    }
+
+    #[test]
+    fn test_speak_request_validation() {
+        let ok = SpeakRequest { text: " hi ".to_string() };
+        assert_eq!(ok.validate().unwrap(), "hi");
+        assert!(SpeakRequest { text: " ".to_string() }.validate().is_err());
+        let long = SpeakRequest { text: "a".repeat(MAX_SPEAK_CHARS + 1) };
+        assert!(long.validate().is_err());
+    }
+}
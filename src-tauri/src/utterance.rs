@@ -0,0 +1,163 @@
+//! Short spoken utterances shown above a mascot
+//!
+//! Utterances from the `say` tool are displayed in a timed bubble over the
+//! mascot window and are never added to the chat transcript, so ambient or
+//! proactive remarks stay out of the conversation history.
+
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+/// Longest utterance accepted, in characters
+pub const MAX_UTTERANCE_CHARS: usize = 120;
+
+/// Allowed display duration range in milliseconds
+const DURATION_RANGE_MS: (u32, u32) = (500, 30_000);
+
+/// Window label of the main mascot
+pub const MAIN_WINDOW: &str = "main";
+
+/// Window label prefix of the mini mascots spawned for subagents
+pub const MINI_WINDOW_PREFIX: &str = "mini-mascot-";
+
+/// How an utterance is voiced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SayStyle {
+    #[default]
+    Normal,
+    Whisper,
+    Shout,
+}
+
+/// Request to show an utterance above a mascot
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct SayRequest {
+    /// What to say (at most 120 characters)
+    pub message: String,
+    /// How long to show the bubble in milliseconds (default: based on length)
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+    /// normal, whisper or shout (default: normal)
+    #[serde(default)]
+    pub style: SayStyle,
+    /// Which mascot speaks: "main" (default) or a mini mascot like "mini-mascot-1"
+    #[serde(default)]
+    pub window: Option<String>,
+}
+
+/// Payload of the `mascot-say` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SayEvent {
+    pub window: String,
+    pub message: String,
+    pub duration_ms: u32,
+    pub style: SayStyle,
+}
+
+/// Reading-time based default duration
+fn default_duration_ms(message: &str) -> u32 {
+    let chars = message.chars().count() as u32;
+    (2000 + chars * 60).clamp(DURATION_RANGE_MS.0, DURATION_RANGE_MS.1)
+}
+
+impl SayRequest {
+    /// Parse the input of a say tool_use from the CLI stream
+    pub fn from_tool_input(input: &serde_json::Value) -> Result<Self, String> {
+        serde_json::from_value(input.clone()).map_err(|e| format!("Invalid say input: {}", e))
+    }
+
+    /// Validate the request and build the event payload
+    pub fn to_event(&self) -> Result<SayEvent, String> {
+        let message = self.message.trim();
+        if message.is_empty() {
+            return Err("Message must not be empty".to_string());
+        }
+        let chars = message.chars().count();
+        if chars > MAX_UTTERANCE_CHARS {
+            return Err(format!(
+                "Message is {} characters; keep utterances under {} and use the chat for longer replies",
+                chars, MAX_UTTERANCE_CHARS
+            ));
+        }
+
+        let duration_ms = self.duration_ms.unwrap_or_else(|| default_duration_ms(message));
+        if !(DURATION_RANGE_MS.0..=DURATION_RANGE_MS.1).contains(&duration_ms) {
+            return Err(format!(
+                "Duration must be between {} and {} ms, got {}",
+                DURATION_RANGE_MS.0, DURATION_RANGE_MS.1, duration_ms
+            ));
+        }
+
+        let window = self.window.clone().unwrap_or_else(|| MAIN_WINDOW.to_string());
+        if window != MAIN_WINDOW && !window.starts_with(MINI_WINDOW_PREFIX) {
+            return Err(format!(
+                "Unknown mascot window '{}'. Use \"{}\" or \"{}N\"",
+                window, MAIN_WINDOW, MINI_WINDOW_PREFIX
+            ));
+        }
+
+        Ok(SayEvent {
+            window,
+            message: message.to_string(),
+            duration_ms,
+            style: self.style,
+        })
+    }
+}
+
+/// Show an utterance above the requested mascot window
+pub fn emit_say(app: &tauri::AppHandle, request: &SayRequest) -> Result<SayEvent, String> {
+    let event = request.to_event()?;
+    if app.get_webview_window(&event.window).is_none() {
+        return Err(format!("Mascot window '{}' is not open", event.window));
+    }
+    app.emit("mascot-say", &event).map_err(|e| e.to_string())?;
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: serde_json::Value) -> SayRequest {
+        SayRequest::from_tool_input(&json).unwrap()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let event = request(serde_json::json!({ "message": " Hi! " })).to_event().unwrap();
+        assert_eq!(event.window, MAIN_WINDOW);
+        assert_eq!(event.message, "Hi!");
+        assert_eq!(event.style, SayStyle::Normal);
+        assert_eq!(event.duration_ms, 2180);
+    }
+
+    #[test]
+    fn test_style_and_window() {
+        let req = request(serde_json::json!({
+            "message": "psst",
+            "style": "whisper",
+            "window": "mini-mascot-2",
+            "duration_ms": 1500
+        }));
+        let event = req.to_event().unwrap();
+        assert_eq!(event.style, SayStyle::Whisper);
+        assert_eq!(event.window, "mini-mascot-2");
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["durationMs"], 1500);
+        assert_eq!(json["style"], "whisper");
+    }
+
+    #[test]
+    fn test_rejects_invalid_requests() {
+        assert!(request(serde_json::json!({ "message": "  " })).to_event().is_err());
+        let long = "a".repeat(MAX_UTTERANCE_CHARS + 1);
+        assert!(request(serde_json::json!({ "message": long })).to_event().is_err());
+        assert!(request(serde_json::json!({ "message": "hi", "duration_ms": 10 })).to_event().is_err());
+        assert!(request(serde_json::json!({ "message": "hi", "window": "chat" })).to_event().is_err());
+        assert!(SayRequest::from_tool_input(&serde_json::json!({ "message": "hi", "style": "sing" })).is_err());
+    }
+}
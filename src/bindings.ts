@@ -109,6 +109,7 @@ async openImageInViewer(base64Data: string) : Promise<Result<null, string>> {
 /**
  * Set custom working directory for Claude CLI
  * Also clears the session to start fresh with the new cwd
+ * Root, home and system directories are refused or confirmed first
  */
 async setSidecarCwd(path: string) : Promise<Result<null, string>> {
     try {
@@ -130,12 +131,157 @@ async getSidecarCwd() : Promise<string | null> {
 async getActualCwd() : Promise<string> {
     return await TAURI_INVOKE("get_actual_cwd");
 },
+/**
+ * Get the permission policy in effect for a directory (default: the current cwd)
+ */
+async getEffectivePolicy(cwd: string | null) : Promise<EffectivePolicy> {
+    return await TAURI_INVOKE("get_effective_policy", { cwd });
+},
+/**
+ * Get the sandbox the next Codex turn will run in
+ */
+async getCodexSandbox() : Promise<CodexSandboxStatus> {
+    return await TAURI_INVOKE("get_codex_sandbox");
+},
 /**
  * Get recent working directories
  */
 async getRecentCwds() : Promise<string[]> {
     return await TAURI_INVOKE("get_recent_cwds");
 },
+/**
+ * Sync the chat history index from the frontend
+ * Persisted so the MCP server can expose saved sessions as a resource
+ */
+async syncChatSessions(sessions: ChatSessionSummary[]) : Promise<void> {
+    await TAURI_INVOKE("sync_chat_sessions", { sessions });
+},
+/**
+ * Get the persisted app settings
+ */
+async getAppSettings() : Promise<AppSettings> {
+    return await TAURI_INVOKE("get_app_settings");
+},
+/**
+ * Replace the persisted app settings
+ * The MCP server picks up changes on its next tool call
+ */
+async setAppSettings(settings: AppSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_app_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Record the UI language (selects the TTS voice)
+ */
+async setLanguage(language: string) : Promise<void> {
+    await TAURI_INVOKE("set_language", { language });
+},
+/**
+ * Stop reading aloud
+ */
+async stopSpeaking() : Promise<void> {
+    await TAURI_INVOKE("stop_speaking");
+},
+/**
+ * Get a pending approval for the approval window
+ */
+async getApprovalRequest(id: string) : Promise<ApprovalRequest | null> {
+    return await TAURI_INVOKE("get_approval_request", { id });
+},
+/**
+ * Approve or deny a pending agent action
+ */
+async respondApproval(id: string, decision: ApprovalDecision) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("respond_approval", { id, decision }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Query the audit log of agent activity (oldest first)
+ */
+async queryAuditLog(query: AuditQuery) : Promise<Result<AuditEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("query_audit_log", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check the audit log's hash chain for tampering
+ */
+async verifyAuditLog() : Promise<Result<AuditVerification, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("verify_audit_log") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export a turn's activity as a Markdown report and return its path
+ */
+async exportAuditTurn(turnId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_audit_turn", { turnId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the screenshots the agent has taken, newest first
+ */
+async getScreenshotHistory() : Promise<CaptureRecord[]> {
+    return await TAURI_INVOKE("get_screenshot_history");
+},
+/**
+ * Delete one screenshot from the history
+ */
+async deleteScreenshot(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_screenshot", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete the whole screenshot history, returning how many were removed
+ */
+async purgeScreenshotHistory() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("purge_screenshot_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List workspace checkpoints with their changes, newest first
+ * Without a session id, lists the active backend's current session.
+ */
+async listCheckpoints(sessionId: string | null) : Promise<Checkpoint[]> {
+    return await TAURI_INVOKE("list_checkpoints", { sessionId });
+},
+/**
+ * Roll the workspace back to a checkpoint, returning the files that changed
+ */
+async restoreCheckpoint(id: string) : Promise<Result<CheckpointDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_checkpoint", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Open native folder picker dialog
  */
@@ -198,6 +344,18 @@ async clearCodexSessionCmd() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Remove the mascot MCP server entry older versions added to ~/.codex/config.toml
+ * Returns whether an entry was removed (the original is backed up first)
+ */
+async uninstallCodexMcpConfig() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("uninstall_codex_mcp_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Clear Claude session specifically
  */
@@ -221,6 +379,420 @@ async clearClaudeSessionCmd() : Promise<Result<null, string>> {
 
 /** user-defined types **/
 
+/**
+ * Payload of the `agent-file-changes` event
+ */
+export type AgentFileChanges = { 
+/**
+ * "claude" or "codex"
+ */
+backend: string; sessionId: string | null; 
+/**
+ * Checkpoint the turn can be rolled back to
+ */
+checkpointId: string | null; files: FileDiff[] }
+/**
+ * Persisted app settings
+ */
+export type AppSettings = { 
+/**
+ * When set, capture_screenshot politely refuses
+ */
+screenshotsPaused: boolean; 
+/**
+ * App names or window titles (case-insensitive substrings) hidden from screenshots
+ */
+screenshotDenyList: string[]; 
+/**
+ * Hide Supiki's own windows (mascot, chat) from screenshots
+ */
+redactSupikiWindows: boolean; redactionStyle: RedactionStyle; 
+/**
+ * Longest edge of screenshots sent to the agent
+ */
+screenshotMaxDimension: number; 
+/**
+ * 1-99 encodes lossy JPEG, 100 encodes lossless WebP
+ */
+screenshotQuality: number; screenshotGrayscale: boolean; 
+/**
+ * Target size of an encoded screenshot in bytes
+ */
+screenshotMaxBytes: number | null; screenshotConsent: ScreenshotConsent; 
+/**
+ * URL schemes the agent may open without asking
+ */
+openAllowedSchemes: string[]; 
+/**
+ * Domains (and their subdomains) the agent may open without asking
+ */
+openAllowedDomains: string[]; 
+/**
+ * Directories whose files the agent may open without asking
+ */
+openAllowedPathPrefixes: string[]; 
+/**
+ * Speak the agent's answers with the TTS engine
+ */
+readAnswersAloud: boolean; 
+/**
+ * TTS engine command; text goes to stdin, `{voice}` and `{output}` are substituted
+ */
+ttsCommand: string; 
+/**
+ * TTS voice per UI language code
+ */
+ttsVoices: { [key in string]: string }; 
+/**
+ * Codex sandbox mode; unset follows the permission policy
+ */
+codexSandbox: CodexSandbox | null; 
+/**
+ * Let commands in the workspace-write sandbox use the network
+ */
+codexNetworkAccess: boolean; 
+/**
+ * Directories Codex may write to besides the workspace (workspace-write only)
+ */
+codexWritableRoots: string[]; 
+/**
+ * Extra regular expressions masked in the backend log
+ */
+logRedactPatterns: string[]; sensitiveCwdAction: SensitiveCwdAction; 
+/**
+ * Only skip permission checks in a git repository with a clean tree
+ */
+requireCleanGitRepo: boolean }
+/**
+ * The user's answer to an approval
+ */
+export type ApprovalDecision = "deny" | "allow" | "alwaysAllow"
+/**
+ * Payload of the `approval-request` event
+ */
+export type ApprovalRequest = { id: string; 
+/**
+ * Short summary, e.g. "Open link?"
+ */
+title: string; 
+/**
+ * What exactly will happen (URL, path, command)
+ */
+detail: string; 
+/**
+ * Offer an "always allow" answer
+ */
+allowAlways: boolean }
+/**
+ * One line of the audit log
+ */
+export type AuditEntry = { seq: number; 
+/**
+ * Unix timestamp in milliseconds
+ */
+timestamp: number; kind: AuditKind; 
+/**
+ * "claude" or "codex"
+ */
+backend: string | null; sessionId: string | null; turnId: string | null; cwd: string | null; tool: string | null; 
+/**
+ * Tool input (JSON), approval detail or turn settings
+ */
+detail: string | null; 
+/**
+ * Decision or turn result
+ */
+outcome: string | null; prevHash: string; hash: string }
+/**
+ * What an audit entry records
+ */
+export type AuditKind = "turnStart" | "toolUse" | 
+/**
+ * A user decision in an approval window
+ */
+"approval" | 
+/**
+ * A permission request decided without asking (policy or "always allow")
+ */
+"permission" | "turnEnd"
+/**
+ * Filter for `query`; unset fields match everything
+ */
+export type AuditQuery = { sessionId: string | null; turnId: string | null; tool: string | null; 
+/**
+ * Unix timestamps in milliseconds (inclusive)
+ */
+since: number | null; until: number | null; 
+/**
+ * Return only the newest entries
+ */
+limit: number | null }
+/**
+ * Result of checking the hash chain
+ */
+export type AuditVerification = { entries: number; valid: boolean; 
+/**
+ * Where the chain breaks, if it does
+ */
+error: string | null; 
+/**
+ * The newest entry was checked against the stored head
+ */
+anchored: boolean }
+/**
+ * A capture in the history
+ */
+export type CaptureRecord = { id: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+timestamp: number; 
+/**
+ * Tool that took the screenshot
+ */
+tool: string; description: string | null; sessionId: string | null; 
+/**
+ * Size of the capture before it was scaled down
+ */
+width: number; height: number; 
+/**
+ * Thumbnail as a data URL (filled in when listing)
+ */
+thumbnail?: string | null }
+export type ChangeStatus = "added" | "modified" | "deleted"
+/**
+ * A snapshot of the workspace
+ */
+export type Checkpoint = { id: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+timestamp: number; 
+/**
+ * "claude" or "codex"
+ */
+backend: string; sessionId: string | null; 
+/**
+ * Workspace root (the repository root for git checkpoints)
+ */
+root: string; kind: CheckpointKind; 
+/**
+ * "turn", or "restore" for the state kept before a rollback
+ */
+reason: string; 
+/**
+ * What changed after the checkpoint was taken
+ */
+changes: FileChange[] }
+/**
+ * Payload of the `checkpoint-diff` event
+ */
+export type CheckpointDiff = { checkpointId: string; root: string; changes: FileChange[] }
+/**
+ * How a checkpoint is stored
+ */
+export type CheckpointKind = 
+/**
+ * A commit under `refs/supiki/checkpoints/`
+ */
+"git" | 
+/**
+ * A manifest into the content-addressed store
+ */
+"copy"
+/**
+ * Claude CLI flags derived from a policy
+ */
+export type ClaudePermissions = { allowedTools: string[]; disallowedTools: string[]; 
+/**
+ * Everything not disallowed is allowed, so no prompts are needed
+ */
+skipPermissions: boolean; 
+/**
+ * Some edit or shell tool isn't denied outright, so a turn may change files
+ */
+canWrite: boolean }
+/**
+ * Summary of a saved chat session, synced from the frontend's history
+ */
+export type ChatSessionSummary = { id: string; title: string; 
+/**
+ * Unix timestamp in milliseconds of the last message
+ */
+updatedAt: number; messageCount: number }
+/**
+ * Codex `--sandbox` mode
+ */
+export type CodexSandbox = "read-only" | "workspace-write" | "danger-full-access"
+/**
+ * The sandbox a Codex turn runs in
+ */
+export type CodexSandboxStatus = { mode: CodexSandbox; 
+/**
+ * Chosen in the settings rather than derived from the permission policy
+ */
+fromSettings: boolean; 
+/**
+ * Whether sandboxed commands can use the network
+ */
+networkAccess: boolean; 
+/**
+ * Writable directories besides the workspace
+ */
+writableRoots: string[] }
+/**
+ * A hunk of a unified diff
+ */
+export type DiffHunk = { 
+/**
+ * 1-based start line and line count in the old file
+ */
+oldStart: number; oldLines: number; 
+/**
+ * 1-based start line and line count in the new file
+ */
+newStart: number; newLines: number; 
+/**
+ * Lines prefixed with ' ', '-' or '+'
+ */
+lines: string[] }
+/**
+ * The policy in effect for a workspace
+ */
+export type EffectivePolicy = { cwd: string | null; 
+/**
+ * Workspace entry of the policy file that applies, if any
+ */
+workspace: string | null; defaultAction: PolicyAction; 
+/**
+ * Workspace rules, then global rules, then built-in rules
+ */
+rules: PolicyRule[]; claude: ClaudePermissions; 
+/**
+ * Codex sandbox mode unless the settings choose one
+ */
+codexSandbox: CodexSandbox; 
+/**
+ * Why the Codex sandbox is read-only, if it is
+ */
+codexSandboxReason: string | null }
+/**
+ * An emotion the mascot can express
+ */
+export type Emotion = "neutral" | "happy" | "sad" | "excited" | "thinking" | "confused" | "surprised" | "curious"
+/**
+ * Payload of the `agent-emotion` event
+ */
+export type EmotionEvent = { emotion: Emotion; 
+/**
+ * How long to show the emotion in milliseconds
+ */
+duration: number; 
+/**
+ * Strength of the expression from 0.0 to 1.0
+ */
+intensity: number; 
+/**
+ * Emotion to return to after the duration (neutral when unset)
+ */
+blendTo: Emotion | null }
+/**
+ * A file changed since a checkpoint
+ */
+export type FileChange = { 
+/**
+ * Path relative to the checkpoint root, with `/` separators
+ */
+path: string; status: ChangeStatus; 
+/**
+ * Added and removed lines (git checkpoints, text files only)
+ */
+additions: number | null; deletions: number | null }
+/**
+ * How an agent changed one file during a turn
+ */
+export type FileDiff = { 
+/**
+ * Relative to the checkpoint root or working directory, with `/`
+ * separators; absolute for files outside it
+ */
+path: string; 
+/**
+ * Tools that edited the file
+ */
+tools: string[]; status: ChangeStatus; additions: number; deletions: number; hunks: DiffHunk[]; 
+/**
+ * The whole diff as unified diff text
+ */
+unified: string; 
+/**
+ * No text diff, because a version is binary or too large
+ */
+binary: boolean; 
+/**
+ * No text diff, because the content before the turn is unknown
+ */
+beforeUnknown: boolean }
+/**
+ * What happens when a tool call matches a rule
+ */
+export type PolicyAction = "allow" | "ask" | "deny"
+/**
+ * One rule of the policy
+ */
+export type PolicyRule = { 
+/**
+ * Tool name; a trailing `*` matches a prefix (e.g. `mcp__github__*`)
+ */
+tool: string; action: PolicyAction; 
+/**
+ * Only match these path globs; relative globs are resolved against the workspace
+ */
+paths: string[]; 
+/**
+ * Only match commands starting with one of these prefixes (Bash)
+ */
+commands: string[] }
+/**
+ * How redacted screenshot regions are hidden
+ */
+export type RedactionStyle = "blackout" | "blur"
+/**
+ * When the agent may take screenshots
+ */
+export type ScreenshotConsent = "always" | 
+/**
+ * Ask before every screenshot
+ */
+"ask" | 
+/**
+ * Ask once per agent session
+ */
+"session"
+/**
+ * What happens when the working directory is set to a root, home or system directory
+ */
+export type SensitiveCwdAction = "refuse" | "confirm" | "allow"
+/**
+ * Payload of the `tts-audio` event, one per synthesized chunk
+ */
+export type TtsAudioEvent = { 
+/**
+ * Utterance the chunk belongs to; a new utterance replaces the old one
+ */
+utterance: number; index: number; isLast: boolean; 
+/**
+ * WAV audio as a data URL
+ */
+audio: string }
+/**
+ * Payload of the `workspace-warning` event
+ */
+export type WorkspaceWarning = { path: string; reason: string; 
+/**
+ * The directory was not used
+ */
+blocked: boolean }
 
 
 /** tauri-specta globals **/
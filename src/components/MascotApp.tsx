@@ -3,19 +3,24 @@ import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { useTranslation } from "react-i18next";
 import { useAnimationState, AnimationState, Direction } from "../hooks/useMascotState";
-import { usePhysics } from "../hooks/usePhysics";
+import { usePhysics, type PathPoint } from "../hooks/usePhysics";
 import { useChatWindow } from "../hooks/useChatWindow";
 import { useDrag } from "../hooks/useDrag";
 import { useAutoWalk } from "../hooks/useAutoWalk";
 import { useMascotEvents } from "../hooks/useMascotEvents";
+import { useSequencePlayer } from "../hooks/useSequencePlayer";
+import { useSpeech } from "../hooks/useSpeech";
 import { useContextMenu } from "../hooks/useContextMenu";
+import Utterance from "./mascot/Utterance";
 import { WINDOW_WIDTH, WINDOW_HEIGHT, CHAT_WIDTH, CHAT_HEIGHT } from "../constants";
-import type { Emotion } from "../emotion";
+import type { Emotion, EmotionChange } from "../emotion";
 
 export interface MascotRenderProps {
   animationState: AnimationState;
   direction: Direction;
   emotion: Emotion;
+  /** Strength of the expression from 0.0 to 1.0 */
+  emotionIntensity: number;
   onClick: (e: React.MouseEvent) => void;
   onMouseDown: (e: React.MouseEvent) => void;
   onContextMenu: (e: React.MouseEvent) => void;
@@ -23,7 +28,7 @@ export interface MascotRenderProps {
 
 interface MascotAppProps {
   renderMascot: (props: MascotRenderProps) => ReactNode;
-  onEmotionChange?: (emotion: Emotion) => void;
+  onEmotionChange?: (emotion: Emotion, intensity: number) => void;
 }
 
 export function MascotApp({ renderMascot, onEmotionChange }: MascotAppProps) {
@@ -36,8 +41,8 @@ export function MascotApp({ renderMascot, onEmotionChange }: MascotAppProps) {
 
   // Notify parent of emotion changes
   useEffect(() => {
-    onEmotionChange?.(mascot.emotion);
-  }, [mascot.emotion, onEmotionChange]);
+    onEmotionChange?.(mascot.emotion, mascot.emotionIntensity);
+  }, [mascot.emotion, mascot.emotionIntensity, onEmotionChange]);
 
   // Chat window management
   const chatWindow = useChatWindow(
@@ -123,8 +128,8 @@ export function MascotApp({ renderMascot, onEmotionChange }: MascotAppProps) {
   // Event handlers for mascot events
   const eventHandlers = useMemo(
     () => ({
-      onEmotionChange: (emotion: Parameters<typeof mascot.setEmotion>[0]) => {
-        mascot.setEmotion(emotion);
+      onEmotionChange: (change: EmotionChange) => {
+        mascot.setEmotion(change.emotion, change.duration, change);
       },
       onWalkToWindow: (targetX: number) => {
         const currentX = physics.getState().x;
@@ -148,6 +153,15 @@ export function MascotApp({ renderMascot, onEmotionChange }: MascotAppProps) {
           mascot.setDirection(physics.getDirection());
         });
       },
+      onMovePath: (points: PathPoint[], speed: number) => {
+        const last = points[points.length - 1];
+        mascot.setDirection(last.x > physics.getState().x ? "right" : "left");
+        mascot.setAnimationState("walking");
+        physics.followPath(points, speed, () => {
+          mascot.setAnimationState("idle");
+          mascot.setDirection(physics.getDirection());
+        });
+      },
       onChatClosed: () => {
         (chatWindow as ReturnType<typeof useChatWindow> & { _handleClosed: () => void })._handleClosed();
         if (physicsEnabled) {
@@ -202,6 +216,20 @@ export function MascotApp({ renderMascot, onEmotionChange }: MascotAppProps) {
     getCurrentX: () => physics.getState().x,
   });
 
+  useSequencePlayer({
+    jump: () => {
+      mascot.triggerJump();
+      physics.jump();
+    },
+    setAnimationState: mascot.setAnimationState,
+    setDirection: mascot.setDirection,
+    getCenterX: () => physics.getState().x + WINDOW_WIDTH / 2,
+    onStart: () => physics.stopWalking(),
+  });
+
+  // Play text-to-speech audio (speak tool and read-aloud answers)
+  useSpeech();
+
   // Start physics on mount and when chat closes
   useEffect(() => {
     if (physicsEnabled && !chatOpen) {
@@ -258,11 +286,13 @@ export function MascotApp({ renderMascot, onEmotionChange }: MascotAppProps) {
       onMouseDown={handleMouseDown}
       onDoubleClick={handleDoubleClick}
     >
+      <Utterance />
       <div className="mascot-wrapper">
         {renderMascot({
           animationState: mascot.animationState,
           direction: mascot.direction,
           emotion: mascot.emotion,
+          emotionIntensity: mascot.emotionIntensity,
           onClick: handleClick,
           onMouseDown: handleMascotMouseDown,
           onContextMenu: openContextMenu,
@@ -3,6 +3,8 @@ import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import supikiImage from "../resources/supiki.webp";
 import { usePhysics } from "../hooks/usePhysics";
+import Utterance from "./mascot/Utterance";
+import type { Emotion } from "../emotion";
 import {
   MINI_WINDOW_WIDTH,
   MINI_WINDOW_HEIGHT,
@@ -14,11 +16,12 @@ import {
 
 interface MiniSupikiAppProps {
   id: string;
+  emotion?: Emotion;
 }
 
 type MiniMascotState = "idle" | "walking" | "falling";
 
-function MiniSupikiApp({ id }: MiniSupikiAppProps) {
+function MiniSupikiApp({ id, emotion = "neutral" }: MiniSupikiAppProps) {
   const [isVisible, setIsVisible] = useState(false);
   const [mascotState, setMascotState] = useState<MiniMascotState>("falling");
   const [facingDirection, setFacingDirection] = useState<"left" | "right">("right");
@@ -114,6 +117,7 @@ function MiniSupikiApp({ id }: MiniSupikiAppProps) {
     <div
       className="mini-mascot-container"
       style={{
+        position: "relative",
         width: `${MINI_WINDOW_WIDTH}px`,
         height: `${MINI_WINDOW_HEIGHT}px`,
         display: "flex",
@@ -126,20 +130,24 @@ function MiniSupikiApp({ id }: MiniSupikiAppProps) {
         transformOrigin: "center bottom",
       }}
     >
-      <img
-        src={supikiImage}
-        alt="Mini Supiki"
-        style={{
-          maxWidth: "70px",
-          maxHeight: "60px",
-          width: "auto",
-          height: "auto",
-          filter: "drop-shadow(0 2px 4px rgba(0,0,0,0.2))",
-          transform: facingDirection === "left" ? "scaleX(-1)" : "scaleX(1)",
-          transition: "transform 0.1s ease-out",
-        }}
-        draggable={false}
-      />
+      <Utterance />
+      {/* The emotion animation goes on a wrapper so it doesn't override the facing flip */}
+      <div className={`mascot-emotion-${emotion}`}>
+        <img
+          src={supikiImage}
+          alt="Mini Supiki"
+          style={{
+            maxWidth: "70px",
+            maxHeight: "60px",
+            width: "auto",
+            height: "auto",
+            filter: "drop-shadow(0 2px 4px rgba(0,0,0,0.2))",
+            transform: facingDirection === "left" ? "scaleX(-1)" : "scaleX(1)",
+            transition: "transform 0.1s ease-out",
+          }}
+          draggable={false}
+        />
+      </div>
     </div>
   );
 }
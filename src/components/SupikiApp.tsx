@@ -28,6 +28,16 @@ function SupikiApp() {
     };
   }, [playCompletionSound]);
 
+  // Play a sound when a reminder set by the agent is due
+  useEffect(() => {
+    const unlisten = listen("reminder-due", () => {
+      playCompletionSound();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [playCompletionSound]);
+
   // Handle subagent (Task) events - spawn and close mini mascots
   useEffect(() => {
     const unlistenStart = listen<SubagentStartEvent>("subagent-start", async (event) => {
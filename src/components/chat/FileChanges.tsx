@@ -0,0 +1,66 @@
+import { useTranslation } from "react-i18next";
+import type { AgentFileChanges, FileDiff } from "../../bindings";
+
+interface FileChangesProps {
+  changes: AgentFileChanges;
+  onDismiss: () => void;
+}
+
+function lineClass(line: string) {
+  if (line.startsWith("+")) return "file-diff-line added";
+  if (line.startsWith("-")) return "file-diff-line removed";
+  return "file-diff-line";
+}
+
+function FileDiffView({ file }: { file: FileDiff }) {
+  const { t } = useTranslation();
+  const name = file.path.split(/[\\/]/).pop() ?? file.path;
+
+  return (
+    <details className="file-diff">
+      <summary title={`${file.path} (${file.tools.join(", ")})`}>
+        <span className="file-diff-name">{name}</span>
+        <span className="file-diff-count added">+{file.additions}</span>
+        <span className="file-diff-count removed">-{file.deletions}</span>
+      </summary>
+      {file.beforeUnknown ? (
+        <div className="file-diff-binary">{t("chat.fileChangesUnknown")}</div>
+      ) : file.binary ? (
+        <div className="file-diff-binary">{t("chat.fileChangesBinary")}</div>
+      ) : (
+        <pre className="file-diff-body">
+          {file.hunks.map((hunk, i) => (
+            <div key={i}>
+              <div className="file-diff-hunk">
+                @@ -{hunk.oldStart},{hunk.oldLines} +{hunk.newStart},{hunk.newLines} @@
+              </div>
+              {hunk.lines.map((line, j) => (
+                <div key={j} className={lineClass(line)}>
+                  {line}
+                </div>
+              ))}
+            </div>
+          ))}
+        </pre>
+      )}
+    </details>
+  );
+}
+
+function FileChanges({ changes, onDismiss }: FileChangesProps) {
+  const { t } = useTranslation();
+
+  return (
+    <div className="file-changes">
+      <div className="file-changes-header">
+        <span>{t("chat.fileChanges", { count: changes.files.length })}</span>
+        <button onClick={onDismiss}>x</button>
+      </div>
+      {changes.files.map((file) => (
+        <FileDiffView key={file.path} file={file} />
+      ))}
+    </div>
+  );
+}
+
+export default FileChanges;
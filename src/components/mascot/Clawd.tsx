@@ -1,17 +1,19 @@
 import { AnimationState, Direction } from "../../hooks/useMascotState";
-import { type Emotion, FACIAL_CONFIG, emotionToFacial } from "../../emotion";
+import { type Emotion, emotionToFacial, scaleFacialConfig } from "../../emotion";
 
 interface ClawdProps {
   animationState: AnimationState;
   direction: Direction;
   emotion?: Emotion;
+  /** Strength of the expression from 0.0 to 1.0 */
+  intensity?: number;
   onClick?: (e: React.MouseEvent) => void;
   onMouseDown?: (e: React.MouseEvent) => void;
   onDoubleClick?: (e: React.MouseEvent) => void;
   onContextMenu?: (e: React.MouseEvent) => void;
 }
 
-function Clawd({ animationState, direction, emotion: emotionProp = "neutral", onClick, onMouseDown, onDoubleClick, onContextMenu }: ClawdProps) {
+function Clawd({ animationState, direction, emotion: emotionProp = "neutral", intensity = 1, onClick, onMouseDown, onDoubleClick, onContextMenu }: ClawdProps) {
   // When talking and no active emotion (neutral), show curious
   const emotion = animationState === "talking" && emotionProp === "neutral" ? "curious" : emotionProp;
   // Convert emotion to facial state for visual rendering
@@ -27,12 +29,18 @@ function Clawd({ animationState, direction, emotion: emotionProp = "neutral", on
         return "mascot-falling";
       case "talking":
         return "mascot-talking";
+      case "waving":
+        return "mascot-waving";
+      case "spinning":
+        return "mascot-spinning";
+      case "sleeping":
+        return "mascot-sleeping";
       default:
         return "mascot-idle";
     }
   };
 
-  const facialConfig = FACIAL_CONFIG[facialState];
+  const facialConfig = scaleFacialConfig(facialState, intensity);
   const leftEye = facialConfig.leftEye;
   const rightEye = facialConfig.rightEye;
   const eyebrows = facialConfig.eyebrows;
@@ -148,6 +148,12 @@ function Supiki({ animationState, direction, onClick, onMouseDown, onDoubleClick
         return "mascot-falling";
       case "talking":
         return "mascot-talking";
+      case "waving":
+        return "mascot-waving";
+      case "spinning":
+        return "mascot-spinning";
+      case "sleeping":
+        return "mascot-sleeping";
       default:
         return "mascot-idle";
     }
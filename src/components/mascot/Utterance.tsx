@@ -0,0 +1,77 @@
+import { useEffect, useRef, useState } from "react";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { useTauriEvent } from "../../hooks/useTauriEvent";
+
+type UtteranceStyle = "normal" | "whisper" | "shout";
+
+interface SayEvent {
+  window: string;
+  message: string;
+  durationMs: number;
+  style: UtteranceStyle;
+}
+
+interface ReminderDueEvent {
+  id: string;
+  message: string;
+  dueAt: number;
+}
+
+/** How long a due reminder stays on screen */
+const REMINDER_DURATION_MS = 15_000;
+
+/** Longer reminders are shortened to fit the bubble (like `say` messages) */
+const REMINDER_MAX_CHARS = 120;
+
+/**
+ * Timed speech bubble for the `say` tool and due reminders.
+ * Only shows utterances addressed to the current mascot window (reminders go
+ * to the main mascot) and never touches the chat transcript.
+ */
+function Utterance() {
+  const [current, setCurrent] = useState<SayEvent | null>(null);
+  const timeoutRef = useRef<number | null>(null);
+  const label = getCurrentWindow().label;
+
+  const show = (event: SayEvent) => {
+    if (timeoutRef.current) {
+      window.clearTimeout(timeoutRef.current);
+    }
+    setCurrent(event);
+    timeoutRef.current = window.setTimeout(() => {
+      setCurrent(null);
+      timeoutRef.current = null;
+    }, event.durationMs);
+  };
+
+  useTauriEvent<SayEvent>("mascot-say", (event) => {
+    if (event.window !== label) return;
+    show(event);
+  });
+
+  useTauriEvent<ReminderDueEvent>("reminder-due", (event) => {
+    if (label !== "main") return;
+    const chars = Array.from(event.message);
+    const message =
+      chars.length > REMINDER_MAX_CHARS ? `${chars.slice(0, REMINDER_MAX_CHARS).join("")}…` : event.message;
+    show({ window: label, message, durationMs: REMINDER_DURATION_MS, style: "normal" });
+  });
+
+  useEffect(() => {
+    return () => {
+      if (timeoutRef.current) {
+        window.clearTimeout(timeoutRef.current);
+      }
+    };
+  }, []);
+
+  if (!current) return null;
+
+  return (
+    <div className={`utterance utterance-${current.style}`} role="status">
+      {current.message}
+    </div>
+  );
+}
+
+export default Utterance;
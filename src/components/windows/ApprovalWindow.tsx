@@ -0,0 +1,79 @@
+import { useCallback, useEffect, useState } from "react";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { useTranslation } from "react-i18next";
+import { commands, type ApprovalDecision, type ApprovalRequest } from "../../bindings";
+import { useModalWindow } from "../../hooks/useModalWindow";
+import "../../styles/questionmodal.css";
+
+interface ApprovalWindowProps {
+  id: string;
+}
+
+/**
+ * Asks the user to approve an agent action (opened by the backend).
+ * Closing the window without answering counts as a denial.
+ */
+function ApprovalWindow({ id }: ApprovalWindowProps) {
+  const { t } = useTranslation();
+  const [request, setRequest] = useState<ApprovalRequest | null>(null);
+
+  useEffect(() => {
+    commands
+      .getApprovalRequest(id)
+      .then((result) => {
+        if (result) {
+          setRequest(result);
+        } else {
+          // Already answered or timed out
+          getCurrentWindow().close();
+        }
+      })
+      .catch((err) => console.error("[ApprovalWindow] Failed to load request:", err));
+  }, [id]);
+
+  const respond = useCallback(
+    async (decision: ApprovalDecision) => {
+      try {
+        await commands.respondApproval(id, decision);
+      } catch (err) {
+        console.error("[ApprovalWindow] Failed to respond:", err);
+      }
+      await getCurrentWindow().close();
+    },
+    [id]
+  );
+
+  const { handleDragStart } = useModalWindow({
+    onEscape: () => respond("deny"),
+  });
+
+  if (!request) return null;
+
+  return (
+    <div className="question-modal approval-modal" onMouseDown={handleDragStart}>
+      <div className="modal-header question-modal-header">
+        <span>{request.title}</span>
+      </div>
+
+      <div className="modal-body">
+        <div className="question-text approval-detail">{request.detail}</div>
+      </div>
+
+      <div className="modal-footer question-modal-footer approval-footer">
+        <button className="question-nav-btn" onClick={() => respond("deny")}>
+          {t("approvalWindow.deny")}
+        </button>
+        {request.allowAlways && (
+          <button className="question-nav-btn" onClick={() => respond("alwaysAllow")}>
+            {t("approvalWindow.alwaysAllow")}
+          </button>
+        )}
+        <button className="question-submit-btn" onClick={() => respond("allow")} autoFocus>
+          {t("approvalWindow.allow")}
+        </button>
+      </div>
+    </div>
+  );
+}
+
+export default ApprovalWindow;
@@ -1,29 +1,41 @@
-import { useCallback, useEffect, useRef } from "react";
+import { useCallback, useEffect, useRef, useState } from "react";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { emit, listen } from "@tauri-apps/api/event";
 import { useTranslation } from "react-i18next";
 import SpeechBubble from "../mascot/SpeechBubble";
 import ChatInput, { type AttachedImage } from "../chat/ChatInput";
+import FileChanges from "../chat/FileChanges";
 import QuestionModal from "../modals/QuestionModal";
 import PlanModeExitModal from "../modals/PlanModeExitModal";
 import { useAgentChat } from "../../hooks/useAgentChat";
 import { useModalWindow } from "../../hooks/useModalWindow";
-import type { Emotion } from "../../emotion";
+import type { Emotion, EmotionChange } from "../../emotion";
+import {
+  commands,
+  type AgentFileChanges,
+  type CheckpointDiff,
+  type WorkspaceWarning,
+} from "../../bindings";
 
 function ChatWindow() {
   const { t } = useTranslation();
   const messagesEndRef = useRef<HTMLDivElement>(null);
   const skipBlurRef = useRef(false);
+  const [workspaceWarning, setWorkspaceWarning] = useState<WorkspaceWarning | null>(null);
+  const [checkpointDiff, setCheckpointDiff] = useState<CheckpointDiff | null>(null);
+  const [checkpointNote, setCheckpointNote] = useState<string | null>(null);
+  const [fileChanges, setFileChanges] = useState<AgentFileChanges | null>(null);
 
   // Check if viewing a past session (read-only mode)
   const urlParams = new URLSearchParams(window.location.search);
   const viewSessionId = urlParams.get("viewSession");
   const isViewMode = !!viewSessionId;
 
-  const handleEmotionChange = (emotion: Emotion) => {
+  const handleEmotionChange = (emotion: Emotion, details?: Omit<EmotionChange, "emotion">) => {
     // Send emotion to main window (only in active chat mode)
     if (!isViewMode) {
-      emit("emotion-change", emotion);
+      const change: EmotionChange = { emotion, ...details };
+      emit("emotion-change", change);
     }
   };
 
@@ -73,6 +85,49 @@ function ChatWindow() {
     };
   }, [chat]);
 
+  // Listen for workspace guardrail warnings (risky cwd, unattended turn refused)
+  useEffect(() => {
+    const unlisten = listen<WorkspaceWarning>("workspace-warning", (event) => {
+      setWorkspaceWarning(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Listen for what the last turn changed in the workspace
+  useEffect(() => {
+    const unlisten = listen<CheckpointDiff>("checkpoint-diff", (event) => {
+      setCheckpointDiff(event.payload);
+      setCheckpointNote(null);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Listen for the diffs of the files the last turn edited
+  useEffect(() => {
+    const unlisten = listen<AgentFileChanges>("agent-file-changes", (event) => {
+      setFileChanges(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleUndoTurn = async () => {
+    if (!checkpointDiff) return;
+    const result = await commands.restoreCheckpoint(checkpointDiff.checkpointId);
+    setCheckpointDiff(null);
+    setFileChanges(null);
+    setCheckpointNote(
+      result.status === "ok"
+        ? t("chat.checkpointRestored", { count: result.data.changes.length })
+        : result.error
+    );
+  };
+
   // Scroll to bottom when new messages arrive
   useEffect(() => {
     messagesEndRef.current?.scrollIntoView({ behavior: "smooth" });
@@ -150,6 +205,38 @@ function ChatWindow() {
         </div>
       </div>
       <div className="chat-window-body">
+        {!isViewMode && workspaceWarning && (
+          <div
+            className="workspace-warning"
+            title={workspaceWarning.path}
+            onClick={() => setWorkspaceWarning(null)}
+          >
+            {t("chat.workspaceWarning", { reason: workspaceWarning.reason })}
+          </div>
+        )}
+        {!isViewMode && checkpointDiff && (
+          <div
+            className="checkpoint-diff"
+            title={checkpointDiff.changes.map((c) => `${c.status}: ${c.path}`).join("\n")}
+          >
+            <span>
+              {t("chat.checkpointChanged", {
+                count: checkpointDiff.changes.length,
+                additions: checkpointDiff.changes.reduce((n, c) => n + (c.additions ?? 0), 0),
+                deletions: checkpointDiff.changes.reduce((n, c) => n + (c.deletions ?? 0), 0),
+              })}
+            </span>
+            <button onClick={handleUndoTurn}>{t("chat.checkpointUndo")}</button>
+          </div>
+        )}
+        {!isViewMode && checkpointNote && (
+          <div className="checkpoint-diff" onClick={() => setCheckpointNote(null)}>
+            {checkpointNote}
+          </div>
+        )}
+        {!isViewMode && fileChanges && (
+          <FileChanges changes={fileChanges} onDismiss={() => setFileChanges(null)} />
+        )}
         {/* Tool indicator when agent is using tools */}
         {!isViewMode && chat.streamingState?.currentToolName && (
           <div className="tool-indicator">
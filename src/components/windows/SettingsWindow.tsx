@@ -9,16 +9,40 @@ import {
   type BackendMode,
 } from "../../services/settingsStorage";
 import { changeLanguage } from "../../i18n";
-import { commands } from "../../bindings";
+import {
+  commands,
+  type AppSettings,
+  type CaptureRecord,
+  type CodexSandbox,
+  type CodexSandboxStatus,
+  type ScreenshotConsent,
+} from "../../bindings";
 import { useModalWindow } from "../../hooks/useModalWindow";
 import { Modal } from "../modals/Modal";
 import "../../styles/settings.css";
 
+const CODEX_SANDBOXES: { mode: CodexSandbox | null; labelKey: string }[] = [
+  { mode: null, labelKey: "settings.sandboxAuto" },
+  { mode: "read-only", labelKey: "settings.sandboxReadOnly" },
+  { mode: "workspace-write", labelKey: "settings.sandboxWorkspaceWrite" },
+  { mode: "danger-full-access", labelKey: "settings.sandboxFullAccess" },
+];
+
+const SCREENSHOT_CONSENTS: { mode: ScreenshotConsent; labelKey: string }[] = [
+  { mode: "always", labelKey: "settings.screenshotAlways" },
+  { mode: "session", labelKey: "settings.screenshotSession" },
+  { mode: "ask", labelKey: "settings.screenshotAsk" },
+];
+
 function SettingsWindow() {
   const { t } = useTranslation();
   const [settings, setSettings] = useState<Settings>(loadSettings);
   const [claudeAvailable, setClaudeAvailable] = useState<boolean | null>(null);
   const [codexAvailable, setCodexAvailable] = useState<boolean | null>(null);
+  const [appSettings, setAppSettings] = useState<AppSettings | null>(null);
+  const [codexSandbox, setCodexSandbox] = useState<CodexSandboxStatus | null>(null);
+  const [screenshotHistory, setScreenshotHistory] = useState<CaptureRecord[]>([]);
+  const [codexConfigNote, setCodexConfigNote] = useState<string | null>(null);
 
   // Check CLI availability on mount
   useEffect(() => {
@@ -29,6 +53,11 @@ function SettingsWindow() {
       setCodexAvailable(result.status === "ok");
     });
 
+    // Backend-persisted settings (shared with the MCP server)
+    commands.getAppSettings().then(setAppSettings);
+    commands.getCodexSandbox().then(setCodexSandbox);
+    commands.getScreenshotHistory().then(setScreenshotHistory);
+
     // Sync backend mode with Rust state on mount
     commands.getBackendMode().then((mode) => {
       setSettings((currentSettings) => {
@@ -58,6 +87,7 @@ function SettingsWindow() {
     setSettings(newSettings);
     saveSettings(newSettings);
     changeLanguage(language); // Update i18n
+    commands.setLanguage(language); // Update the TTS voice
   };
 
   const handleBackendChange = async (mode: BackendMode) => {
@@ -74,6 +104,62 @@ function SettingsWindow() {
     }
   };
 
+  const handleReadAloudChange = async (readAnswersAloud: boolean) => {
+    if (!appSettings) return;
+    const newAppSettings = { ...appSettings, readAnswersAloud };
+    const result = await commands.setAppSettings(newAppSettings);
+    if (result.status === "ok") {
+      setAppSettings(newAppSettings);
+      if (!readAnswersAloud) {
+        await commands.stopSpeaking();
+      }
+    }
+  };
+
+  const updateCodexSandbox = async (changes: Partial<AppSettings>) => {
+    if (!appSettings) return;
+    const newAppSettings = { ...appSettings, ...changes };
+    const result = await commands.setAppSettings(newAppSettings);
+    if (result.status === "ok") {
+      setAppSettings(newAppSettings);
+      setCodexSandbox(await commands.getCodexSandbox());
+    }
+  };
+
+  const handleUninstallCodexConfig = async () => {
+    const result = await commands.uninstallCodexMcpConfig();
+    if (result.status === "ok") {
+      setCodexConfigNote(
+        t(result.data ? "settings.codexConfigRemoved" : "settings.codexConfigClean")
+      );
+    } else {
+      setCodexConfigNote(result.error);
+    }
+  };
+
+  const handleScreenshotConsentChange = async (screenshotConsent: ScreenshotConsent) => {
+    if (!appSettings) return;
+    const newAppSettings = { ...appSettings, screenshotConsent };
+    const result = await commands.setAppSettings(newAppSettings);
+    if (result.status === "ok") {
+      setAppSettings(newAppSettings);
+    }
+  };
+
+  const handleDeleteScreenshot = async (id: string) => {
+    const result = await commands.deleteScreenshot(id);
+    if (result.status === "ok") {
+      setScreenshotHistory((history) => history.filter((record) => record.id !== id));
+    }
+  };
+
+  const handlePurgeScreenshots = async () => {
+    const result = await commands.purgeScreenshotHistory();
+    if (result.status === "ok") {
+      setScreenshotHistory([]);
+    }
+  };
+
   return (
     <Modal
       title={t("settings.title")}
@@ -127,6 +213,44 @@ function SettingsWindow() {
           </div>
         </div>
 
+        {settings.backendMode === "codex" && (
+          <div className="settings-section">
+            <label className="settings-label">{t("settings.codexSandbox")}</label>
+            <div className="settings-language-list">
+              {CODEX_SANDBOXES.map(({ mode, labelKey }) => (
+                <button
+                  key={mode ?? "auto"}
+                  className={`settings-language-item ${
+                    (appSettings?.codexSandbox ?? null) === mode ? "selected" : ""
+                  }`}
+                  disabled={!appSettings}
+                  onClick={() => updateCodexSandbox({ codexSandbox: mode })}
+                >
+                  {t(labelKey)}
+                </button>
+              ))}
+            </div>
+            <label className="settings-toggle">
+              <input
+                type="checkbox"
+                checked={appSettings?.codexNetworkAccess ?? false}
+                disabled={!appSettings || codexSandbox?.mode !== "workspace-write"}
+                onChange={(e) => updateCodexSandbox({ codexNetworkAccess: e.target.checked })}
+              />
+              <span>{t("settings.sandboxNetworkAccess")}</span>
+            </label>
+            {codexSandbox && (
+              <span className="settings-hint">
+                {t("settings.sandboxActive", { mode: codexSandbox.mode })}
+              </span>
+            )}
+            <button className="settings-language-item" onClick={handleUninstallCodexConfig}>
+              {t("settings.codexConfigUninstall")}
+            </button>
+            {codexConfigNote && <span className="settings-hint">{codexConfigNote}</span>}
+          </div>
+        )}
+
         <div className="settings-section">
           <label className="settings-label">{t("settings.language")}</label>
           <div className="settings-language-list">
@@ -143,6 +267,56 @@ function SettingsWindow() {
             ))}
           </div>
         </div>
+
+        <div className="settings-section">
+          <label className="settings-label">{t("settings.voice")}</label>
+          <label className="settings-toggle">
+            <input
+              type="checkbox"
+              checked={appSettings?.readAnswersAloud ?? false}
+              disabled={!appSettings}
+              onChange={(e) => handleReadAloudChange(e.target.checked)}
+            />
+            <span>{t("settings.readAnswersAloud")}</span>
+          </label>
+        </div>
+
+        <div className="settings-section">
+          <label className="settings-label">{t("settings.screenshots")}</label>
+          <div className="settings-language-list">
+            {SCREENSHOT_CONSENTS.map(({ mode, labelKey }) => (
+              <button
+                key={mode}
+                className={`settings-language-item ${
+                  appSettings?.screenshotConsent === mode ? "selected" : ""
+                }`}
+                disabled={!appSettings}
+                onClick={() => handleScreenshotConsentChange(mode)}
+              >
+                {t(labelKey)}
+              </button>
+            ))}
+          </div>
+          {screenshotHistory.length > 0 && (
+            <>
+              <div className="settings-screenshot-history">
+                {screenshotHistory.map((record) => (
+                  <img
+                    key={record.id}
+                    src={record.thumbnail ?? undefined}
+                    title={`${new Date(record.timestamp).toLocaleString()} · ${record.tool}${
+                      record.description ? ` · ${record.description}` : ""
+                    }\n${t("settings.screenshotDeleteHint")}`}
+                    onClick={() => handleDeleteScreenshot(record.id)}
+                  />
+                ))}
+              </div>
+              <button className="settings-language-item" onClick={handlePurgeScreenshots}>
+                {t("settings.screenshotPurge", { count: screenshotHistory.length })}
+              </button>
+            </>
+          )}
+        </div>
       </div>
     </Modal>
   );
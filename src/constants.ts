@@ -17,9 +17,11 @@ export const CONTEXT_MENU_HEIGHT = 115;
 export const HISTORY_LIST_WIDTH = 280;
 export const HISTORY_LIST_HEIGHT = 350;
 export const SETTINGS_WINDOW_WIDTH = 280;
-export const SETTINGS_WINDOW_HEIGHT = 240;
+export const SETTINGS_WINDOW_HEIGHT = 300;
 export const CWD_WINDOW_WIDTH = 300;
 export const CWD_WINDOW_HEIGHT = 260;
+export const APPROVAL_WINDOW_WIDTH = 300;
+export const APPROVAL_WINDOW_HEIGHT = 180;
 
 // Chat window positioning relative to Clawd
 export const DEFAULT_CHAT_OFFSET = {
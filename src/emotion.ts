@@ -23,6 +23,19 @@ export const EMOTIONS: Emotion[] = [
   "curious",
 ];
 
+/**
+ * An emotion to show, with the optional details of a set_emotion call.
+ */
+export interface EmotionChange {
+  emotion: Emotion;
+  /** How long to show it in milliseconds */
+  duration?: number;
+  /** Strength of the expression from 0.0 to 1.0 (default 1) */
+  intensity?: number;
+  /** Emotion to blend back to after the duration (default neutral) */
+  blendTo?: Emotion | null;
+}
+
 // Re-export FacialState types for components that need the visual config
 export type { FacialState, EyeConfig } from "./facialState";
-export { FACIAL_STATES, FACIAL_CONFIG, emotionToFacial } from "./facialState";
+export { FACIAL_STATES, FACIAL_CONFIG, emotionToFacial, scaleFacialConfig } from "./facialState";
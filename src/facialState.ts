@@ -106,6 +106,29 @@ export const FACIAL_CONFIG: Record<FacialState, EyeConfig> = {
   },
 };
 
+/**
+ * Facial configuration for a state shown at partial strength.
+ * Eyes are interpolated from neutral; eyebrows appear from half strength.
+ */
+export function scaleFacialConfig(state: FacialState, intensity: number): EyeConfig {
+  const target = FACIAL_CONFIG[state];
+  const base = FACIAL_CONFIG.neutral;
+  const t = Math.min(Math.max(intensity, 0), 1);
+  const lerp = (from: number, to: number) => from + (to - from) * t;
+  const eye = (from: EyeConfig["leftEye"], to: EyeConfig["leftEye"]) => ({
+    y: lerp(from.y, to.y),
+    height: lerp(from.height, to.height),
+    width: lerp(from.width ?? 2, to.width ?? 2),
+    offsetX: lerp(from.offsetX ?? 0, to.offsetX ?? 0),
+  });
+  return {
+    leftEye: eye(base.leftEye, target.leftEye),
+    rightEye: eye(base.rightEye, target.rightEye),
+    eyebrows: t >= 0.5 ? target.eyebrows : undefined,
+    pupils: target.pupils,
+  };
+}
+
 /**
  * Map an Emotion to its corresponding FacialState.
  * Currently 1:1, but this allows for future divergence
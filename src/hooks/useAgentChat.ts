@@ -4,6 +4,7 @@ import { getAgentService } from "../services/agentService";
 import { detectEmotion } from "../services/emotionMapper";
 import { sessionStorage } from "../services/sessionStorage";
 import { generateId } from "../utils/id";
+import type { EmotionChange } from "../emotion";
 import type {
   AgentChatMessage,
   StreamingState,
@@ -17,7 +18,7 @@ import type {
 const MAX_MESSAGES = 100;
 
 interface UseAgentChatOptions {
-  onEmotionChange?: (emotion: Emotion) => void;
+  onEmotionChange?: (emotion: Emotion, details?: Omit<EmotionChange, "emotion">) => void;
   sessionId?: string; // Optional: load specific session for viewing history
 }
 
@@ -43,9 +44,9 @@ export function useAgentChat(options: UseAgentChatOptions = {}) {
   // Register emotion callback - emotions now come via events, not polling!
   useEffect(() => {
     const unsubscribe = agentService.current.onEmotionUpdate(
-      (emotion, _duration) => {
+      ({ emotion, ...details }) => {
         console.log("[useAgentChat] Emotion event received:", emotion);
-        onEmotionChange?.(emotion);
+        onEmotionChange?.(emotion, details);
       }
     );
     return unsubscribe;
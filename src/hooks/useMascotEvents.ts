@@ -2,13 +2,15 @@ import { useEffect } from "react";
 import { getCurrentWindow, currentMonitor } from "@tauri-apps/api/window";
 import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { listen } from "@tauri-apps/api/event";
-import type { Emotion } from "../emotion";
+import type { EmotionChange } from "../emotion";
 import { WINDOW_WIDTH } from "../constants";
+import type { PathPoint } from "./usePhysics";
 
 export interface MascotEventHandlers {
-  onEmotionChange: (emotion: Emotion) => void;
+  onEmotionChange: (change: EmotionChange) => void;
   onWalkToWindow: (targetX: number) => void;
   onMove: (target: string, x: number | null) => void;
+  onMovePath: (points: PathPoint[], speed: number) => void;
   onChatClosed: () => void;
   onChatMoved: (chatX: number, chatY: number) => void;
   onOpenChatHistory: () => void;
@@ -29,7 +31,7 @@ export function useMascotEvents(
 
   // Listen for emotion changes from chat window
   useEffect(() => {
-    const unlisten = listen<Emotion>("emotion-change", (event) => {
+    const unlisten = listen<EmotionChange>("emotion-change", (event) => {
       handlers.onEmotionChange(event.payload);
     });
     return () => {
@@ -53,12 +55,23 @@ export function useMascotEvents(
 
   // Listen for move events from sidecar
   useEffect(() => {
-    const unlisten = listen<{ target: string; x: number | null }>(
+    const unlisten = listen<{
+      target: string;
+      x: number | null;
+      points?: PathPoint[];
+      speed?: number;
+    }>(
       "clawd-move",
       async (event) => {
-        const { target, x } = event.payload;
+        const { target, x, points, speed } = event.payload;
         if (chatOpen || isDragging) return;
 
+        // Resolved by the backend against real monitor geometry
+        if (points && points.length > 0) {
+          handlers.onMovePath(points, speed ?? 1);
+          return;
+        }
+
         const monitor = await currentMonitor();
         const appWindow = getCurrentWindow();
         const scaleFactor = await appWindow.scaleFactor();
@@ -2,16 +2,32 @@ import { useState, useCallback, useRef, useEffect } from "react";
 import type { Emotion } from "../emotion";
 import { EMOTION_RESET_DURATION, TALK_DURATION } from "../constants";
 
-export type AnimationState = "idle" | "walking" | "talking" | "jumping" | "falling";
+export type AnimationState =
+  | "idle"
+  | "walking"
+  | "talking"
+  | "jumping"
+  | "falling"
+  | "waving"
+  | "spinning"
+  | "sleeping";
 export type Direction = "left" | "right";
 
+export interface EmotionOptions {
+  /** Strength of the expression from 0.0 to 1.0 (default 1) */
+  intensity?: number;
+  /** Emotion to blend back to after the duration (default neutral) */
+  blendTo?: Emotion | null;
+}
+
 interface AnimationStateManager {
   animationState: AnimationState;
   direction: Direction;
   emotion: Emotion;
+  emotionIntensity: number;
   setAnimationState: (state: AnimationState) => void;
   setDirection: (dir: Direction) => void;
-  setEmotion: (emotion: Emotion, duration?: number) => void;
+  setEmotion: (emotion: Emotion, duration?: number, options?: EmotionOptions) => void;
   triggerJump: () => void;
   triggerTalk: () => void;
   isGrounded: boolean;
@@ -22,6 +38,7 @@ export function useAnimationState(): AnimationStateManager {
   const [animationState, setAnimationStateInternal] = useState<AnimationState>("idle");
   const [direction, setDirection] = useState<Direction>("right");
   const [emotion, setEmotionInternal] = useState<Emotion>("neutral");
+  const [emotionIntensity, setEmotionIntensity] = useState(1);
   const [isGrounded, setGrounded] = useState(true);
   const stateTimeoutRef = useRef<number | null>(null);
   const emotionTimeoutRef = useRef<number | null>(null);
@@ -47,18 +64,21 @@ export function useAnimationState(): AnimationStateManager {
     }, TALK_DURATION);
   }, [setAnimationState]);
 
-  const setEmotion = useCallback((newEmotion: Emotion, duration?: number) => {
+  const setEmotion = useCallback((newEmotion: Emotion, duration?: number, options?: EmotionOptions) => {
     if (emotionTimeoutRef.current) {
       clearTimeout(emotionTimeoutRef.current);
       emotionTimeoutRef.current = null;
     }
     setEmotionInternal(newEmotion);
+    setEmotionIntensity(options?.intensity ?? 1);
 
-    // Auto-reset to neutral after duration (default from constants)
-    if (newEmotion !== "neutral") {
+    // Blend back to neutral (or the requested emotion) after duration (default from constants)
+    const blendTo = options?.blendTo ?? "neutral";
+    if (newEmotion !== blendTo) {
       const resetDuration = duration ?? EMOTION_RESET_DURATION;
       emotionTimeoutRef.current = window.setTimeout(() => {
-        setEmotionInternal("neutral");
+        setEmotionInternal(blendTo);
+        setEmotionIntensity(1);
       }, resetDuration);
     }
   }, []);
@@ -78,6 +98,7 @@ export function useAnimationState(): AnimationStateManager {
     animationState,
     direction,
     emotion,
+    emotionIntensity,
     setAnimationState,
     setDirection,
     setEmotion,
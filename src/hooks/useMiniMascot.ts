@@ -47,8 +47,12 @@ export function useMiniMascot(): UseMiniMascotReturn {
     const usedSlots = new Set(
       activeMascots.current.map((m) => parseInt(m.windowLabel.split("-").pop() || "0"))
     );
+    // Skip slots taken by helper mascots the agent spawned from the backend
     let slot = 1;
-    while (usedSlots.has(slot) && slot <= MAX_MINI_MASCOTS) {
+    while (
+      slot <= MAX_MINI_MASCOTS &&
+      (usedSlots.has(slot) || (await WebviewWindow.getByLabel(`mini-mascot-${slot}`)))
+    ) {
       slot++;
     }
 
@@ -16,6 +16,12 @@ interface PhysicsState {
   velocityY: number;
 }
 
+export interface PathPoint {
+  x: number;
+  /** null keeps the mascot on the floor */
+  y: number | null;
+}
+
 interface ScreenBounds {
   width: number;
   height: number;
@@ -66,6 +72,12 @@ export function usePhysics({
   const walkDirectionRef = useRef<"left" | "right">("right");
   const walkTargetXRef = useRef<number | null>(null);
   const onReachTargetRef = useRef<(() => void) | null>(null);
+  // Path following (from move_to) glides through points, ignoring gravity
+  const pathRef = useRef<PathPoint[]>([]);
+  const pathSpeedRef = useRef(1);
+  const onPathDoneRef = useRef<(() => void) | null>(null);
+  // Height to stand at after reaching an elevated point (e.g. a title bar)
+  const perchYRef = useRef<number | null>(null);
 
   const updateScreenBounds = useCallback(async () => {
     try {
@@ -113,25 +125,47 @@ export function usePhysics({
     }
   }, [physicsConfig.jumpForce, onGrounded]);
 
+  const clearPath = useCallback(() => {
+    pathRef.current = [];
+    onPathDoneRef.current = null;
+    perchYRef.current = null;
+  }, []);
+
   const startWalking = useCallback((direction: "left" | "right") => {
+    clearPath();
     isWalkingRef.current = true;
     walkDirectionRef.current = direction;
-  }, []);
+  }, [clearPath]);
 
   const stopWalking = useCallback(() => {
+    clearPath();
     isWalkingRef.current = false;
     walkTargetXRef.current = null;
     onReachTargetRef.current = null;
-  }, []);
+  }, [clearPath]);
 
   const walkToX = useCallback((targetX: number, onReach?: () => void) => {
+    clearPath();
     const currentX = stateRef.current.x;
     const direction = targetX > currentX ? "right" : "left";
     walkTargetXRef.current = targetX;
     onReachTargetRef.current = onReach || null;
     isWalkingRef.current = true;
     walkDirectionRef.current = direction;
-  }, []);
+  }, [clearPath]);
+
+  const followPath = useCallback(
+    (points: PathPoint[], speed: number, onDone?: () => void) => {
+      isWalkingRef.current = false;
+      walkTargetXRef.current = null;
+      onReachTargetRef.current = null;
+      perchYRef.current = null;
+      pathRef.current = [...points];
+      pathSpeedRef.current = speed;
+      onPathDoneRef.current = onDone || null;
+    },
+    []
+  );
 
   // Track frames for periodic bounds update
   const frameCountRef = useRef(0);
@@ -146,6 +180,39 @@ export function usePhysics({
 
     const bounds = screenBoundsRef.current;
     const state = stateRef.current;
+    const defaultFloorY = bounds.top + bounds.height - windowHeight - bounds.taskbarHeight;
+
+    // Glide along the move_to path (may cross monitors, so skip collisions)
+    if (pathRef.current.length > 0) {
+      const target = pathRef.current[0];
+      const targetY = target.y ?? defaultFloorY;
+      const dx = target.x - state.x;
+      const dy = targetY - state.y;
+      const distance = Math.hypot(dx, dy);
+      const step = physicsConfig.walkSpeed * pathSpeedRef.current;
+      walkDirectionRef.current = dx >= 0 ? "right" : "left";
+
+      if (distance <= step) {
+        state.x = target.x;
+        state.y = targetY;
+        pathRef.current.shift();
+        if (pathRef.current.length === 0) {
+          perchYRef.current = target.y;
+          const callback = onPathDoneRef.current;
+          onPathDoneRef.current = null;
+          if (callback) callback();
+        }
+      } else {
+        state.x += (dx / distance) * step;
+        state.y += (dy / distance) * step;
+      }
+      state.velocityX = 0;
+      state.velocityY = 0;
+
+      await setPosition(state.x, state.y);
+      onPositionUpdate(state.x, state.y);
+      return;
+    }
 
     // Apply gravity
     state.velocityY += physicsConfig.gravity;
@@ -186,7 +253,7 @@ export function usePhysics({
     state.y += state.velocityY;
 
     // Floor collision (bottom of screen, accounting for monitor position and taskbar)
-    const floorY = bounds.top + bounds.height - windowHeight - bounds.taskbarHeight;
+    const floorY = perchYRef.current ?? defaultFloorY;
     if (state.y >= floorY) {
       state.y = floorY;
       if (state.velocityY > 0) {
@@ -281,6 +348,7 @@ export function usePhysics({
     startWalking,
     stopWalking,
     walkToX,
+    followPath,
     syncPosition,
     updateScreenBounds,
     getState: () => stateRef.current,
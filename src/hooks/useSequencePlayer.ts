@@ -0,0 +1,99 @@
+import { useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
+import type { AnimationState, Direction } from "./useMascotState";
+
+type SequenceStep = {
+  atMs: number;
+  durationMs: number;
+} & (
+  | { action: "jump" | "wave" | "spin" }
+  | { action: "sleep"; duration_ms?: number | null }
+  | { action: "look_at"; x: number; y: number }
+  | { action: "wait"; duration_ms: number }
+);
+
+interface SequenceEvent {
+  name: string | null;
+  steps: SequenceStep[];
+  totalMs: number;
+}
+
+export interface SequenceHandlers {
+  jump: () => void;
+  setAnimationState: (state: AnimationState) => void;
+  setDirection: (direction: Direction) => void;
+  getCenterX: () => number;
+  /** Called when a sequence starts, e.g. to stop walking */
+  onStart: () => void;
+}
+
+/**
+ * Plays timeline events from the `play_sequence` tool.
+ * A new sequence replaces the one in progress; `mascot-sequence-cancel` stops it.
+ */
+export function useSequencePlayer(handlers: SequenceHandlers) {
+  const handlersRef = useRef(handlers);
+  handlersRef.current = handlers;
+  const timeoutsRef = useRef<number[]>([]);
+
+  useEffect(() => {
+    const clear = () => {
+      const hadSteps = timeoutsRef.current.length > 0;
+      timeoutsRef.current.forEach((id) => window.clearTimeout(id));
+      timeoutsRef.current = [];
+      return hadSteps;
+    };
+
+    const runStep = (step: SequenceStep) => {
+      const h = handlersRef.current;
+      switch (step.action) {
+        case "jump":
+          h.jump();
+          break;
+        case "wave":
+          h.setAnimationState("waving");
+          break;
+        case "spin":
+          h.setAnimationState("spinning");
+          break;
+        case "sleep":
+          h.setAnimationState("sleeping");
+          break;
+        case "look_at":
+          h.setDirection(step.x < h.getCenterX() ? "left" : "right");
+          break;
+        case "wait":
+          h.setAnimationState("idle");
+          break;
+      }
+    };
+
+    const schedule = (callback: () => void, delay: number) => {
+      timeoutsRef.current.push(window.setTimeout(callback, delay));
+    };
+
+    const unlistenPlay = listen<SequenceEvent>("mascot-sequence", (event) => {
+      clear();
+      handlersRef.current.onStart();
+      for (const step of event.payload.steps) {
+        schedule(() => runStep(step), step.atMs);
+      }
+      schedule(() => {
+        timeoutsRef.current = [];
+        handlersRef.current.setAnimationState("idle");
+      }, event.payload.totalMs);
+    });
+
+    const unlistenCancel = listen("mascot-sequence-cancel", () => {
+      if (clear()) {
+        handlersRef.current.setAnimationState("idle");
+      }
+    });
+
+    return () => {
+      clear();
+      unlistenPlay.then((fn) => fn());
+      unlistenCancel.then((fn) => fn());
+    };
+  }, []);
+}
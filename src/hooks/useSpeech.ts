@@ -0,0 +1,63 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { commands, type TtsAudioEvent } from "../bindings";
+import { getLanguage } from "../services/settingsStorage";
+
+/**
+ * Plays text-to-speech audio chunks from the backend in order.
+ * Chunks of a newer utterance replace the one in progress; `tts-stop` stops playback.
+ */
+export function useSpeech() {
+  useEffect(() => {
+    // The voice follows the selected UI language
+    commands.setLanguage(getLanguage());
+
+    const audio = new Audio();
+    let utterance = 0;
+    let queue: string[] = [];
+    let playing = false;
+
+    const playNext = () => {
+      const next = queue.shift();
+      if (!next) {
+        playing = false;
+        return;
+      }
+      playing = true;
+      audio.src = next;
+      audio.play().catch((err) => {
+        console.warn("[useSpeech] Failed to play audio:", err);
+        playNext();
+      });
+    };
+
+    const stop = () => {
+      queue = [];
+      playing = false;
+      audio.pause();
+      audio.currentTime = 0;
+    };
+
+    audio.addEventListener("ended", playNext);
+
+    const unlistenAudio = listen<TtsAudioEvent>("tts-audio", (event) => {
+      const chunk = event.payload;
+      if (chunk.utterance < utterance) return;
+      if (chunk.utterance > utterance) {
+        stop();
+        utterance = chunk.utterance;
+      }
+      queue.push(chunk.audio);
+      if (!playing) playNext();
+    });
+
+    const unlistenStop = listen("tts-stop", stop);
+
+    return () => {
+      stop();
+      audio.removeEventListener("ended", playNext);
+      unlistenAudio.then((fn) => fn());
+      unlistenStop.then((fn) => fn());
+    };
+  }, []);
+}
@@ -12,7 +12,7 @@ export type SupikiSoundTrigger = "click" | "emotion";
 
 interface UseSupikiSoundsReturn {
   playClickSound: () => void;
-  playEmotionSound: (emotion: Emotion) => void;
+  playEmotionSound: (emotion: Emotion, intensity?: number) => void;
   playCompletionSound: () => void;
 }
 
@@ -32,7 +32,7 @@ export function useSupikiSounds(): UseSupikiSoundsReturn {
     };
   }, []);
 
-  const playSound = useCallback((soundUrl: string) => {
+  const playSound = useCallback((soundUrl: string, volume = 1) => {
     if (!audioRef.current) return;
 
     // Stop any currently playing sound
@@ -41,6 +41,7 @@ export function useSupikiSounds(): UseSupikiSoundsReturn {
 
     // Play new sound
     audioRef.current.src = soundUrl;
+    audioRef.current.volume = Math.min(Math.max(volume, 0), 1);
     audioRef.current.play().catch((err) => {
       console.warn("Failed to play sound:", err);
     });
@@ -53,8 +54,8 @@ export function useSupikiSounds(): UseSupikiSoundsReturn {
     playSound(randomSound);
   }, [playSound]);
 
-  // Play sound based on emotion
-  const playEmotionSound = useCallback((emotion: Emotion) => {
+  // Play sound based on emotion, quieter for weaker expressions
+  const playEmotionSound = useCallback((emotion: Emotion, intensity = 1) => {
     // Only play if emotion changed
     if (emotion === lastEmotionRef.current) return;
     lastEmotionRef.current = emotion;
@@ -66,11 +67,11 @@ export function useSupikiSounds(): UseSupikiSoundsReturn {
     switch (emotion) {
       case "happy":
       case "excited":
-        playSound(goodSound);
+        playSound(goodSound, intensity);
         break;
       case "sad":
       case "confused": // treating confused as "angry/frustrated"
-        playSound(dontpushSound);
+        playSound(dontpushSound, intensity);
         break;
       default:
         // No sound for neutral, thinking, surprised, curious
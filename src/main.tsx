@@ -8,7 +8,9 @@ import ContextMenuWindow from "./components/windows/ContextMenuWindow";
 import ChatHistoryListWindow from "./components/windows/ChatHistoryListWindow";
 import SettingsWindow from "./components/windows/SettingsWindow";
 import CwdWindow from "./components/windows/CwdWindow";
+import ApprovalWindow from "./components/windows/ApprovalWindow";
 import { commands } from "./bindings";
+import { EMOTIONS } from "./emotion";
 import "./i18n"; // Initialize i18n
 import "./styles/index.css";
 
@@ -26,8 +28,11 @@ const isContextMenu = urlParams.get("contextmenu") === "true";
 const isHistoryList = urlParams.get("historylist") === "true";
 const isSettings = urlParams.get("settings") === "true";
 const isCwd = urlParams.get("cwd") === "true";
+const approvalId = urlParams.get("approval");
 const isMiniMascot = urlParams.get("miniMascot") === "true";
 const miniMascotId = urlParams.get("id");
+// Helpers spawned by the agent start with the emotion it chose
+const miniMascotEmotion = EMOTIONS.find((e) => e === urlParams.get("emotion")) ?? "neutral";
 
 // Check mascot type from environment variable (for dev mode)
 const envSupikiMode = import.meta.env.VITE_MASCOT_TYPE === "supiki";
@@ -55,8 +60,9 @@ function RootComponent() {
   if (isHistoryList) return <ChatHistoryListWindow />;
   if (isSettings) return <SettingsWindow />;
   if (isCwd) return <CwdWindow />;
+  if (approvalId) return <ApprovalWindow id={approvalId} />;
   if (isChatWindow) return <ChatWindow />;
-  if (isMiniMascot) return <MiniSupikiApp id={miniMascotId || "1"} />;
+  if (isMiniMascot) return <MiniSupikiApp id={miniMascotId || "1"} emotion={miniMascotEmotion} />;
 
   // Wait for supiki mode check before rendering mascot
   if (!loaded) return null;
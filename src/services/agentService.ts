@@ -1,11 +1,11 @@
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type { AgentQueryCallbacks, Emotion, AgentQuestionEvent, AttachedImage, ExitPlanModeEvent } from "./agentTypes";
-import { EMOTIONS } from "../emotion";
-import { commands } from "../bindings";
+import { EMOTIONS, type EmotionChange } from "../emotion";
+import { commands, type EmotionEvent } from "../bindings";
 import { getLanguage, getBackendMode } from "./settingsStorage";
 
 // Emotion update callback type
-type EmotionCallback = (emotion: Emotion, duration: number) => void;
+type EmotionCallback = (change: EmotionChange) => void;
 
 // Question callback type
 type QuestionCallback = (event: AgentQuestionEvent) => void;
@@ -51,17 +51,13 @@ export class AgentService {
   private async setupEmotionListener(): Promise<void> {
     if (this.emotionListener) return;
 
-    this.emotionListener = await listen<{
-      type: string;
-      emotion: string;
-      duration: number;
-    }>("agent-emotion", (event) => {
-      const { emotion, duration } = event.payload;
-      console.log("[AgentService] Emotion event:", emotion, duration);
+    this.emotionListener = await listen<EmotionEvent>("agent-emotion", (event) => {
+      const { emotion, duration, intensity, blendTo } = event.payload;
+      console.log("[AgentService] Emotion event:", emotion, duration, intensity, blendTo);
 
       if (EMOTIONS.includes(emotion as Emotion)) {
         for (const callback of this.emotionCallbacks) {
-          callback(emotion as Emotion, duration);
+          callback({ emotion: emotion as Emotion, duration, intensity, blendTo: blendTo as Emotion | null });
         }
       }
     });
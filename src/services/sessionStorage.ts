@@ -1,5 +1,6 @@
 import type { ChatSession, AgentChatMessage } from "./agentTypes";
 import { generateId } from "../utils/id";
+import { commands } from "../bindings";
 
 const SESSIONS_KEY = "clawd-sessions";
 const CURRENT_SESSION_KEY = "clawd-current-session";
@@ -35,6 +36,22 @@ class SessionStorage {
     } catch (e) {
       console.error("Failed to save sessions:", e);
     }
+    this.syncToBackend();
+  }
+
+  /**
+   * Share the session index with the backend so the MCP server can expose it
+   */
+  private syncToBackend(): void {
+    const summaries = this.sessions.map((s) => ({
+      id: s.id,
+      title: s.title,
+      updatedAt: s.updatedAt,
+      messageCount: s.messageCount,
+    }));
+    commands.syncChatSessions(summaries).catch((e) => {
+      console.error("Failed to sync sessions:", e);
+    });
   }
 
   getSessions(): ChatSession[] {
@@ -117,6 +134,7 @@ class SessionStorage {
     localStorage.removeItem(CURRENT_SESSION_KEY);
     // Also clear legacy storage
     localStorage.removeItem("clawd-chat-history");
+    this.syncToBackend();
   }
 }
 